@@ -1,11 +1,16 @@
+use std::process::ExitCode;
+
 use colored::Colorize;
 use holochain_scaffolding_cli::cli::HcScaffold;
 use structopt::StructOpt;
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    if let Err(e) = HcScaffold::from_args().run().await {
-        eprintln!("{}", e.to_string().red());
+async fn main() -> ExitCode {
+    match HcScaffold::from_args().run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e.to_string().red());
+            ExitCode::FAILURE
+        }
     }
-    Ok(())
 }
@@ -7,14 +7,21 @@ use crate::scaffold::example::ExampleType;
 use crate::scaffold::web_app::template_type::TemplateType;
 
 use colored::Colorize;
-use std::{path::Path, str::FromStr};
+use std::str::FromStr;
 use structopt::StructOpt;
 
+mod apply;
+mod bridge_call;
+mod capability;
 mod collection;
 mod dna;
 mod entry_type;
 mod example;
+mod inspect;
 mod link_type;
+mod remove;
+mod rename;
+mod signal;
 mod template;
 mod web_app;
 mod zome;
@@ -27,6 +34,16 @@ pub struct HcScaffold {
     /// Or a path to a custom template
     template: Option<TemplateType>,
 
+    #[structopt(long)]
+    /// Print the files that would be created, modified or deleted, with a diff of their contents,
+    /// without writing anything to disk
+    dry_run: bool,
+
+    #[structopt(long)]
+    /// Never prompt: fail with an error naming the missing argument instead.
+    /// Also enabled when the CI environment variable is set to "true"
+    no_interactive: bool,
+
     #[structopt(subcommand)]
     command: HcScaffoldCommand,
 }
@@ -42,63 +59,108 @@ pub enum HcScaffoldCommand {
     EntryType(entry_type::EntryType),
     LinkType(link_type::LinkType),
     Collection(collection::Collection),
+    Signal(signal::Signal),
+    Capability(capability::Capability),
+    BridgeCall(bridge_call::BridgeCall),
     Example(example::Example),
+    Apply(apply::Apply),
+    Inspect(inspect::Inspect),
+    Remove(remove::Remove),
+    Rename(rename::Rename),
 }
 
 impl HcScaffold {
     pub async fn run(self) -> anyhow::Result<()> {
-        let current_dir = std::env::current_dir()?;
-        let scaffold_config = ScaffoldConfig::from_package_json_path(&current_dir)?;
-        let template_type = self.get_template_type(&current_dir, scaffold_config.as_ref())?;
+        let dry_run = self.dry_run;
+        let no_interactive = self.no_interactive || is_ci();
+        let template = self.template.as_ref();
+        let app_template =
+            || get_template_type(template, || template_type_of_current_dir(no_interactive));
 
+        // Inspecting, removing, renaming and scaffolding capabilities and bridge calls don't render any templates,
+        // so they don't need one
         match self.command {
-            HcScaffoldCommand::WebApp(web_app) => web_app.run(&template_type).await,
-            HcScaffoldCommand::Template(template) => template.run(&template_type),
-            HcScaffoldCommand::Dna(dna) => dna.run(&template_type),
-            HcScaffoldCommand::Zome(zome) => zome.run(&template_type),
-            HcScaffoldCommand::EntryType(entry_type) => entry_type.run(&template_type),
-            HcScaffoldCommand::LinkType(link_type) => link_type.run(&template_type),
-            HcScaffoldCommand::Collection(collection) => collection.run(&template_type),
-            HcScaffoldCommand::Example(example) => example.run(&template_type).await,
-        }
-    }
-
-    fn get_template_type(
-        &self,
-        current_dir: &Path,
-        scaffold_config: Option<&ScaffoldConfig>,
-    ) -> Result<TemplateType, ScaffoldError> {
-        // Read template_type config if no `--template` flag is provided and use it or
-        // ensure that if a `--template` is explicity provided, it matches the original
-        // template the app was scaffolded with
-        let template = match (scaffold_config, &self.template) {
-            (Some(config), Some(template)) if config.template != *template => {
-                return Err(ScaffoldError::InvalidArguments(format!(
-                    "The value {} passed with `--template` does not match the template the web-app was scaffolded with: {}",
-                    template.name().italic(),
-                    config.template.name().italic(),
-                )));
+            HcScaffoldCommand::WebApp(web_app) => {
+                let template_type =
+                    get_template_type(template, || TemplateType::choose(no_interactive))?;
+                web_app.run(&template_type, dry_run, no_interactive).await
             }
-            (Some(config), _) => Some(&config.template),
-            (_, t) => t.as_ref(),
-        };
-
-        match template {
-            Some(template) => Ok(template.clone()),
-            None => {
-                let template_type = match &self.command {
-                    HcScaffoldCommand::WebApp { .. } => TemplateType::choose()?,
-                    HcScaffoldCommand::Example(example::Example { ref example, .. }) => {
-                        match example {
-                            Some(ExampleType::HelloWorld) => TemplateType::Vanilla,
-                            Some(ExampleType::Forum) => TemplateType::choose_non_vanilla()?,
-                            None => TemplateType::choose_non_headless()?,
-                        }
+            HcScaffoldCommand::Template(t) => t.run(&app_template()?, dry_run, no_interactive),
+            HcScaffoldCommand::Dna(dna) => dna.run(&app_template()?, dry_run, no_interactive),
+            HcScaffoldCommand::Zome(zome) => zome.run(&app_template()?, dry_run, no_interactive),
+            HcScaffoldCommand::EntryType(entry_type) => {
+                entry_type.run(&app_template()?, dry_run, no_interactive)
+            }
+            HcScaffoldCommand::LinkType(link_type) => {
+                link_type.run(&app_template()?, dry_run, no_interactive)
+            }
+            HcScaffoldCommand::Collection(collection) => {
+                collection.run(&app_template()?, dry_run, no_interactive)
+            }
+            HcScaffoldCommand::Signal(signal) => {
+                signal.run(&app_template()?, dry_run, no_interactive)
+            }
+            HcScaffoldCommand::Capability(capability) => capability.run(dry_run, no_interactive),
+            HcScaffoldCommand::BridgeCall(bridge_call) => bridge_call.run(dry_run, no_interactive),
+            HcScaffoldCommand::Example(example) => {
+                let template_type = match example.example {
+                    Some(ExampleType::HelloWorld) => {
+                        get_template_type(template, || Ok(TemplateType::Vanilla))?
                     }
-                    _ => TemplateType::try_from(&load_directory_into_memory(current_dir)?)?,
+                    Some(ExampleType::Forum) => get_template_type(template, || {
+                        TemplateType::choose_non_vanilla(no_interactive)
+                    })?,
+                    None => get_template_type(template, || {
+                        TemplateType::choose_non_headless(no_interactive)
+                    })?,
                 };
-                Ok(template_type)
+                example.run(&template_type, dry_run, no_interactive).await
             }
+            HcScaffoldCommand::Apply(apply) => apply.run(&app_template()?, dry_run, no_interactive),
+            HcScaffoldCommand::Inspect(inspect) => inspect.run(),
+            HcScaffoldCommand::Remove(remove) => remove.run(dry_run, no_interactive),
+            HcScaffoldCommand::Rename(rename) => rename.run(dry_run, no_interactive),
         }
     }
 }
+
+/// Gets the template to render the command with, calling `choose` if neither the `--template` flag
+/// nor the config of the web-app in the current directory set one
+fn get_template_type(
+    template: Option<&TemplateType>,
+    choose: impl FnOnce() -> Result<TemplateType, ScaffoldError>,
+) -> Result<TemplateType, ScaffoldError> {
+    let current_dir = std::env::current_dir()?;
+    let scaffold_config = ScaffoldConfig::from_package_json_path(&current_dir)?;
+
+    // Read template_type config if no `--template` flag is provided and use it or
+    // ensure that if a `--template` is explicity provided, it matches the original
+    // template the app was scaffolded with
+    let template = match (scaffold_config.as_ref(), template) {
+        (Some(config), Some(template)) if config.template != *template => {
+            return Err(ScaffoldError::InvalidArguments(format!(
+                "The value {} passed with `--template` does not match the template the web-app was scaffolded with: {}",
+                template.name().italic(),
+                config.template.name().italic(),
+            )));
+        }
+        (Some(config), _) => Some(&config.template),
+        (_, t) => t,
+    };
+
+    match template {
+        Some(template) => Ok(template.clone()),
+        None => choose(),
+    }
+}
+
+/// CI runners set `CI=true`, where there is nobody to answer the prompts
+fn is_ci() -> bool {
+    std::env::var("CI").is_ok_and(|ci| matches!(ci.to_lowercase().as_str(), "true" | "1"))
+}
+
+/// Infers the template from the UI of the app in the current directory
+fn template_type_of_current_dir(no_interactive: bool) -> Result<TemplateType, ScaffoldError> {
+    let current_dir = std::env::current_dir()?;
+    TemplateType::get_or_choose(&load_directory_into_memory(&current_dir)?, no_interactive)
+}
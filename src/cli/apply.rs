@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use colored::Colorize;
+use structopt::StructOpt;
+
+use crate::{
+    file_tree::{build_file_tree, load_directory_into_memory},
+    scaffold::{
+        app::cargo::exec_metadata,
+        happ_spec::{apply_happ_spec, HappSpec},
+        web_app::template_type::TemplateType,
+    },
+    utils::run_cargo_fmt_if_available,
+};
+
+#[derive(Debug, StructOpt)]
+/// Scaffold the DNAs, zomes, entry types, link types and collections described in a hApp spec file
+pub struct Apply {
+    #[structopt(parse(from_os_str))]
+    /// Path to the hApp spec file, in YAML or TOML format
+    pub spec: PathBuf,
+
+    #[structopt(long)]
+    /// Skips UI generation for everything in the spec.
+    pub no_ui: bool,
+
+    #[structopt(long)]
+    /// Skips test generation for everything in the spec.
+    pub no_spec: bool,
+}
+
+impl Apply {
+    pub fn run(
+        self,
+        template_type: &TemplateType,
+        dry_run: bool,
+        no_interactive: bool,
+    ) -> anyhow::Result<()> {
+        let content = std::fs::read_to_string(&self.spec)?;
+        let spec = HappSpec::from_file_content(&self.spec, &content)?;
+
+        let current_dir = std::env::current_dir()?;
+        let file_tree = load_directory_into_memory(&current_dir)?;
+
+        let file_tree = apply_happ_spec(
+            file_tree,
+            &template_type.file_tree()?,
+            &spec,
+            self.no_ui,
+            self.no_spec,
+            no_interactive,
+        )?;
+
+        let f = file_tree.clone();
+        build_file_tree(file_tree, ".", dry_run)?;
+
+        if let Err(e) = run_cargo_fmt_if_available(dry_run) {
+            println!(
+                "{}: {}",
+                "rustfmt exec failed: ".yellow(),
+                e.to_string().yellow()
+            );
+        }
+
+        // Execute cargo metadata to set up the cargo workspace in case the spec scaffolded the first zome crates
+        if !dry_run {
+            exec_metadata(&f)?;
+        }
+
+        println!(
+            "\nhApp spec {} applied!",
+            self.spec.to_string_lossy().italic()
+        );
+
+        Ok(())
+    }
+}
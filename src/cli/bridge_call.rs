@@ -0,0 +1,83 @@
+use colored::Colorize;
+use structopt::StructOpt;
+
+use crate::{
+    file_tree::{build_file_tree, load_directory_into_memory},
+    scaffold::{
+        bridge_call::{choose_function, scaffold_bridge_call},
+        dna::DnaFileTree,
+        zome::ZomeFileTree,
+    },
+    utils::run_cargo_fmt_if_available,
+};
+
+#[derive(Debug, StructOpt)]
+/// Scaffold a function that calls an extern function of another coordinator zome of the same cell,
+/// with the input and output types of that function
+pub struct BridgeCall {
+    #[structopt(long)]
+    /// Name of the dna in which both coordinator zomes are
+    pub dna: Option<String>,
+
+    #[structopt(long)]
+    /// Name of the coordinator zome that makes the call
+    pub from_zome: Option<String>,
+
+    #[structopt(long)]
+    /// Name of the coordinator zome whose function is called
+    pub to_zome: Option<String>,
+
+    #[structopt(long = "fn")]
+    /// Name of the extern function of the "to" zome that is called
+    pub fn_name: Option<String>,
+}
+
+impl BridgeCall {
+    pub fn run(self, dry_run: bool, no_interactive: bool) -> anyhow::Result<()> {
+        let current_dir = std::env::current_dir()?;
+        let file_tree = load_directory_into_memory(&current_dir)?;
+
+        let dna_file_tree =
+            DnaFileTree::get_or_choose(file_tree, self.dna.as_deref(), no_interactive)?;
+        let from_zome_file_tree = ZomeFileTree::get_or_choose_coordinator(
+            dna_file_tree.clone(),
+            self.from_zome.as_deref(),
+            no_interactive,
+        )?;
+        let to_zome_file_tree = ZomeFileTree::get_or_choose_coordinator(
+            dna_file_tree,
+            self.to_zome.as_deref(),
+            no_interactive,
+        )?;
+
+        let fn_name = match self.fn_name {
+            Some(fn_name) => fn_name,
+            None => choose_function(&to_zome_file_tree, no_interactive)?,
+        };
+        let to_zome_name = to_zome_file_tree.zome_manifest.name.0.to_string();
+
+        let file_tree = scaffold_bridge_call(
+            from_zome_file_tree,
+            &to_zome_file_tree.zome_manifest,
+            &fn_name,
+        )?;
+
+        build_file_tree(file_tree, ".", dry_run)?;
+
+        if let Err(e) = run_cargo_fmt_if_available(dry_run) {
+            println!(
+                "{}: {}",
+                "rustfmt exec failed: ".yellow(),
+                e.to_string().yellow()
+            );
+        }
+
+        println!(
+            "\nBridge call to {} in zome {} scaffolded!",
+            fn_name.italic(),
+            to_zome_name.italic()
+        );
+
+        Ok(())
+    }
+}
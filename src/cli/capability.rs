@@ -0,0 +1,101 @@
+use std::str::FromStr;
+
+use colored::Colorize;
+use convert_case::Case;
+use structopt::StructOpt;
+
+use crate::{
+    file_tree::{build_file_tree, load_directory_into_memory},
+    scaffold::{
+        capability::{choose_functions, scaffold_capability, CapabilityAccess},
+        dna::DnaFileTree,
+        zome::ZomeFileTree,
+    },
+    utils::{check_case, check_interactive, input_with_case, run_cargo_fmt_if_available},
+};
+
+#[derive(Debug, StructOpt)]
+/// Scaffold a capability grant that lets other agents call functions of a coordinator zome,
+/// and the functions that call them in the cells of other agents
+pub struct Capability {
+    #[structopt(long)]
+    /// Name of the dna in which you want to scaffold the capability
+    pub dna: Option<String>,
+
+    #[structopt(long)]
+    /// Name of the coordinator zome in which you want to scaffold the capability
+    pub zome: Option<String>,
+
+    /// Name of the capability being scaffolded, in snake_case, used as the tag of its grants and claims
+    pub name: Option<String>,
+
+    #[structopt(long, parse(try_from_str = CapabilityAccess::from_str))]
+    /// Which agents can call the granted functions: "unrestricted", "transferable" or "assigned"
+    pub access: Option<CapabilityAccess>,
+
+    #[structopt(long, value_delimiter = ",")]
+    /// The extern functions of the zome that the capability grants, eg. "get_post,get_all_posts"
+    pub functions: Vec<String>,
+}
+
+impl Capability {
+    pub fn run(self, dry_run: bool, no_interactive: bool) -> anyhow::Result<()> {
+        let current_dir = std::env::current_dir()?;
+        let file_tree = load_directory_into_memory(&current_dir)?;
+        let name = match self.name {
+            Some(n) => {
+                check_case(&n, "capability name", Case::Snake)?;
+                n
+            }
+            None => {
+                check_interactive("the `<name>` argument", no_interactive)?;
+                input_with_case(
+                    "Capability name (snake_case, eg. \"read_posts\"):",
+                    Case::Snake,
+                    no_interactive,
+                )?
+            }
+        };
+
+        let dna_file_tree =
+            DnaFileTree::get_or_choose(file_tree, self.dna.as_deref(), no_interactive)?;
+        let zome_file_tree = ZomeFileTree::get_or_choose_coordinator(
+            dna_file_tree,
+            self.zome.as_deref(),
+            no_interactive,
+        )?;
+
+        let functions = match self.functions.is_empty() {
+            true => choose_functions(&zome_file_tree, no_interactive)?,
+            false => self.functions,
+        };
+        let access = match self.access {
+            Some(access) => access,
+            None => CapabilityAccess::choose(no_interactive)?,
+        };
+
+        let file_tree = scaffold_capability(zome_file_tree, &name, access, &functions)?;
+
+        build_file_tree(file_tree, ".", dry_run)?;
+
+        if let Err(e) = run_cargo_fmt_if_available(dry_run) {
+            println!(
+                "{}: {}",
+                "rustfmt exec failed: ".yellow(),
+                e.to_string().yellow()
+            );
+        }
+
+        println!("\nCapability {} scaffolded!", name.italic());
+
+        if access != CapabilityAccess::Unrestricted {
+            println!(
+                r#"
+Call "create_{name}_grant" to create a grant, and share its secret with the agents that should be able to call its functions.
+They need to call "create_{name}_claim" with the secret before calling the functions of the capability."#
+            );
+        }
+
+        Ok(())
+    }
+}
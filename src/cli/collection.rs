@@ -14,7 +14,7 @@ use crate::{
         zome::ZomeFileTree,
     },
     templates::ScaffoldedTemplate,
-    utils::{check_case, input_with_case, run_cargo_fmt_if_available},
+    utils::{check_case, check_interactive, input_with_case, run_cargo_fmt_if_available},
 };
 
 #[derive(Debug, StructOpt)]
@@ -28,15 +28,31 @@ pub struct Collection {
     /// Name of the integrity zome in which you want to scaffold the link type
     pub zome: Option<String>,
 
-    /// Collection type: "global" or "by-author"
+    /// Collection type: "global", "by-author", "by-path" or "time-indexed"
     pub collection_type: Option<CollectionType>,
 
     /// Collection name, just to differentiate it from other collections
     pub collection_name: Option<String>,
 
     #[structopt(parse(try_from_str = EntryTypeReference::from_str))]
-    /// Entry type that is going to be added to the collection
-    pub entry_type: Option<EntryTypeReference>,
+    /// Entry types that are going to be added to the collection, "global" and "by-author" collections can have more than one
+    pub entry_types: Vec<EntryTypeReference>,
+
+    #[structopt(long)]
+    /// Field of the entry type whose value is the key of a "by-path" collection, it must be a String field
+    pub key_field: Option<String>,
+
+    #[structopt(long)]
+    /// Makes the getter of a "global" or "by-author" collection return its links one page at a time, newest first
+    pub paginated: bool,
+
+    #[structopt(long)]
+    /// String, bool or enum field of the entry type that entries must have a given value in to be in the collection
+    pub filter_field: Option<String>,
+
+    #[structopt(long)]
+    /// Value that the filter field must have, eg. "published", "true" or the variant of an enum
+    pub filter_value: Option<String>,
 
     #[structopt(long)]
     /// Skips UI generation for this collection.
@@ -48,23 +64,36 @@ pub struct Collection {
 }
 
 impl Collection {
-    pub fn run(self, template_type: &TemplateType) -> anyhow::Result<()> {
+    pub fn run(
+        self,
+        template_type: &TemplateType,
+        dry_run: bool,
+        no_interactive: bool,
+    ) -> anyhow::Result<()> {
         let current_dir = std::env::current_dir()?;
         let file_tree = load_directory_into_memory(&current_dir)?;
 
-        let dna_file_tree = DnaFileTree::get_or_choose(file_tree, self.dna.as_deref())?;
-        let zome_file_tree =
-            ZomeFileTree::get_or_choose_integrity(dna_file_tree, self.zome.as_deref())?;
+        let dna_file_tree =
+            DnaFileTree::get_or_choose(file_tree, self.dna.as_deref(), no_interactive)?;
+        let zome_file_tree = ZomeFileTree::get_or_choose_integrity(
+            dna_file_tree,
+            self.zome.as_deref(),
+            no_interactive,
+        )?;
 
         let name = match self.collection_name {
             Some(n) => {
                 check_case(&n, "collection name", Case::Snake)?;
                 n
             }
-            None => input_with_case(
-                "Collection name (snake_case, eg. \"all_posts\"):",
-                Case::Snake,
-            )?,
+            None => {
+                check_interactive("the `<collection-name>` argument", no_interactive)?;
+                input_with_case(
+                    "Collection name (snake_case, eg. \"all_posts\"):",
+                    Case::Snake,
+                    no_interactive,
+                )?
+            }
         };
 
         let ScaffoldedTemplate {
@@ -75,14 +104,19 @@ impl Collection {
             &template_type.file_tree()?,
             &name,
             self.collection_type,
-            self.entry_type,
+            self.entry_types,
+            self.key_field,
+            self.paginated,
+            self.filter_field,
+            self.filter_value,
             self.no_ui,
             self.no_spec,
+            no_interactive,
         )?;
 
-        build_file_tree(file_tree, ".")?;
+        build_file_tree(file_tree, ".", dry_run)?;
 
-        if let Err(e) = run_cargo_fmt_if_available() {
+        if let Err(e) = run_cargo_fmt_if_available(dry_run) {
             println!(
                 "{}: {}",
                 "rustfmt exec failed: ".yellow(),
@@ -6,7 +6,7 @@ use crate::{
     file_tree::{build_file_tree, load_directory_into_memory},
     scaffold::{app::AppFileTree, dna::scaffold_dna, web_app::template_type::TemplateType},
     templates::ScaffoldedTemplate,
-    utils::{check_case, input_with_case},
+    utils::{check_case, check_interactive, input_with_case},
 };
 
 #[derive(Debug, StructOpt)]
@@ -21,7 +21,12 @@ pub struct Dna {
 }
 
 impl Dna {
-    pub fn run(self, template_type: &TemplateType) -> anyhow::Result<()> {
+    pub fn run(
+        self,
+        template_type: &TemplateType,
+        dry_run: bool,
+        no_interactive: bool,
+    ) -> anyhow::Result<()> {
         let current_dir = std::env::current_dir()?;
         let file_tree = load_directory_into_memory(&current_dir)?;
 
@@ -30,17 +35,26 @@ impl Dna {
                 check_case(&n, "dna name", Case::Snake)?;
                 n
             }
-            None => input_with_case("DNA name (snake_case):", Case::Snake)?,
+            None => {
+                check_interactive("the `<name>` argument", no_interactive)?;
+                input_with_case("DNA name (snake_case):", Case::Snake, no_interactive)?
+            }
         };
 
-        let app_file_tree = AppFileTree::get_or_choose(file_tree, self.app.as_deref())?;
+        let app_file_tree =
+            AppFileTree::get_or_choose(file_tree, self.app.as_deref(), no_interactive)?;
 
         let ScaffoldedTemplate {
             file_tree,
             next_instructions,
-        } = scaffold_dna(app_file_tree, &template_type.file_tree()?, &name)?;
+        } = scaffold_dna(
+            app_file_tree,
+            &template_type.file_tree()?,
+            &name,
+            no_interactive,
+        )?;
 
-        build_file_tree(file_tree, ".")?;
+        build_file_tree(file_tree, ".", dry_run)?;
 
         println!("\nDNA {} scaffolded!", name.italic());
 
@@ -5,20 +5,28 @@ use convert_case::Case;
 use structopt::StructOpt;
 
 use crate::{
+    error::ScaffoldError,
     file_tree::{build_file_tree, load_directory_into_memory},
     scaffold::{
         dna::DnaFileTree,
-        entry_type::{crud::Crud, definitions::FieldDefinition, scaffold_entry_type},
+        entry_type::{
+            crud::{Crud, Policy},
+            definitions::{EntryVisibility, FieldDefinition},
+            scaffold_entry_type, scaffold_entry_type_fields, EntryTypeOptions,
+        },
         web_app::template_type::TemplateType,
         zome::ZomeFileTree,
     },
     templates::ScaffoldedTemplate,
-    utils::{check_case, input_with_case, run_cargo_fmt_if_available},
+    utils::{check_case, check_interactive, input_with_case, run_cargo_fmt_if_available},
 };
 
 #[derive(Debug, StructOpt)]
 /// Scaffold an entry type and CRUD functions into an existing zome
 pub struct EntryType {
+    #[structopt(subcommand)]
+    pub command: Option<EntryTypeCommand>,
+
     #[structopt(long)]
     /// Name of the dna in which you want to scaffold the entry type
     pub dna: Option<String>,
@@ -45,11 +53,40 @@ pub struct EntryType {
     /// Only applies if update is selected in the "crud" argument
     pub link_from_original_to_each_update: Option<bool>,
 
-    #[structopt(long, value_delimiter = ",", parse(try_from_str = FieldDefinition::from_str))]
+    #[structopt(long, parse(try_from_str = Policy::from_str))]
+    /// Who can update the entries: "anyone" (default) or "author", the author of the original entry
+    /// Only applies if update is selected in the "crud" argument
+    pub update_policy: Option<Policy>,
+
+    #[structopt(long, parse(try_from_str = Policy::from_str))]
+    /// Who can delete the entries: "anyone" (default) or "author", the author of the original entry
+    /// Only applies if delete is selected in the "crud" argument
+    pub delete_policy: Option<Policy>,
+
+    #[structopt(long, value_delimiter = ",")]
+    /// The fields whose values can't change when an entry is updated, eg. "title,slug"
+    /// Only applies if update is selected in the "crud" argument
+    pub immutable_fields: Vec<String>,
+
+    #[structopt(long, parse(try_from_str = EntryVisibility::from_str))]
+    /// Whether the entries are "public" (default), published to the DHT, or "private", only kept in the source chain
+    /// of their author, who is the only agent that can read them
+    pub visibility: Option<EntryVisibility>,
+
+    #[structopt(long)]
+    /// The number of validations required before the entries stop being published, holochain's default if not set
+    pub required_validations: Option<u8>,
+
+    #[structopt(long)]
+    /// Custom name for the entry definition, set in the "#[entry_type(name = ...)]" attribute of the entry type
+    pub entry_def_name: Option<String>,
+
+    #[structopt(long)]
     /// The fields that the entry type struct should contain
-    /// Syntax: <FIELD_NAME>:<FIELD_TYPE>:<WIDGET>:<LINKED_FROM> , (widget and linked_from are optional)
-    /// Eg. "title:String:TextField" , "posts_hashes:Vec\<ActionHash\>::Post"
-    pub fields: Option<Vec<FieldDefinition>>,
+    /// Syntax: <FIELD_NAME>:<FIELD_TYPE>:<WIDGET>:<LINKED_FROM>;<CONSTRAINT>... , (widget, linked_from and constraints are optional)
+    /// Constraints: min_len=<n>, max_len=<n>, non_empty, regex=<pattern>, range=<min>..<max>, one_of=<a>|<b>
+    /// Eg. "title:String:TextField;min_len=3" , "posts_hashes:Vec\<ActionHash\>::Post" , "address:Address{street:String:TextField,zip:String}:Form"
+    pub fields: Option<Vec<String>>,
 
     #[structopt(long)]
     /// Skips UI generation for this entry-type, overriding any specified widgets in the --fields option.
@@ -61,7 +98,62 @@ pub struct EntryType {
 }
 
 impl EntryType {
-    pub fn run(self, template_type: &TemplateType) -> anyhow::Result<()> {
+    /// The arguments given to scaffold a new entry type, which the add-field subcommand doesn't take
+    fn arguments_for_new_entry_type(&self) -> Vec<&'static str> {
+        [
+            ("--dna", self.dna.is_some()),
+            ("--zome", self.zome.is_some()),
+            ("<name>", self.name.is_some()),
+            (
+                "--reference-entry-hash",
+                self.reference_entry_hash.is_some(),
+            ),
+            ("--crud", self.crud.is_some()),
+            (
+                "--link-from-original-to-each-update",
+                self.link_from_original_to_each_update.is_some(),
+            ),
+            ("--update-policy", self.update_policy.is_some()),
+            ("--delete-policy", self.delete_policy.is_some()),
+            ("--immutable-fields", !self.immutable_fields.is_empty()),
+            ("--visibility", self.visibility.is_some()),
+            (
+                "--required-validations",
+                self.required_validations.is_some(),
+            ),
+            ("--entry-def-name", self.entry_def_name.is_some()),
+            ("--fields", self.fields.is_some()),
+            ("--no-ui", self.no_ui),
+            ("--no-spec", self.no_spec),
+        ]
+        .into_iter()
+        .filter_map(|(argument, given)| given.then_some(argument))
+        .collect()
+    }
+
+    pub fn run(
+        mut self,
+        template_type: &TemplateType,
+        dry_run: bool,
+        no_interactive: bool,
+    ) -> anyhow::Result<()> {
+        if let Some(EntryTypeCommand::AddField(add_field)) = self.command.take() {
+            let ignored_arguments = self.arguments_for_new_entry_type();
+            if !ignored_arguments.is_empty() {
+                return Err(ScaffoldError::InvalidArguments(format!(
+                    "{} can't be used with the add-field subcommand, pass them after it instead",
+                    ignored_arguments.join(", ")
+                ))
+                .into());
+            }
+            return add_field.run(template_type, dry_run, no_interactive);
+        }
+
+        let fields = self
+            .fields
+            .as_deref()
+            .map(FieldDefinition::parse_list)
+            .transpose()?;
         let current_dir = std::env::current_dir()?;
         let file_tree = load_directory_into_memory(&current_dir)?;
         let name = match self.name {
@@ -69,12 +161,19 @@ impl EntryType {
                 check_case(&n, "entry type name", Case::Snake)?;
                 n
             }
-            None => input_with_case("Entry type name (snake_case):", Case::Snake)?,
+            None => {
+                check_interactive("the `<name>` argument", no_interactive)?;
+                input_with_case("Entry type name (snake_case):", Case::Snake, no_interactive)?
+            }
         };
 
-        let dna_file_tree = DnaFileTree::get_or_choose(file_tree, self.dna.as_deref())?;
-        let zome_file_tree =
-            ZomeFileTree::get_or_choose_integrity(dna_file_tree, self.zome.as_deref())?;
+        let dna_file_tree =
+            DnaFileTree::get_or_choose(file_tree, self.dna.as_deref(), no_interactive)?;
+        let zome_file_tree = ZomeFileTree::get_or_choose_integrity(
+            dna_file_tree,
+            self.zome.as_deref(),
+            no_interactive,
+        )?;
 
         let ScaffoldedTemplate {
             file_tree,
@@ -83,17 +182,26 @@ impl EntryType {
             zome_file_tree,
             &template_type.file_tree()?,
             &name,
-            self.crud,
-            self.reference_entry_hash,
-            self.link_from_original_to_each_update,
-            self.fields.as_ref(),
+            EntryTypeOptions {
+                crud: self.crud,
+                reference_entry_hash: self.reference_entry_hash,
+                link_from_original_to_each_update: self.link_from_original_to_each_update,
+                update_policy: self.update_policy.unwrap_or_default(),
+                delete_policy: self.delete_policy.unwrap_or_default(),
+                immutable_fields: self.immutable_fields,
+                visibility: self.visibility.unwrap_or_default(),
+                required_validations: self.required_validations,
+                entry_def_name: self.entry_def_name,
+                fields,
+            },
             self.no_ui,
             self.no_spec,
+            no_interactive,
         )?;
 
-        build_file_tree(file_tree, ".")?;
+        build_file_tree(file_tree, ".", dry_run)?;
 
-        if let Err(e) = run_cargo_fmt_if_available() {
+        if let Err(e) = run_cargo_fmt_if_available(dry_run) {
             println!(
                 "{}: {}",
                 "rustfmt exec failed: ".yellow(),
@@ -117,3 +225,132 @@ Add new collections for that entry type with:
         Ok(())
     }
 }
+
+#[derive(Debug, StructOpt)]
+pub enum EntryTypeCommand {
+    AddField(AddField),
+}
+
+#[derive(Debug, StructOpt)]
+/// Add new fields to an existing entry type, updating its validation, CRUD functions and UI
+pub struct AddField {
+    #[structopt(long)]
+    /// Name of the dna in which the entry type exists
+    pub dna: Option<String>,
+
+    #[structopt(long)]
+    /// Name of the integrity zome in which the entry type is defined
+    pub zome: Option<String>,
+
+    /// Name of the entry type to add the fields to
+    pub entry_type: String,
+
+    #[structopt(long)]
+    /// The fields to add to the entry type struct
+    /// Syntax: <FIELD_NAME>:<FIELD_TYPE>:<WIDGET>:<LINKED_FROM>;<CONSTRAINT>... , (widget, linked_from and constraints are optional)
+    /// Constraints: min_len=<n>, max_len=<n>, non_empty, regex=<pattern>, range=<min>..<max>, one_of=<a>|<b>
+    /// Eg. "title:String:TextField;min_len=3" , "posts_hashes:Vec\<ActionHash\>::Post" , "address:Address{street:String:TextField,zip:String}:Form"
+    pub fields: Option<Vec<String>>,
+
+    #[structopt(long)]
+    /// Skips UI generation for the new fields, overriding any specified widgets in the --fields option.
+    pub no_ui: bool,
+
+    #[structopt(long)]
+    /// Skips test generation for the new fields
+    pub no_spec: bool,
+}
+
+impl AddField {
+    pub fn run(
+        self,
+        template_type: &TemplateType,
+        dry_run: bool,
+        no_interactive: bool,
+    ) -> anyhow::Result<()> {
+        check_case(&self.entry_type, "entry type name", Case::Snake)?;
+        let fields = self
+            .fields
+            .as_deref()
+            .map(FieldDefinition::parse_list)
+            .transpose()?;
+
+        let current_dir = std::env::current_dir()?;
+        let file_tree = load_directory_into_memory(&current_dir)?;
+
+        let dna_file_tree =
+            DnaFileTree::get_or_choose(file_tree, self.dna.as_deref(), no_interactive)?;
+        let zome_file_tree = ZomeFileTree::get_or_choose_integrity(
+            dna_file_tree,
+            self.zome.as_deref(),
+            no_interactive,
+        )?;
+
+        let ScaffoldedTemplate {
+            file_tree,
+            next_instructions,
+        } = scaffold_entry_type_fields(
+            zome_file_tree,
+            &template_type.file_tree()?,
+            &self.entry_type,
+            fields.as_ref(),
+            self.no_ui,
+            self.no_spec,
+            no_interactive,
+        )?;
+
+        build_file_tree(file_tree, ".", dry_run)?;
+
+        if let Err(e) = run_cargo_fmt_if_available(dry_run) {
+            println!(
+                "{}: {}",
+                "rustfmt exec failed: ".yellow(),
+                e.to_string().yellow()
+            );
+        }
+
+        println!(
+            "\nFields added to the entry type {}!",
+            self.entry_type.italic()
+        );
+
+        if let Some(i) = next_instructions {
+            println!("\n{}", i);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arguments_for_new_entry_type() {
+        let entry_type = EntryType::from_iter_safe([
+            "entry-type",
+            "--dna",
+            "forum",
+            "--no-ui",
+            "add-field",
+            "post",
+            "--fields",
+            "title:String",
+        ])
+        .unwrap();
+        assert!(matches!(
+            entry_type.command,
+            Some(EntryTypeCommand::AddField(_))
+        ));
+        assert_eq!(
+            entry_type.arguments_for_new_entry_type(),
+            vec!["--dna", "--no-ui"]
+        );
+
+        let entry_type =
+            EntryType::from_iter_safe(["entry-type", "add-field", "post", "--dna", "forum"])
+                .unwrap();
+        assert!(entry_type.arguments_for_new_entry_type().is_empty());
+    }
+}
@@ -1,13 +1,12 @@
-use std::{ffi::OsString, path::PathBuf, str::FromStr};
+use std::{path::PathBuf, str::FromStr};
 
-use build_fs_tree::{Build, MergeableFileSystemTree};
 use colored::Colorize;
 use structopt::StructOpt;
 use tokio::fs;
 
 use crate::{
-    error::ScaffoldError,
-    file_tree::build_file_tree,
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::{build_file_tree, FileTree},
     scaffold::{
         app::{git::setup_git_environment, nix::setup_nix_developer_environment, AppFileTree},
         collection::{scaffold_collection, CollectionType},
@@ -18,7 +17,7 @@ use crate::{
             definitions::{
                 Cardinality, EntryTypeReference, FieldDefinition, FieldType, Referenceable,
             },
-            scaffold_entry_type,
+            scaffold_entry_type, EntryTypeOptions,
         },
         example::ExampleType,
         web_app::{package_manager::PackageManager, scaffold_web_app, template_type::TemplateType},
@@ -52,7 +51,12 @@ pub struct Example {
 }
 
 impl Example {
-    pub async fn run(self, template_type: &TemplateType) -> anyhow::Result<()> {
+    pub async fn run(
+        self,
+        template_type: &TemplateType,
+        dry_run: bool,
+        no_interactive: bool,
+    ) -> anyhow::Result<()> {
         let template_file_tree = template_type.file_tree()?;
         let template_name = template_type.name();
         let is_vanilla_template = matches!(template_type, TemplateType::Vanilla);
@@ -64,7 +68,7 @@ impl Example {
                     println!("Scaffolding the {} example project", "hello-world".italic());
                     ExampleType::HelloWorld
                 } else {
-                    ExampleType::choose_non_vanilla()?
+                    ExampleType::choose_non_vanilla(no_interactive)?
                 }
             }
         };
@@ -89,7 +93,7 @@ impl Example {
 
         let package_manager = match self.package_manager {
             Some(p) => p,
-            None => PackageManager::choose()?,
+            None => PackageManager::choose(no_interactive)?,
         };
 
         // Match on example types
@@ -107,166 +111,13 @@ impl Example {
 
                 file_tree
             }
-            ExampleType::Forum => {
-                // scaffold web-app
-                let ScaffoldedTemplate { file_tree, .. } = scaffold_web_app(
-                    &example_name,
-                    Some("A simple 'forum' application."),
-                    package_manager,
-                    false,
-                    &template_file_tree,
-                    self.holo_enabled,
-                )?;
-
-                // scaffold dna hello_world
-                let dna_name = "forum";
-
-                let app_file_tree = AppFileTree::get_or_choose(file_tree, Some(&example_name))?;
-                let ScaffoldedTemplate { file_tree, .. } =
-                    scaffold_dna(app_file_tree, &template_file_tree, dna_name)?;
-
-                // scaffold integrity zome posts
-                let dna_file_tree = DnaFileTree::get_or_choose(file_tree, Some(dna_name))?;
-                let dna_manifest_path = dna_file_tree.dna_manifest_path.clone();
-
-                let integrity_zome_name = "posts_integrity";
-                let integrity_zome_path = PathBuf::new()
-                    .join("dnas")
-                    .join(dna_name)
-                    .join("zomes")
-                    .join("integrity");
-                let ScaffoldedTemplate { file_tree, .. } = scaffold_integrity_zome_with_path(
-                    dna_file_tree,
-                    &template_file_tree,
-                    integrity_zome_name,
-                    &integrity_zome_path,
-                )?;
-
-                let dna_file_tree =
-                    DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
-
-                let coordinator_zome_name = "posts";
-                let coordinator_zome_path = PathBuf::new()
-                    .join("dnas")
-                    .join(dna_name)
-                    .join("zomes")
-                    .join("coordinator");
-                let ScaffoldedTemplate { file_tree, .. } = scaffold_coordinator_zome_in_path(
-                    dna_file_tree,
-                    &template_file_tree,
-                    coordinator_zome_name,
-                    Some(&vec![integrity_zome_name.to_owned()]),
-                    &coordinator_zome_path,
-                )?;
-
-                // Scaffold the app here to enable ZomeFileTree::from_manifest(), which calls `cargo metadata`
-                MergeableFileSystemTree::<OsString, String>::from(file_tree.clone())
-                    .build(&app_dir)?;
-
-                std::env::set_current_dir(&app_dir)?;
-
-                let dna_file_tree =
-                    DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
-
-                let zome_file_tree = ZomeFileTree::get_or_choose_integrity(
-                    dna_file_tree,
-                    Some(integrity_zome_name),
-                )?;
-
-                let post_entry_type_name = "post";
-
-                let ScaffoldedTemplate { file_tree, .. } = scaffold_entry_type(
-                    zome_file_tree,
-                    &template_file_tree,
-                    "post",
-                    Some(Crud {
-                        update: true,
-                        delete: true,
-                    }),
-                    Some(false),
-                    Some(true),
-                    Some(&vec![
-                        FieldDefinition {
-                            field_name: "title".to_string(),
-                            field_type: FieldType::String,
-                            widget: Some("TextField".to_string()),
-                            cardinality: Cardinality::Single,
-                            linked_from: None,
-                        },
-                        FieldDefinition {
-                            field_name: "content".to_string(),
-                            field_type: FieldType::String,
-                            widget: Some("TextArea".to_string()),
-                            cardinality: Cardinality::Single,
-                            linked_from: None,
-                        },
-                    ]),
-                    false,
-                    false,
-                )?;
-
-                let dna_file_tree =
-                    DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
-
-                let zome_file_tree =
-                    ZomeFileTree::get_or_choose_integrity(dna_file_tree, Some("posts_integrity"))?;
-
-                let ScaffoldedTemplate { file_tree, .. } = scaffold_entry_type(
-                    zome_file_tree,
-                    &template_file_tree,
-                    "comment",
-                    Some(Crud {
-                        update: false,
-                        delete: true,
-                    }),
-                    Some(false),
-                    Some(true),
-                    Some(&vec![
-                        FieldDefinition {
-                            field_name: "comment".to_string(),
-                            field_type: FieldType::String,
-                            widget: Some("TextArea".to_string()),
-                            cardinality: Cardinality::Single,
-                            linked_from: None,
-                        },
-                        FieldDefinition {
-                            field_name: "post_hash".to_string(),
-                            field_type: FieldType::ActionHash,
-                            widget: None,
-                            cardinality: Cardinality::Single,
-                            linked_from: Some(Referenceable::EntryType(EntryTypeReference {
-                                entry_type: post_entry_type_name.to_string(),
-                                reference_entry_hash: false,
-                            })),
-                        },
-                    ]),
-                    false,
-                    false,
-                )?;
-
-                let dna_file_tree =
-                    DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
-
-                let zome_file_tree = ZomeFileTree::get_or_choose_integrity(
-                    dna_file_tree,
-                    Some(integrity_zome_name),
-                )?;
-
-                let ScaffoldedTemplate { file_tree, .. } = scaffold_collection(
-                    zome_file_tree,
-                    &template_file_tree,
-                    "all_posts",
-                    Some(CollectionType::Global),
-                    Some(EntryTypeReference {
-                        entry_type: "post".to_string(),
-                        reference_entry_hash: false,
-                    }),
-                    false,
-                    false,
-                )?;
-
-                file_tree
-            }
+            ExampleType::Forum => scaffold_forum(
+                &example_name,
+                package_manager,
+                &template_file_tree,
+                self.holo_enabled,
+                no_interactive,
+            )?,
         };
 
         let ScaffoldedTemplate {
@@ -276,9 +127,15 @@ impl Example {
 
         let file_tree = ScaffoldConfig::write_to_package_json(file_tree, template_type)?;
 
-        build_file_tree(file_tree, &app_dir)?;
+        build_file_tree(file_tree, &app_dir, dry_run)?;
 
-        if let Err(e) = run_cargo_fmt_if_available() {
+        if dry_run {
+            return Ok(());
+        }
+
+        std::env::set_current_dir(&app_dir)?;
+
+        if let Err(e) = run_cargo_fmt_if_available(dry_run) {
             println!(
                 "{}: {}",
                 "rustfmt exec failed: ".yellow(),
@@ -305,3 +162,214 @@ impl Example {
         Ok(())
     }
 }
+
+/// Scaffolds the forum example in memory: a web-app with a `forum` DNA whose `posts` zomes
+/// hold posts, comments on them and a collection of all the posts
+fn scaffold_forum(
+    example_name: &str,
+    package_manager: PackageManager,
+    template_file_tree: &FileTree,
+    holo_enabled: bool,
+    no_interactive: bool,
+) -> ScaffoldResult<FileTree> {
+    // scaffold web-app
+    let ScaffoldedTemplate { file_tree, .. } = scaffold_web_app(
+        example_name,
+        Some("A simple 'forum' application."),
+        package_manager,
+        false,
+        template_file_tree,
+        holo_enabled,
+    )?;
+
+    // scaffold dna hello_world
+    let dna_name = "forum";
+
+    let app_file_tree = AppFileTree::get_or_choose(file_tree, Some(example_name), no_interactive)?;
+    let ScaffoldedTemplate { file_tree, .. } =
+        scaffold_dna(app_file_tree, template_file_tree, dna_name, no_interactive)?;
+
+    // scaffold integrity zome posts
+    let dna_file_tree = DnaFileTree::get_or_choose(file_tree, Some(dna_name), no_interactive)?;
+    let dna_manifest_path = dna_file_tree.dna_manifest_path.clone();
+
+    let integrity_zome_name = "posts_integrity";
+    let integrity_zome_path = PathBuf::new()
+        .join("dnas")
+        .join(dna_name)
+        .join("zomes")
+        .join("integrity");
+    let ScaffoldedTemplate { file_tree, .. } = scaffold_integrity_zome_with_path(
+        dna_file_tree,
+        template_file_tree,
+        integrity_zome_name,
+        &integrity_zome_path,
+    )?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+
+    let coordinator_zome_name = "posts";
+    let coordinator_zome_path = PathBuf::new()
+        .join("dnas")
+        .join(dna_name)
+        .join("zomes")
+        .join("coordinator");
+    let ScaffoldedTemplate { file_tree, .. } = scaffold_coordinator_zome_in_path(
+        dna_file_tree,
+        template_file_tree,
+        coordinator_zome_name,
+        Some(&vec![integrity_zome_name.to_owned()]),
+        &coordinator_zome_path,
+    )?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+
+    let zome_file_tree = ZomeFileTree::get_or_choose_integrity(
+        dna_file_tree,
+        Some(integrity_zome_name),
+        no_interactive,
+    )?;
+
+    let post_entry_type_name = "post";
+
+    let ScaffoldedTemplate { file_tree, .. } = scaffold_entry_type(
+        zome_file_tree,
+        template_file_tree,
+        "post",
+        EntryTypeOptions {
+            crud: Some(Crud {
+                update: true,
+                delete: true,
+            }),
+            reference_entry_hash: Some(false),
+            link_from_original_to_each_update: Some(true),
+            fields: Some(vec![
+                FieldDefinition {
+                    field_name: "title".to_string(),
+                    field_type: FieldType::String,
+                    widget: Some("TextField".to_string()),
+                    cardinality: Cardinality::Single,
+                    linked_from: None,
+                    constraints: vec![],
+                },
+                FieldDefinition {
+                    field_name: "content".to_string(),
+                    field_type: FieldType::String,
+                    widget: Some("TextArea".to_string()),
+                    cardinality: Cardinality::Single,
+                    linked_from: None,
+                    constraints: vec![],
+                },
+            ]),
+            ..Default::default()
+        },
+        false,
+        false,
+        no_interactive,
+    )?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+
+    let zome_file_tree = ZomeFileTree::get_or_choose_integrity(
+        dna_file_tree,
+        Some("posts_integrity"),
+        no_interactive,
+    )?;
+
+    let ScaffoldedTemplate { file_tree, .. } = scaffold_entry_type(
+        zome_file_tree,
+        template_file_tree,
+        "comment",
+        EntryTypeOptions {
+            crud: Some(Crud {
+                update: false,
+                delete: true,
+            }),
+            reference_entry_hash: Some(false),
+            link_from_original_to_each_update: Some(true),
+            fields: Some(vec![
+                FieldDefinition {
+                    field_name: "comment".to_string(),
+                    field_type: FieldType::String,
+                    widget: Some("TextArea".to_string()),
+                    cardinality: Cardinality::Single,
+                    linked_from: None,
+                    constraints: vec![],
+                },
+                FieldDefinition {
+                    field_name: "post_hash".to_string(),
+                    field_type: FieldType::ActionHash,
+                    widget: None,
+                    cardinality: Cardinality::Single,
+                    linked_from: Some(Referenceable::EntryType(EntryTypeReference {
+                        entry_type: post_entry_type_name.to_string(),
+                        reference_entry_hash: false,
+                    })),
+                    constraints: vec![],
+                },
+            ]),
+            ..Default::default()
+        },
+        false,
+        false,
+        no_interactive,
+    )?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+
+    let zome_file_tree = ZomeFileTree::get_or_choose_integrity(
+        dna_file_tree,
+        Some(integrity_zome_name),
+        no_interactive,
+    )?;
+
+    let ScaffoldedTemplate { file_tree, .. } = scaffold_collection(
+        zome_file_tree,
+        template_file_tree,
+        "all_posts",
+        Some(CollectionType::Global),
+        vec![EntryTypeReference {
+            entry_type: "post".to_string(),
+            reference_entry_hash: false,
+        }],
+        None,
+        false,
+        None,
+        None,
+        false,
+        false,
+        no_interactive,
+    )?;
+
+    Ok(file_tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forum_dry_run_writes_nothing() {
+        let template_file_tree = TemplateType::Svelte.file_tree().unwrap();
+        let file_tree = scaffold_forum(
+            "forum",
+            PackageManager::Npm,
+            &template_file_tree,
+            false,
+            true,
+        )
+        .unwrap();
+        let ScaffoldedTemplate { file_tree, .. } = scaffold_example(
+            file_tree,
+            PackageManager::Npm,
+            &template_file_tree,
+            &ExampleType::Forum,
+        )
+        .unwrap();
+
+        let app_dir = std::env::temp_dir().join(format!("forum-dry-run-{}", std::process::id()));
+        build_file_tree(file_tree, &app_dir, true).unwrap();
+
+        assert!(!app_dir.exists());
+    }
+}
@@ -0,0 +1,20 @@
+use structopt::StructOpt;
+
+use crate::{file_tree::load_directory_into_memory, scaffold::inspect::inspect_happ};
+
+#[derive(Debug, StructOpt)]
+/// Print the apps, DNAs, zomes, entry types, link types and extern functions of the current hApp as JSON
+pub struct Inspect {}
+
+impl Inspect {
+    pub fn run(self) -> anyhow::Result<()> {
+        let current_dir = std::env::current_dir()?;
+        let file_tree = load_directory_into_memory(&current_dir)?;
+
+        let inspection = inspect_happ(&file_tree)?;
+
+        println!("{}", serde_json::to_string_pretty(&inspection)?);
+
+        Ok(())
+    }
+}
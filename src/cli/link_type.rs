@@ -6,8 +6,14 @@ use structopt::StructOpt;
 use crate::{
     file_tree::{build_file_tree, load_directory_into_memory},
     scaffold::{
-        dna::DnaFileTree, entry_type::definitions::Referenceable, link_type::scaffold_link_type,
-        web_app::template_type::TemplateType, zome::ZomeFileTree,
+        dna::DnaFileTree,
+        entry_type::{
+            crud::Policy,
+            definitions::{FieldType, Referenceable},
+        },
+        link_type::{scaffold_link_type, EntryTypeLocation, LinkValidation},
+        web_app::template_type::TemplateType,
+        zome::ZomeFileTree,
     },
     templates::ScaffoldedTemplate,
     utils::run_cargo_fmt_if_available,
@@ -32,6 +38,22 @@ pub struct LinkType {
     /// Entry type (or agent role) used as the target for the links
     pub to_referenceable: Option<Referenceable>,
 
+    #[structopt(long)]
+    /// Integrity zome in which the "<from-referenceable>" entry type is defined, if not the one of the link type
+    pub from_zome: Option<String>,
+
+    #[structopt(long)]
+    /// DNA in which the "<from-referenceable>" entry type is defined, if not the one of the link type
+    pub from_dna: Option<String>,
+
+    #[structopt(long)]
+    /// Integrity zome in which the "<to-referenceable>" entry type is defined, if not the one of the link type
+    pub to_zome: Option<String>,
+
+    #[structopt(long)]
+    /// DNA in which the "<to-referenceable>" entry type is defined, if not the one of the link type
+    pub to_dna: Option<String>,
+
     #[structopt(long)]
     /// Whether to create the inverse link, from the "--to-referenceable" entry type to the "--from-referenceable" entry type
     pub bidirectional: Option<bool>,
@@ -40,6 +62,25 @@ pub struct LinkType {
     /// Whether this link type can be deleted
     pub delete: Option<bool>,
 
+    #[structopt(long, parse(try_from_str = FieldType::parse_struct))]
+    /// Struct serialized into the tag of the links, with its fields, eg. "Weight{value:u32,note:String}"
+    /// It will be defined in the integrity zome if it doesn't exist yet
+    pub tag_type: Option<FieldType>,
+
+    #[structopt(long, parse(try_from_str = Policy::from_str))]
+    /// Who can delete the links: "anyone" (default) or "author", the agent that created the link
+    /// Only applies if the link type can be deleted
+    pub delete_policy: Option<Policy>,
+
+    #[structopt(long)]
+    /// Only allows agents to create links whose base is their own public key, for link types from an agent role
+    pub base_is_author: bool,
+
+    #[structopt(long)]
+    /// Only allows each agent to create one link from a given base to a given target, until it's deleted
+    /// The validation only looks up the links in the source chain of the author
+    pub unique: bool,
+
     #[structopt(long)]
     /// Skips UI generation for this link-type.
     pub no_ui: bool,
@@ -50,13 +91,22 @@ pub struct LinkType {
 }
 
 impl LinkType {
-    pub fn run(self, template_type: &TemplateType) -> anyhow::Result<()> {
+    pub fn run(
+        self,
+        template_type: &TemplateType,
+        dry_run: bool,
+        no_interactive: bool,
+    ) -> anyhow::Result<()> {
         let current_dir = std::env::current_dir()?;
         let file_tree = load_directory_into_memory(&current_dir)?;
 
-        let dna_file_tree = DnaFileTree::get_or_choose(file_tree, self.dna.as_deref())?;
-        let zome_file_tree =
-            ZomeFileTree::get_or_choose_integrity(dna_file_tree, self.zome.as_deref())?;
+        let dna_file_tree =
+            DnaFileTree::get_or_choose(file_tree, self.dna.as_deref(), no_interactive)?;
+        let zome_file_tree = ZomeFileTree::get_or_choose_integrity(
+            dna_file_tree,
+            self.zome.as_deref(),
+            no_interactive,
+        )?;
 
         let ScaffoldedTemplate {
             file_tree,
@@ -68,13 +118,29 @@ impl LinkType {
             self.to_referenceable.as_ref(),
             self.delete,
             self.bidirectional,
+            self.tag_type.as_ref(),
+            LinkValidation {
+                delete_policy: self.delete_policy.unwrap_or_default(),
+                base_is_author: self.base_is_author,
+                unique: self.unique,
+                ..Default::default()
+            },
+            &EntryTypeLocation {
+                dna: self.from_dna,
+                zome: self.from_zome,
+            },
+            &EntryTypeLocation {
+                dna: self.to_dna,
+                zome: self.to_zome,
+            },
             self.no_ui,
             self.no_spec,
+            no_interactive,
         )?;
 
-        build_file_tree(file_tree, ".")?;
+        build_file_tree(file_tree, ".", dry_run)?;
 
-        if let Err(e) = run_cargo_fmt_if_available() {
+        if let Err(e) = run_cargo_fmt_if_available(dry_run) {
             println!(
                 "{}: {}",
                 "rustfmt exec failed: ".yellow(),
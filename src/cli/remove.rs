@@ -0,0 +1,126 @@
+use colored::Colorize;
+use structopt::StructOpt;
+
+use crate::{
+    file_tree::{build_file_tree_with_deletions, load_directory_into_memory},
+    scaffold::{
+        dna::DnaFileTree,
+        remove::{remove_collection, remove_entry_type, remove_link_type},
+        zome::ZomeFileTree,
+    },
+    utils::run_cargo_fmt_if_available,
+};
+
+#[derive(Debug, StructOpt)]
+/// Remove an entry type, link type or collection, undoing what was scaffolded for it
+pub enum Remove {
+    /// Remove an entry type, its validation, its CRUD functions and the link types validated along with it
+    EntryType {
+        #[structopt(long)]
+        /// Name of the dna from which you want to remove the entry type
+        dna: Option<String>,
+
+        #[structopt(long)]
+        /// Name of the integrity zome from which you want to remove the entry type
+        zome: Option<String>,
+
+        /// Name of the entry type to remove
+        name: String,
+
+        #[structopt(long)]
+        /// Whether to also delete the UI and test files that were generated for the entry type
+        delete_ui_and_tests: Option<bool>,
+    },
+    /// Remove a link type, its validation and its zome functions
+    LinkType {
+        #[structopt(long)]
+        /// Name of the dna from which you want to remove the link type
+        dna: Option<String>,
+
+        #[structopt(long)]
+        /// Name of the integrity zome from which you want to remove the link type
+        zome: Option<String>,
+
+        /// Name of the link type to remove, as it appears in the `LinkTypes` enum (eg. "PostToComments")
+        name: String,
+
+        #[structopt(long)]
+        /// Whether to also delete the UI and test files that were generated for the link type
+        delete_ui_and_tests: Option<bool>,
+    },
+    /// Remove a collection, its link type and its getter function
+    Collection {
+        #[structopt(long)]
+        /// Name of the dna from which you want to remove the collection
+        dna: Option<String>,
+
+        #[structopt(long)]
+        /// Name of the integrity zome from which you want to remove the collection
+        zome: Option<String>,
+
+        /// Name of the collection to remove
+        name: String,
+
+        #[structopt(long)]
+        /// Whether to also delete the UI and test files that were generated for the collection
+        delete_ui_and_tests: Option<bool>,
+    },
+}
+
+impl Remove {
+    pub fn run(self, dry_run: bool, no_interactive: bool) -> anyhow::Result<()> {
+        let current_dir = std::env::current_dir()?;
+        let file_tree = load_directory_into_memory(&current_dir)?;
+
+        let (dna, zome) = match &self {
+            Remove::EntryType { dna, zome, .. }
+            | Remove::LinkType { dna, zome, .. }
+            | Remove::Collection { dna, zome, .. } => (dna.clone(), zome.clone()),
+        };
+
+        let dna_file_tree = DnaFileTree::get_or_choose(file_tree, dna.as_deref(), no_interactive)?;
+        let zome_file_tree =
+            ZomeFileTree::get_or_choose_integrity(dna_file_tree, zome.as_deref(), no_interactive)?;
+
+        let (file_tree, removed) = match self {
+            Remove::EntryType {
+                name,
+                delete_ui_and_tests,
+                ..
+            } => (
+                remove_entry_type(zome_file_tree, &name, delete_ui_and_tests, no_interactive)?,
+                "Entry type",
+            ),
+            Remove::LinkType {
+                name,
+                delete_ui_and_tests,
+                ..
+            } => (
+                remove_link_type(zome_file_tree, &name, delete_ui_and_tests, no_interactive)?,
+                "Link type",
+            ),
+            Remove::Collection {
+                name,
+                delete_ui_and_tests,
+                ..
+            } => (
+                remove_collection(zome_file_tree, &name, delete_ui_and_tests, no_interactive)?,
+                "Collection",
+            ),
+        };
+
+        build_file_tree_with_deletions(file_tree, ".", dry_run)?;
+
+        if let Err(e) = run_cargo_fmt_if_available(dry_run) {
+            println!(
+                "{}: {}",
+                "rustfmt exec failed: ".yellow(),
+                e.to_string().yellow()
+            );
+        }
+
+        println!("\n{removed} removed!");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use colored::Colorize;
+use convert_case::Case;
+use structopt::StructOpt;
+
+use crate::{
+    file_tree::{build_file_tree_with_deletions, load_directory_into_memory},
+    scaffold::{dna::DnaFileTree, rename::rename_entry_type, zome::ZomeFileTree},
+    utils::{check_case, run_cargo_fmt_if_available},
+};
+
+#[derive(Debug, StructOpt)]
+/// Rename something that was scaffolded, everywhere it is referenced
+pub enum Rename {
+    /// Rename an entry type and every identifier derived from its name in the zomes, the UI and the tests
+    EntryType {
+        #[structopt(long)]
+        /// Name of the dna in which the entry type is defined
+        dna: Option<String>,
+
+        #[structopt(long)]
+        /// Name of the integrity zome in which the entry type is defined
+        zome: Option<String>,
+
+        /// Current name of the entry type (snake_case)
+        old_name: String,
+
+        /// New name for the entry type (snake_case)
+        new_name: String,
+    },
+}
+
+impl Rename {
+    pub fn run(self, dry_run: bool, no_interactive: bool) -> anyhow::Result<()> {
+        let Rename::EntryType {
+            dna,
+            zome,
+            old_name,
+            new_name,
+        } = self;
+
+        check_case(&old_name, "entry type name", Case::Snake)?;
+        check_case(&new_name, "entry type name", Case::Snake)?;
+
+        let current_dir = std::env::current_dir()?;
+        let file_tree = load_directory_into_memory(&current_dir)?;
+
+        let dna_file_tree = DnaFileTree::get_or_choose(file_tree, dna.as_deref(), no_interactive)?;
+        let zome_file_tree =
+            ZomeFileTree::get_or_choose_integrity(dna_file_tree, zome.as_deref(), no_interactive)?;
+
+        let file_tree = rename_entry_type(zome_file_tree, &old_name, &new_name)?;
+
+        build_file_tree_with_deletions(file_tree, ".", dry_run)?;
+
+        if let Err(e) = run_cargo_fmt_if_available(dry_run) {
+            println!(
+                "{}: {}",
+                "rustfmt exec failed: ".yellow(),
+                e.to_string().yellow()
+            );
+        }
+
+        println!(
+            r#"
+Entry type "{old_name}" renamed to "{new_name}"!
+
+Files outside the UI and test folders of its zomes, like the ones importing its UI components, need to be updated by hand."#
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use colored::Colorize;
+use convert_case::Case;
+use structopt::StructOpt;
+
+use crate::{
+    file_tree::{build_file_tree, load_directory_into_memory},
+    scaffold::{
+        dna::DnaFileTree, entry_type::definitions::FieldDefinition, signal::scaffold_signal,
+        web_app::template_type::TemplateType, zome::ZomeFileTree,
+    },
+    templates::ScaffoldedTemplate,
+    utils::{check_case, check_interactive, input_with_case, run_cargo_fmt_if_available},
+};
+
+#[derive(Debug, StructOpt)]
+/// Scaffold a signal that agents can send to each other, and that is emitted to the UI of the recipients
+pub struct Signal {
+    #[structopt(long)]
+    /// Name of the dna in which you want to scaffold the signal
+    pub dna: Option<String>,
+
+    #[structopt(long)]
+    /// Name of the coordinator zome in which you want to scaffold the signal
+    pub zome: Option<String>,
+
+    /// Name of the signal being scaffolded, in snake_case
+    pub name: Option<String>,
+
+    #[structopt(long)]
+    /// The fields that the signal carries, with the same syntax as the fields of entry types
+    /// Enum and struct fields are not supported, eg. "message:String" , "post_hash:ActionHash"
+    pub fields: Option<Vec<String>>,
+
+    #[structopt(long)]
+    /// Skips UI generation for this signal
+    pub no_ui: bool,
+}
+
+impl Signal {
+    pub fn run(
+        self,
+        template_type: &TemplateType,
+        dry_run: bool,
+        no_interactive: bool,
+    ) -> anyhow::Result<()> {
+        let fields = self
+            .fields
+            .as_deref()
+            .map(FieldDefinition::parse_list)
+            .transpose()?
+            .unwrap_or_default();
+        let current_dir = std::env::current_dir()?;
+        let file_tree = load_directory_into_memory(&current_dir)?;
+        let name = match self.name {
+            Some(n) => {
+                check_case(&n, "signal name", Case::Snake)?;
+                n
+            }
+            None => {
+                check_interactive("the `<name>` argument", no_interactive)?;
+                input_with_case(
+                    "Signal name (snake_case, eg. \"new_message\"):",
+                    Case::Snake,
+                    no_interactive,
+                )?
+            }
+        };
+
+        let dna_file_tree =
+            DnaFileTree::get_or_choose(file_tree, self.dna.as_deref(), no_interactive)?;
+        let zome_file_tree = ZomeFileTree::get_or_choose_coordinator(
+            dna_file_tree,
+            self.zome.as_deref(),
+            no_interactive,
+        )?;
+
+        let ScaffoldedTemplate {
+            file_tree,
+            next_instructions,
+        } = scaffold_signal(
+            zome_file_tree,
+            &template_type.file_tree()?,
+            &name,
+            &fields,
+            self.no_ui,
+            no_interactive,
+        )?;
+
+        build_file_tree(file_tree, ".", dry_run)?;
+
+        if let Err(e) = run_cargo_fmt_if_available(dry_run) {
+            println!(
+                "{}: {}",
+                "rustfmt exec failed: ".yellow(),
+                e.to_string().yellow()
+            );
+        }
+
+        println!("\nSignal {} scaffolded!", name.italic());
+
+        if let Some(i) = next_instructions {
+            println!("\n{}", i);
+        }
+
+        Ok(())
+    }
+}
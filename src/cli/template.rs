@@ -1,10 +1,11 @@
-use std::{ffi::OsString, path::PathBuf};
-
-use build_fs_tree::{dir, Build, MergeableFileSystemTree};
+use build_fs_tree::dir;
 use dialoguer::{theme::ColorfulTheme, Input};
 use structopt::StructOpt;
 
-use crate::scaffold::web_app::template_type::TemplateType;
+use crate::{
+    file_tree::build_file_tree, scaffold::web_app::template_type::TemplateType,
+    utils::check_interactive,
+};
 
 #[derive(Debug, StructOpt)]
 #[structopt(setting = structopt::clap::AppSettings::InferSubcommands)]
@@ -19,10 +20,16 @@ pub enum Template {
 }
 
 impl Template {
-    pub fn run(self, template_type: &TemplateType) -> anyhow::Result<()> {
+    pub fn run(
+        self,
+        template_type: &TemplateType,
+        dry_run: bool,
+        no_interactive: bool,
+    ) -> anyhow::Result<()> {
         let target_template = match self.target_template() {
             Some(t) => t,
             None => {
+                check_interactive("the `--to-template` option", no_interactive)?;
                 // Enter template name
                 Input::with_theme(&ColorfulTheme::default())
                     .with_prompt("Enter new template name:")
@@ -34,9 +41,7 @@ impl Template {
             target_template.clone() => template_type.file_tree()?
         };
 
-        let file_tree = MergeableFileSystemTree::<OsString, String>::from(template_file_tree);
-
-        file_tree.build(&PathBuf::from("."))?;
+        build_file_tree(template_file_tree, ".", dry_run)?;
 
         match self {
             Template::Clone { .. } => {
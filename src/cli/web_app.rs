@@ -25,7 +25,8 @@ use crate::{
     },
     templates::ScaffoldedTemplate,
     utils::{
-        check_no_whitespace, input_no_whitespace, input_with_case_and_initial_text, input_yes_or_no,
+        check_interactive, check_no_whitespace, input_no_whitespace,
+        input_with_case_and_initial_text, input_yes_or_no,
     },
 };
 
@@ -55,14 +56,22 @@ pub struct WebApp {
 }
 
 impl WebApp {
-    pub async fn run(self, template_type: &TemplateType) -> anyhow::Result<()> {
+    pub async fn run(
+        self,
+        template_type: &TemplateType,
+        dry_run: bool,
+        no_interactive: bool,
+    ) -> anyhow::Result<()> {
         let current_dir = std::env::current_dir()?;
         let name = match self.name {
             Some(n) => {
                 check_no_whitespace(&n, "app name")?;
                 n
             }
-            None => input_no_whitespace("App name (no whitespaces):")?,
+            None => {
+                check_interactive("the `<name>` argument", no_interactive)?;
+                input_no_whitespace("App name (no whitespaces):", no_interactive)?
+            }
         };
 
         let app_folder = current_dir.join(&name);
@@ -75,20 +84,27 @@ impl WebApp {
 
         template_type.check_valid_template()?;
 
-        let setup_nix = if self.setup_nix {
+        // Without a prompt, the holonix environment is only set up when asked for
+        let setup_nix = if self.setup_nix || no_interactive {
             self.setup_nix
         } else {
             input_yes_or_no(
                 "Do you want to set up the holonix development environment for this project?",
                 Some(true),
+                no_interactive,
             )?
         };
 
         let package_manager = match self.package_manager {
             Some(p) => p,
-            None => PackageManager::choose()?,
+            None => PackageManager::choose(no_interactive)?,
         };
 
+        // The initial DNA can only be scaffolded interactively, so fail before writing anything
+        if !self.disable_fast_track {
+            check_interactive("the `--disable-fast-track` flag", no_interactive)?;
+        }
+
         let ScaffoldedTemplate {
             file_tree,
             next_instructions,
@@ -103,7 +119,11 @@ impl WebApp {
 
         let file_tree = ScaffoldConfig::write_to_package_json(file_tree, template_type)?;
 
-        build_file_tree(file_tree, &app_folder)?;
+        build_file_tree(file_tree, &app_folder, dry_run)?;
+
+        if dry_run {
+            return Ok(());
+        }
 
         let mut nix_instructions = "";
 
@@ -120,9 +140,18 @@ impl WebApp {
         let mut disable_fast_track = self.disable_fast_track;
 
         if !disable_fast_track
-            && input_yes_or_no("Do you want to scaffold an initial DNA? (y/n)", None)?
+            && input_yes_or_no(
+                "Do you want to scaffold an initial DNA? (y/n)",
+                None,
+                no_interactive,
+            )?
         {
-            WebApp::scaffold_initial_dna_and_zomes(&name, template_file_tree, &current_dir)?;
+            WebApp::scaffold_initial_dna_and_zomes(
+                &name,
+                template_file_tree,
+                &current_dir,
+                no_interactive,
+            )?;
         } else {
             disable_fast_track = true;
         }
@@ -169,25 +198,38 @@ Here's how you can get started with developing your application:
         name: &str,
         template_file_tree: FileTree,
         path: &Path,
+        no_interactive: bool,
     ) -> ScaffoldResult<()> {
         env::set_current_dir(PathBuf::from(&name))?;
         let dna_name = input_with_case_and_initial_text(
             "Initial DNA name (snake_case):",
             Case::Snake,
             &name.to_case(Case::Snake),
+            no_interactive,
         )?;
 
         let file_tree = load_directory_into_memory(&path.join(name))?;
-        let app_file_tree = AppFileTree::get_or_choose(file_tree, Some(name))?;
+        let app_file_tree = AppFileTree::get_or_choose(file_tree, Some(name), no_interactive)?;
 
-        let ScaffoldedTemplate { file_tree, .. } =
-            scaffold_dna(app_file_tree, &template_file_tree, &dna_name)?;
+        let ScaffoldedTemplate { file_tree, .. } = scaffold_dna(
+            app_file_tree,
+            &template_file_tree,
+            &dna_name,
+            no_interactive,
+        )?;
 
-        if input_yes_or_no("Do you want to scaffold an initial coordinator/integrity zome pair for your DNA? (y/n)", None)? {
-            scaffold_zome_pair(file_tree, template_file_tree, &dna_name)?;
+        // Dry runs never get here, as the initial DNA is scaffolded in the web-app written to disk
+        if input_yes_or_no(
+            "Do you want to scaffold an initial coordinator/integrity zome pair for your DNA? (y/n)",
+            None,
+            no_interactive,
+        )? {
+            let file_tree =
+                scaffold_zome_pair(file_tree, template_file_tree, &dna_name, no_interactive)?;
+            build_file_tree(file_tree, ".", false)?;
             println!("Coordinator/integrity zome pair scaffolded.")
         } else {
-            build_file_tree(file_tree, ".")?;
+            build_file_tree(file_tree, ".", false)?;
             println!("DNA scaffolded.");
         }
 
@@ -1,12 +1,11 @@
-use std::{ffi::OsString, path::PathBuf};
+use std::path::PathBuf;
 
-use build_fs_tree::{Build, MergeableFileSystemTree};
 use colored::Colorize;
 use convert_case::Case;
 use structopt::StructOpt;
 
 use crate::{
-    file_tree::load_directory_into_memory,
+    file_tree::{build_file_tree, load_directory_into_memory},
     scaffold::{
         app::cargo::exec_metadata,
         dna::DnaFileTree,
@@ -17,7 +16,7 @@ use crate::{
         },
     },
     templates::ScaffoldedTemplate,
-    utils::{check_case, input_with_case, run_cargo_fmt_if_available},
+    utils::{check_case, check_interactive, input_with_case, run_cargo_fmt_if_available},
 };
 
 #[derive(Debug, StructOpt)]
@@ -40,7 +39,12 @@ pub struct Zome {
 }
 
 impl Zome {
-    pub fn run(self, template_type: &TemplateType) -> anyhow::Result<()> {
+    pub fn run(
+        self,
+        template_type: &TemplateType,
+        dry_run: bool,
+        no_interactive: bool,
+    ) -> anyhow::Result<()> {
         let current_dir = std::env::current_dir()?;
         let file_tree = load_directory_into_memory(&current_dir)?;
         let template_file_tree = template_type.file_tree()?;
@@ -51,7 +55,7 @@ impl Zome {
 
         let (scaffold_integrity, scaffold_coordinator) = match (&self.integrity, &self.coordinator)
         {
-            (None, None) => select_scaffold_zome_options()?,
+            (None, None) => select_scaffold_zome_options(no_interactive)?,
             _ => (self.integrity.is_some(), self.coordinator.is_some()),
         };
 
@@ -63,10 +67,14 @@ impl Zome {
 
         let name = match self.name {
             Some(n) => n,
-            None => input_with_case(name_prompt, Case::Snake)?,
+            None => {
+                check_interactive("the `<name>` argument", no_interactive)?;
+                input_with_case(name_prompt, Case::Snake, no_interactive)?
+            }
         };
 
-        let mut dna_file_tree = DnaFileTree::get_or_choose(file_tree, self.dna.as_deref())?;
+        let mut dna_file_tree =
+            DnaFileTree::get_or_choose(file_tree, self.dna.as_deref(), no_interactive)?;
         let dna_manifest_path = dna_file_tree.dna_manifest_path.clone();
 
         let mut zome_next_instructions: (Option<String>, Option<String>) = Default::default();
@@ -85,6 +93,7 @@ impl Zome {
                 &template_file_tree,
                 &integrity_zome_name,
                 &self.integrity,
+                no_interactive,
             )?;
 
             zome_next_instructions.0 = next_instructions;
@@ -104,7 +113,7 @@ impl Zome {
                 } else {
                     let integrity_zomes = select_integrity_zomes(&dna_file_tree.dna_manifest, Some(
                       "Select integrity zome(s) this coordinator zome depends on (SPACE to select/unselect, ENTER to continue):"
-                    ))?;
+                    ), no_interactive)?;
                     Some(integrity_zomes)
                 }
             };
@@ -117,6 +126,7 @@ impl Zome {
                 &name,
                 dependencies.as_ref(),
                 &self.coordinator,
+                no_interactive,
             )?;
             zome_next_instructions.1 = next_instructions;
 
@@ -126,14 +136,13 @@ impl Zome {
         }
 
         // TODO: implement scaffold_zome_template
-        let file_tree =
-            MergeableFileSystemTree::<OsString, String>::from(dna_file_tree.file_tree());
+        let file_tree = dna_file_tree.file_tree();
 
         // FIXME: avoid cloning
         let f = file_tree.clone();
-        file_tree.build(&PathBuf::from("."))?;
+        build_file_tree(file_tree, ".", dry_run)?;
 
-        if let Err(e) = run_cargo_fmt_if_available() {
+        if let Err(e) = run_cargo_fmt_if_available(dry_run) {
             println!(
                 "{}: {}",
                 "rustfmt exec failed: ".yellow(),
@@ -142,7 +151,9 @@ impl Zome {
         }
 
         // Execute cargo metadata to set up the cargo workspace in case this zome is the first crate
-        exec_metadata(&f)?;
+        if !dry_run {
+            exec_metadata(&f)?;
+        }
 
         match zome_next_instructions {
             (Some(integrity), Some(coordinator)) => {
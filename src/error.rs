@@ -132,12 +132,27 @@ pub enum ScaffoldError {
     #[error("Entry type \"{0}\" was not found in dna \"{1}\" for the integrity zome \"{2}\"")]
     EntryTypeNotFound(String, String, String),
 
+    #[error("Field \"{0}\" already exists in entry type \"{1}\"")]
+    FieldAlreadyExists(String, String),
+
     #[error("Link type \"{0}\" already exists in dna \"{1}\" for the integrity zome \"{2}\"")]
     LinkTypeAlreadyExists(String, String, String),
 
+    #[error("Link type \"{0}\" was not found in dna \"{1}\" for the integrity zome \"{2}\"")]
+    LinkTypeNotFound(String, String, String),
+
+    #[error("Collection \"{0}\" was not found in dna \"{1}\" for the integrity zome \"{2}\"")]
+    CollectionNotFound(String, String, String),
+
+    #[error("\"{0}\" is still referenced in {1}, remove the code referencing it first")]
+    StillReferenced(String, PathBuf),
+
     #[error("Invalid arguments: \"{0}\"")]
     InvalidArguments(String),
 
+    #[error("Missing {0}, which can't be prompted for when running non-interactively (--no-interactive or CI=true)")]
+    MissingArgumentInNonInteractiveMode(String),
+
     #[error("Failed to build file tree: {0}")]
     FsBuildError(#[from] build_fs_tree::BuildError<PathBuf, io::Error>),
 
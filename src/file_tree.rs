@@ -1,5 +1,6 @@
 use anyhow::Context;
 use build_fs_tree::{dir, file, Build, FileSystemTree, MergeableFileSystemTree};
+use colored::Colorize;
 use ignore::WalkBuilder;
 use include_dir::Dir;
 use regex::Regex;
@@ -109,6 +110,21 @@ pub fn insert_file_tree_in_dir(
     Ok(())
 }
 
+pub fn remove_file(file_tree: &mut FileTree, file_path: &Path) -> ScaffoldResult<()> {
+    let mut folder_path = file_path.to_path_buf();
+    folder_path.pop();
+
+    let v: Vec<OsString> = folder_path.iter().map(|s| s.to_os_string()).collect();
+    file_tree
+        .path_mut(&mut v.iter())
+        .ok_or(ScaffoldError::PathNotFound(folder_path.clone()))?
+        .dir_content_mut()
+        .ok_or(ScaffoldError::PathNotFound(folder_path.clone()))?
+        .remove(file_path.file_name().unwrap_or_default())
+        .ok_or(ScaffoldError::PathNotFound(file_path.to_path_buf()))?;
+    Ok(())
+}
+
 pub fn find_files_by_name(file_tree: &FileTree, file_name: &Path) -> BTreeMap<PathBuf, String> {
     find_files(file_tree, &|file_path, _file_contents| {
         file_name.file_name().eq(&file_path.file_name())
@@ -395,8 +411,129 @@ fn walk_file_tree_rec(
     found_files
 }
 
-pub fn build_file_tree(file_tree: FileTree, path: impl Into<PathBuf>) -> Result<(), ScaffoldError> {
+/// Writes the file tree to the given path, or on dry runs only prints the changes it would make to the files in it
+pub fn build_file_tree(
+    file_tree: FileTree,
+    path: impl Into<PathBuf>,
+    dry_run: bool,
+) -> Result<(), ScaffoldError> {
+    let path = path.into();
+    if dry_run {
+        return print_file_tree_diff(&file_tree, &path, false);
+    }
     let mergeable_tree = MergeableFileSystemTree::from(file_tree);
-    mergeable_tree.build(&path.into())?;
+    mergeable_tree.build(&path)?;
+    Ok(())
+}
+
+/// Like `build_file_tree`, but also deletes from disk the files in the given path
+/// that are no longer present in the given file tree
+pub fn build_file_tree_with_deletions(
+    file_tree: FileTree,
+    path: impl Into<PathBuf>,
+    dry_run: bool,
+) -> ScaffoldResult<()> {
+    let path = path.into();
+    if dry_run {
+        return print_file_tree_diff(&file_tree, &path, true);
+    }
+
+    let existing_files = flatten_file_tree(&load_directory_into_memory(&path)?);
+    let new_files = flatten_file_tree(&file_tree);
+
+    build_file_tree(file_tree, &path, false)?;
+
+    for (file_path, contents) in existing_files {
+        if contents.is_some() && !new_files.contains_key(&file_path) {
+            fs::remove_file(path.join(file_path))?;
+        }
+    }
+
     Ok(())
 }
+
+/// Prints the files that are created or modified in the given path by the given file tree, with a unified diff
+/// of their contents, along with the files missing from the file tree if they are deleted when building it
+pub fn print_file_tree_diff(
+    file_tree: &FileTree,
+    path: &Path,
+    with_deletions: bool,
+) -> ScaffoldResult<()> {
+    let existing_file_tree = match path.exists() {
+        true => load_directory_into_memory(path)?,
+        false => dir! {},
+    };
+
+    let existing_files = flatten_file_tree(&existing_file_tree);
+    let new_files = flatten_file_tree(file_tree);
+
+    // Avoid printing "./" in front of every path when building in the current directory
+    let root = match path == Path::new(".") {
+        true => PathBuf::new(),
+        false => path.to_path_buf(),
+    };
+
+    let mut changes = 0;
+
+    for (file_path, contents) in &new_files {
+        let Some(new_contents) = contents else {
+            continue;
+        };
+        let display_path = root.join(file_path);
+        match existing_files.get(file_path) {
+            Some(Some(old_contents)) if old_contents == new_contents => {}
+            Some(Some(old_contents)) => {
+                println!("{} {}", "modified:".yellow(), display_path.display());
+                print_unified_diff(old_contents, new_contents, &display_path);
+                changes += 1;
+            }
+            _ => {
+                println!("{} {}", "created:".green(), display_path.display());
+                print_unified_diff("", new_contents, &display_path);
+                changes += 1;
+            }
+        }
+    }
+
+    if with_deletions {
+        for (file_path, contents) in &existing_files {
+            if let Some(old_contents) = contents {
+                if !new_files.contains_key(file_path) {
+                    let display_path = root.join(file_path);
+                    println!("{} {}", "deleted:".red(), display_path.display());
+                    print_unified_diff(old_contents, "", &display_path);
+                    changes += 1;
+                }
+            }
+        }
+    }
+
+    println!(
+        "\n{}",
+        format!("Dry run: {changes} file(s) would be changed, nothing was written").bold()
+    );
+
+    Ok(())
+}
+
+fn print_unified_diff(old_contents: &str, new_contents: &str, path: &Path) {
+    let path = path.to_string_lossy();
+    let diff = similar::TextDiff::from_lines(old_contents, new_contents)
+        .unified_diff()
+        .header(&format!("a/{path}"), &format!("b/{path}"))
+        .to_string();
+
+    for line in diff.lines() {
+        if line.starts_with("+++") || line.starts_with("---") {
+            println!("{}", line.bold());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else {
+            println!("{line}");
+        }
+    }
+}
@@ -109,6 +109,7 @@
 //! coordinator-zome/
 //! dna/
 //! entry-type/
+//! entry-type-field/
 //! example/
 //! field-types/
 //! collection/
@@ -140,6 +141,7 @@
 //! - `dna`: uses the `dna` folder. [Available data](`crate::templates::dna::ScaffoldDnaData`).
 //! - `zome`: uses the `coordinator-zome` folder if scaffolding a coordinator zome, and the `integrity-zome` folder if scaffolding an integrity zome. [Available data](`crate::templates::coordinator::ScaffoldCoordinatorZomeData`).
 //! - `entry-type`: uses the `entry-type` folder. [Available data](`crate::templates::entry_type::ScaffoldEntryTypeData`).
+//! - `entry-type add-field`: uses the `entry-type-field` folder, whose templates are expected to merge the new fields into the files previously generated by the `entry-type` folder. [Available data](`crate::templates::entry_type::ScaffoldEntryTypeFieldData`).
 //! - `link-type`: uses the `link-type` folder. [Available data](`crate::templates::link_type::ScaffoldLinkTypeData`).
 //! - `collection`: uses the `collection` folder. [Available data](`crate::templates::collection::ScaffoldCollectionData`).
 //! - `example`: uses the `example` folder. [Available data](`crate::templates::example::ScaffoldExampleData`).
@@ -268,6 +270,16 @@
 //!   {{/match_scope}}
 //! {{/merge}}
 //! ```
+//! - `match_line`: needs to be placed inside a `merge` helper block, and takes a fragment of the existing code as only argument, which may span multiple lines. It then replaces the lines containing that fragment with the contents of the `match_line` block, making them available in the `previous_line_content` variable:
+//!   - Example usage:
+//! ```hbs
+//! {{#merge previous_file_content}}
+//!   {{#match_line "<button type=\"submit\">"}}
+//!     <input name="newField" />
+//! {{previous_line_content}}
+//!   {{/match_line}}
+//! {{/merge}}
+//! ```
 
 pub mod cli;
 pub mod error;
@@ -1,9 +1,16 @@
 pub mod app;
+pub mod bridge_call;
+pub mod capability;
 pub mod collection;
 pub mod config;
 pub mod dna;
 pub mod entry_type;
 pub mod example;
+pub mod happ_spec;
+pub mod inspect;
 pub mod link_type;
+pub mod remove;
+pub mod rename;
+pub mod signal;
 pub mod web_app;
 pub mod zome;
@@ -7,6 +7,7 @@ use mr_bundle::Manifest;
 use crate::{
     error::{ScaffoldError, ScaffoldResult},
     file_tree::{find_files_by_name, FileTree},
+    utils::check_interactive,
 };
 
 pub mod cargo;
@@ -33,6 +34,7 @@ impl AppFileTree {
     pub fn get_or_choose(
         file_tree: FileTree,
         app_name: Option<&str>,
+        no_interactive: bool,
     ) -> ScaffoldResult<AppFileTree> {
         let app_manifests = find_app_manifests(&file_tree)?;
 
@@ -42,7 +44,7 @@ impl AppFileTree {
                 .into_iter()
                 .last()
                 .ok_or(ScaffoldError::AppManifestNotFound),
-            (_, None) => choose_app(app_manifests),
+            (_, None) => choose_app(app_manifests, no_interactive),
             (_, Some(name)) => app_manifests
                 .into_iter()
                 .find(|(_, m)| m.app_name().to_string().eq(name))
@@ -59,6 +61,7 @@ impl AppFileTree {
 
 pub fn choose_app(
     app_manifests: BTreeMap<PathBuf, AppManifest>,
+    no_interactive: bool,
 ) -> ScaffoldResult<(PathBuf, AppManifest)> {
     let manifest_vec: Vec<(PathBuf, AppManifest)> = app_manifests.into_iter().collect();
     let app_names: Vec<String> = manifest_vec
@@ -66,6 +69,7 @@ pub fn choose_app(
         .map(|(_, m)| m.app_name().to_string())
         .collect();
 
+    check_interactive("the `--app` option", no_interactive)?;
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("Multiple apps were found in this repository, choose one:")
         .default(0)
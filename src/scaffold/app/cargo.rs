@@ -4,8 +4,10 @@ use std::{
     str::from_utf8,
 };
 
-use crate::file_tree::{file_content, insert_file, FileTree};
+use crate::file_tree::{dir_content, file_content, file_exists, insert_file, map_file, FileTree};
 use cargo_metadata::{Metadata, MetadataCommand};
+use regex::Regex;
+use toml_edit::{DocumentMut, InlineTable};
 
 use crate::error::{ScaffoldError, ScaffoldResult};
 
@@ -58,56 +60,119 @@ pub fn add_workspace_path_dependency(
     add_workspace_dependency(app_file_tree, crate_name, &toml::Value::Table(table))
 }
 
+/// Adds a dependency inherited from the workspace to the given crate, unless it already depends on it
+pub fn add_crate_workspace_dependency(
+    mut app_file_tree: FileTree,
+    crate_path: &Path,
+    crate_name: &str,
+) -> ScaffoldResult<FileTree> {
+    let cargo_toml_path = crate_path.join("Cargo.toml");
+
+    map_file(&mut app_file_tree, &cargo_toml_path, |contents| {
+        let mut cargo_toml = contents
+            .parse::<DocumentMut>()
+            .map_err(|e| ScaffoldError::MalformedFile(cargo_toml_path.clone(), e.to_string()))?;
+
+        let dependencies = cargo_toml
+            .entry("dependencies")
+            .or_insert(toml_edit::table())
+            .as_table_like_mut()
+            .ok_or(ScaffoldError::MalformedFile(
+                cargo_toml_path.clone(),
+                String::from("dependencies is not a table"),
+            ))?;
+
+        if !dependencies.contains_key(crate_name) {
+            let mut dependency = InlineTable::new();
+            dependency.insert("workspace", true.into());
+            dependencies.insert(crate_name, toml_edit::value(dependency));
+        }
+
+        Ok(cargo_toml.to_string())
+    })?;
+
+    Ok(app_file_tree)
+}
+
+/// Paths of the Cargo.toml files of the members of the workspace
+///
+/// The members are resolved in the given file tree rather than with `cargo metadata`, so that the crates
+/// scaffolded in the same run are found before they are written to disk
 pub fn get_workspace_packages_locations(
     app_file_tree: &FileTree,
 ) -> ScaffoldResult<Option<Vec<PathBuf>>> {
-    let current_dir = std::env::current_dir()?;
+    let Ok(members) = get_workspace_members(app_file_tree) else {
+        return Ok(None);
+    };
 
-    let path = current_dir
-        .join(workspace_cargo_toml_path(app_file_tree))
-        .canonicalize()?;
-    let command_result = MetadataCommand::new().manifest_path(path).exec();
+    let packages_paths = members
+        .iter()
+        .flat_map(|member| workspace_member_dirs(app_file_tree, member))
+        .map(|dir| dir.join("Cargo.toml"))
+        .filter(|path| file_exists(app_file_tree, path))
+        .collect();
+
+    Ok(Some(packages_paths))
+}
+
+/// Folders of the file tree matched by the given workspace member, whose components can contain `*` and `?`
+fn workspace_member_dirs(app_file_tree: &FileTree, member: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::new()];
 
-    match command_result {
-        Ok(metadata) => {
-            let packages_paths: Vec<PathBuf> = metadata
-                .workspace_packages()
+    for component in Path::new(member).iter() {
+        let component = component.to_string_lossy();
+        if !component.contains(['*', '?']) {
+            dirs = dirs
                 .into_iter()
-                .map(|p| {
-                    PathBuf::from(p.manifest_path.as_std_path())
-                        .iter()
-                        .skip(current_dir.components().count())
-                        .collect()
-                })
+                .map(|dir| dir.join(component.as_ref()))
                 .collect();
-            Ok(Some(packages_paths))
+            continue;
         }
-        Err(_) => Ok(None),
+
+        let re = Regex::new(&format!(
+            r"\A{}\z",
+            regex::escape(&component)
+                .replace(r"\*", ".*")
+                .replace(r"\?", ".")
+        ))
+        .expect("Failed to build the workspace member regex");
+        dirs = dirs
+            .into_iter()
+            .flat_map(|dir| {
+                dir_content(app_file_tree, &dir)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|(name, tree)| {
+                        tree.dir_content().is_some() && re.is_match(&name.to_string_lossy())
+                    })
+                    .map(move |(name, _)| dir.join(name))
+            })
+            .collect();
     }
+
+    dirs
 }
 
+/// Path of the Cargo.toml of the member of the workspace with the given crate name
 pub fn workspace_package_path(
     app_file_tree: &FileTree,
     crate_name: &str,
 ) -> ScaffoldResult<Option<PathBuf>> {
-    let current_dir = std::env::current_dir()?;
+    for manifest_path in get_workspace_packages_locations(app_file_tree)?.unwrap_or_default() {
+        let cargo_toml: toml::Value =
+            toml::from_str(file_content(app_file_tree, &manifest_path)?.as_str())?;
 
-    let path = current_dir
-        .join(workspace_cargo_toml_path(app_file_tree))
-        .canonicalize()?;
-    let metadata = MetadataCommand::new().manifest_path(path).exec()?;
-
-    let package_path: Option<PathBuf> = metadata
-        .workspace_packages()
-        .into_iter()
-        .find(|p| p.name.eq(crate_name))
-        .map(|p| {
-            PathBuf::from(p.manifest_path.as_std_path())
-                .iter()
-                .skip(current_dir.components().count())
-                .collect()
-        });
-    Ok(package_path)
+        if cargo_toml
+            .get("package")
+            .and_then(|package| package.get("name"))
+            .and_then(|name| name.as_str())
+            == Some(crate_name)
+        {
+            return Ok(Some(manifest_path));
+        }
+    }
+
+    Ok(None)
 }
 
 pub fn get_workspace_members(app_file_tree: &FileTree) -> ScaffoldResult<Vec<String>> {
@@ -231,3 +296,54 @@ fn add_workspace_dependency(
 fn workspace_cargo_toml_path(_app_file_tree: &FileTree) -> PathBuf {
     PathBuf::new().join("Cargo.toml")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use build_fs_tree::{dir, file};
+
+    fn zome_file_tree(cargo_toml: &str) -> FileTree {
+        dir! {
+            "Cargo.toml" => file!(cargo_toml)
+        }
+    }
+
+    #[test]
+    fn test_add_crate_workspace_dependency() {
+        let file_tree = zome_file_tree(
+            r#"[package]
+name = "posts"
+
+[dependencies] # the dependencies of the zome
+hdk = { workspace = true }
+"#,
+        );
+        let file_tree = add_crate_workspace_dependency(file_tree, Path::new(""), "regex").unwrap();
+        assert_eq!(
+            file_content(&file_tree, Path::new("Cargo.toml")).unwrap(),
+            r#"[package]
+name = "posts"
+
+[dependencies] # the dependencies of the zome
+hdk = { workspace = true }
+regex = { workspace = true }
+"#
+        );
+
+        let file_tree = add_crate_workspace_dependency(file_tree, Path::new(""), "regex").unwrap();
+        assert_eq!(
+            file_content(&file_tree, Path::new("Cargo.toml"))
+                .unwrap()
+                .matches("regex")
+                .count(),
+            1
+        );
+
+        let file_tree = zome_file_tree("[package]\nname = \"posts\"\n");
+        let file_tree = add_crate_workspace_dependency(file_tree, Path::new(""), "hdk").unwrap();
+        assert_eq!(
+            file_content(&file_tree, Path::new("Cargo.toml")).unwrap(),
+            "[package]\nname = \"posts\"\n\n[dependencies]\nhdk = { workspace = true }\n"
+        );
+    }
+}
@@ -15,6 +15,7 @@ use super::{choose_app, find_app_manifests};
 pub fn get_or_choose_app_manifest_path_for_dna_manifest(
     app_file_tree: &FileTree,
     dna_manifest_path: &Path,
+    no_interactive: bool,
 ) -> ScaffoldResult<PathBuf> {
     let dna_manifest = read_dna_manifest(app_file_tree, dna_manifest_path)?;
 
@@ -37,7 +38,7 @@ pub fn get_or_choose_app_manifest_path_for_dna_manifest(
             .into_iter()
             .last()
             .ok_or(ScaffoldError::NoAppsFoundForDna(dna_manifest.name())),
-        _ => choose_app(apps_for_dna),
+        _ => choose_app(apps_for_dna, no_interactive),
     }?;
 
     Ok(path)
@@ -68,6 +69,33 @@ fn bundled_dnas_paths(
     Ok(dna_paths)
 }
 
+/// Name of the role of the given app whose DNA bundle is built in the workdir of the given DNA manifest
+pub fn role_for_dna(
+    app_file_tree: &FileTree,
+    app_manifest_path: &Path,
+    dna_manifest_path: &Path,
+) -> ScaffoldResult<Option<String>> {
+    let app_manifest = read_app_manifest(app_file_tree, app_manifest_path)?;
+
+    let mut app_workdir_location = app_manifest_path.to_path_buf();
+    app_workdir_location.pop();
+    let mut dna_workdir_location = dna_manifest_path.to_path_buf();
+    dna_workdir_location.pop();
+
+    Ok(app_manifest
+        .app_roles()
+        .into_iter()
+        .find(|app_role| match &app_role.dna.location {
+            Some(Location::Bundled(bundled_location)) => {
+                let mut bundled_location = app_workdir_location.join(bundled_location);
+                bundled_location.pop();
+                bundled_location.clean() == dna_workdir_location.clean()
+            }
+            _ => false,
+        })
+        .map(|app_role| app_role.name))
+}
+
 fn read_app_manifest(
     app_file_tree: &FileTree,
     app_manifest_path: &Path,
@@ -0,0 +1,436 @@
+use std::{collections::BTreeSet, ffi::OsString, path::Path};
+
+use convert_case::{Case, Casing};
+use dialoguer::{theme::ColorfulTheme, Select};
+use holochain_types::prelude::ZomeManifest;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::ItemFn;
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::{
+        file_exists, find_map_rust_files, insert_file, map_file, map_rust_files, FileTree,
+    },
+    utils::check_interactive,
+};
+
+use super::{
+    app::cargo::add_crate_workspace_dependency,
+    zome::{
+        coordinator::{
+            extern_output_type, find_all_extern_functions, find_extern_function_in_zomes,
+            signature_type_idents, use_tree_leaves, HOLOCHAIN_CALLBACKS,
+        },
+        ZomeFileTree,
+    },
+};
+
+/// Module of the coordinator zome with the functions that call the extern functions of other zomes of its cell
+const BRIDGE_CALLS_MODULE: &str = "bridge_calls";
+
+/// Prompts a Select dialog to select the extern function of the zome to call
+pub fn choose_function(
+    zome_file_tree: &ZomeFileTree,
+    no_interactive: bool,
+) -> ScaffoldResult<String> {
+    let mut function_names: Vec<String> = find_all_extern_functions(zome_file_tree)?
+        .into_iter()
+        .map(|item_fn| item_fn.sig.ident.to_string())
+        .filter(|name| !HOLOCHAIN_CALLBACKS.contains(&name.as_str()))
+        .collect();
+    function_names.sort();
+
+    if function_names.is_empty() {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "The coordinator zome {} has no functions to call",
+            zome_file_tree.zome_manifest.name.0
+        )));
+    }
+
+    check_interactive("the `--fn` option", no_interactive)?;
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Which function should be called?")
+        .default(0)
+        .items(&function_names)
+        .interact()?;
+
+    Ok(function_names[selection].clone())
+}
+
+/// Adds a function to the `from` coordinator zome that calls the given extern function of the `to` coordinator
+/// zome in the same cell, with its input and output types. The integrity crates that the `to` zome depends on
+/// are added as dependencies of the `from` crate, so the types of the function must come from them
+pub fn scaffold_bridge_call(
+    from_zome_file_tree: ZomeFileTree,
+    to_zome_manifest: &ZomeManifest,
+    fn_name: &str,
+) -> ScaffoldResult<FileTree> {
+    let from_zome_name = from_zome_file_tree.zome_manifest.name.0.to_string();
+    let to_zome_name = to_zome_manifest.name.0.to_string();
+
+    if from_zome_name == to_zome_name {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "The zome {from_zome_name} can call its own function {fn_name} directly"
+        )));
+    }
+    if HOLOCHAIN_CALLBACKS.contains(&fn_name) {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "{fn_name} is a callback, and can only be called by the conductor"
+        )));
+    }
+
+    let Some((_, item_fn)) = find_extern_function_in_zomes(
+        &from_zome_file_tree.dna_file_tree,
+        &vec![to_zome_manifest.clone()],
+        fn_name,
+    )?
+    else {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "The coordinator zome {to_zome_name} has no extern function named {fn_name}"
+        )));
+    };
+
+    let to_zome_file_tree = ZomeFileTree::from_zome_manifest(
+        from_zome_file_tree.dna_file_tree.clone(),
+        to_zome_manifest.clone(),
+    )?;
+    check_signature_types_are_shared(&to_zome_file_tree, &item_fn)?;
+
+    let wrapper_name = format!("call_{}_{fn_name}", to_zome_name.to_case(Case::Snake));
+    let wrapper: syn::Item =
+        syn::parse2(bridge_call_handler(&wrapper_name, &to_zome_name, &item_fn)?)?;
+
+    let integrity_dependencies: Vec<String> = to_zome_manifest
+        .dependencies
+        .iter()
+        .flatten()
+        .map(|dependency| dependency.name.0.to_string())
+        .collect();
+    let imports = integrity_dependencies
+        .iter()
+        .map(|dependency| -> ScaffoldResult<syn::Item> {
+            let dependency: syn::Path = syn::parse_str(dependency)?;
+            Ok(syn::parse_quote! { use #dependency::*; })
+        })
+        .collect::<ScaffoldResult<Vec<syn::Item>>>()?;
+
+    let from_crate_path = from_zome_file_tree.zome_crate_path.clone();
+    let crate_src_path = from_crate_path.join("src");
+    let mut file_tree = from_zome_file_tree.dna_file_tree.file_tree();
+
+    for dependency in &integrity_dependencies {
+        file_tree = add_crate_workspace_dependency(file_tree, &from_crate_path, dependency)?;
+    }
+
+    let bridge_calls_path = crate_src_path.join(format!("{BRIDGE_CALLS_MODULE}.rs"));
+    if !file_exists(&file_tree, &bridge_calls_path) {
+        insert_file(&mut file_tree, &bridge_calls_path, "use hdk::prelude::*;\n")?;
+        map_file(&mut file_tree, &crate_src_path.join("lib.rs"), |contents| {
+            Ok(format!("pub mod {BRIDGE_CALLS_MODULE};\n{contents}"))
+        })?;
+    }
+
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        |file_path, mut file| {
+            if file_path != Path::new(&format!("{BRIDGE_CALLS_MODULE}.rs")) {
+                return Ok(file);
+            }
+
+            if file
+                .items
+                .iter()
+                .any(|item| matches!(item, syn::Item::Fn(f) if f.sig.ident == wrapper_name))
+            {
+                return Err(ScaffoldError::InvalidArguments(format!(
+                    "The function {fn_name} of the zome {to_zome_name} is already called by the zome {from_zome_name}"
+                )));
+            }
+
+            for import in &imports {
+                let import_str = import.to_token_stream().to_string();
+                if !file
+                    .items
+                    .iter()
+                    .any(|item| item.to_token_stream().to_string() == import_str)
+                {
+                    let index = file
+                        .items
+                        .iter()
+                        .take_while(|item| matches!(item, syn::Item::Use(_)))
+                        .count();
+                    file.items.insert(index, import.clone());
+                }
+            }
+
+            file.items.push(wrapper.clone());
+
+            Ok(file)
+        },
+    )?;
+
+    Ok(file_tree)
+}
+
+/// Checks that the types of the signature of the extern function don't come from its coordinator crate, since
+/// the calling zome only depends on the integrity crates of the called zome
+fn check_signature_types_are_shared(
+    to_zome_file_tree: &ZomeFileTree,
+    item_fn: &ItemFn,
+) -> ScaffoldResult<()> {
+    let crate_src_path = to_zome_file_tree.zome_crate_path.join("src");
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    let mut src_path = v.iter();
+    let src_file_tree = to_zome_file_tree
+        .dna_file_tree
+        .file_tree_ref()
+        .path(&mut src_path)
+        .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?;
+
+    let fn_file = find_map_rust_files(src_file_tree, &|_file_path, file| {
+        file.items
+            .iter()
+            .any(|item| matches!(item, syn::Item::Fn(f) if f.sig.ident == item_fn.sig.ident))
+            .then(|| file.clone())
+    })
+    .into_values()
+    .next()
+    .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?;
+    let crate_types: BTreeSet<String> = find_map_rust_files(src_file_tree, &|_file_path, file| {
+        Some(type_names_defined_in_file(file))
+    })
+    .into_values()
+    .flatten()
+    .collect();
+
+    match coordinator_signature_type(item_fn, &fn_file, &crate_types)? {
+        Some(ident) => Err(ScaffoldError::InvalidArguments(format!(
+            "The type {ident} of the function {} is defined in the coordinator zome {}, which other zomes can't depend on: move it to one of the integrity zomes it depends on to call the function from other zomes",
+            item_fn.sig.ident, to_zome_file_tree.zome_manifest.name.0
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// The first type of the signature of the extern function that comes from its own crate, given the file where
+/// it is defined and the names of the types defined in its crate
+///
+/// The types defined in the file or imported by name from the crate come from it, and so do the types
+/// defined anywhere in the crate when the file glob imports one of its modules
+fn coordinator_signature_type(
+    item_fn: &ItemFn,
+    fn_file: &syn::File,
+    crate_types: &BTreeSet<String>,
+) -> ScaffoldResult<Option<syn::Ident>> {
+    let file_types = type_names_defined_in_file(fn_file);
+    let use_leaves: Vec<(Vec<syn::Ident>, syn::UseTree)> = fn_file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Use(item_use) => Some(use_tree_leaves(&item_use.tree, vec![])),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    let from_crate = |segments: &[syn::Ident]| {
+        segments
+            .first()
+            .is_some_and(|s| s == "crate" || s == "self" || s == "super")
+    };
+    let glob_imports_crate_modules = use_leaves
+        .iter()
+        .any(|(segments, leaf)| matches!(leaf, syn::UseTree::Glob(_)) && from_crate(segments));
+
+    for ident in signature_type_idents(item_fn)? {
+        let name = ident.to_string();
+        if file_types.contains(&name) {
+            return Ok(Some(ident));
+        }
+        let imported_from = use_leaves.iter().find_map(|(segments, leaf)| match leaf {
+            syn::UseTree::Name(use_name) if use_name.ident == ident => Some(segments),
+            syn::UseTree::Rename(use_rename) if use_rename.rename == ident => Some(segments),
+            _ => None,
+        });
+        let from_this_crate = match imported_from {
+            Some(segments) => from_crate(segments),
+            None => glob_imports_crate_modules && crate_types.contains(&name),
+        };
+        if from_this_crate {
+            return Ok(Some(ident));
+        }
+    }
+
+    Ok(None)
+}
+
+/// The names of the structs, enums and type aliases defined in the file
+fn type_names_defined_in_file(file: &syn::File) -> BTreeSet<String> {
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Struct(item_struct) => Some(item_struct.ident.to_string()),
+            syn::Item::Enum(item_enum) => Some(item_enum.ident.to_string()),
+            syn::Item::Type(item_type) => Some(item_type.ident.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A function calling the given extern function of the given zome in the same cell, with the same types
+fn bridge_call_handler(
+    wrapper_name: &str,
+    to_zome_name: &str,
+    item_fn: &ItemFn,
+) -> ScaffoldResult<TokenStream> {
+    let function_name = format_ident!("{wrapper_name}");
+    let fn_name = item_fn.sig.ident.to_string();
+    let output_type = extern_output_type(item_fn)?;
+    let input = item_fn.sig.inputs.first().and_then(|arg| match arg {
+        syn::FnArg::Typed(pat_type) => {
+            let param = match pat_type.pat.as_ref() {
+                syn::Pat::Ident(pat_ident) => pat_ident.ident.clone(),
+                _ => format_ident!("input"),
+            };
+            Some((param, pat_type.ty.clone()))
+        }
+        syn::FnArg::Receiver(_) => None,
+    });
+    let (params, payload) = match input {
+        Some((param, input_type)) => (quote! { #param: #input_type }, quote! { #param }),
+        None => (quote! {}, quote! { () }),
+    };
+    let doc = format!(" Calls {fn_name} in the {to_zome_name} zome of this cell");
+    let error_message = format!("Failed to call {fn_name} in the {to_zome_name} zome");
+
+    Ok(quote! {
+        #[doc = #doc]
+        pub fn #function_name(#params) -> ExternResult<#output_type> {
+            let response = call(
+                CallTargetCell::Local,
+                ZomeName::from(#to_zome_name),
+                FunctionName::from(#fn_name),
+                None,
+                #payload,
+            )?;
+            match response {
+                ZomeCallResponse::Ok(result) => result.decode().map_err(|e| wasm_error!(e)),
+                _ => Err(wasm_error!(WasmErrorInner::Guest(format!("{}: {:?}", #error_message, response)))),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bridge_call_handler() {
+        let item_fn: ItemFn = syn::parse_quote! {
+            #[hdk_extern]
+            pub fn get_comments_for_post(post_hash: ActionHash) -> ExternResult<Vec<Link>> {
+                todo!()
+            }
+        };
+        let wrapper: syn::ItemFn = syn::parse2(
+            bridge_call_handler("call_comments_get_comments_for_post", "comments", &item_fn)
+                .unwrap(),
+        )
+        .unwrap();
+        let wrapper = wrapper.to_token_stream().to_string();
+
+        assert!(wrapper.contains("pub fn call_comments_get_comments_for_post (post_hash : ActionHash) -> ExternResult < Vec < Link > >"));
+        assert!(wrapper.contains("call (CallTargetCell :: Local , ZomeName :: from (\"comments\") , FunctionName :: from (\"get_comments_for_post\") , None , post_hash ,) ?"));
+
+        let item_fn: ItemFn = syn::parse_quote! {
+            #[hdk_extern]
+            pub fn get_all_posts() -> ExternResult<Vec<Link>> {
+                todo!()
+            }
+        };
+        let wrapper: syn::ItemFn = syn::parse2(
+            bridge_call_handler("call_posts_get_all_posts", "posts", &item_fn).unwrap(),
+        )
+        .unwrap();
+        let wrapper = wrapper.to_token_stream().to_string();
+        assert!(
+            wrapper.contains("pub fn call_posts_get_all_posts () -> ExternResult < Vec < Link > >")
+        );
+        assert!(wrapper.contains("None , () ,) ?"));
+    }
+
+    #[test]
+    fn test_coordinator_signature_type() {
+        let fn_file: syn::File = syn::parse_quote! {
+            use hdk::prelude::*;
+            use posts_integrity::*;
+            use crate::pagination::Page;
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct UpdatePostInput {
+                pub original_post_hash: ActionHash,
+                pub updated_post: Post,
+            }
+
+            #[hdk_extern]
+            pub fn get_post(post_hash: ActionHash) -> ExternResult<Option<Post>> {
+                todo!()
+            }
+
+            #[hdk_extern]
+            pub fn update_post(input: UpdatePostInput) -> ExternResult<Record> {
+                todo!()
+            }
+
+            #[hdk_extern]
+            pub fn get_posts_page(page: u32) -> ExternResult<Page<Record>> {
+                todo!()
+            }
+        };
+        let extern_fn = |name: &str| {
+            fn_file
+                .items
+                .iter()
+                .find_map(|item| match item {
+                    syn::Item::Fn(item_fn) if item_fn.sig.ident == name => Some(item_fn.clone()),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        let crate_types: BTreeSet<String> = ["UpdatePostInput", "Page", "PostsByAuthor"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let coordinator_type = |name: &str, file: &syn::File| {
+            coordinator_signature_type(&extern_fn(name), file, &crate_types)
+                .unwrap()
+                .map(|ident| ident.to_string())
+        };
+
+        // Post comes from the integrity crate, and the rest from the hdk prelude
+        assert_eq!(coordinator_type("get_post", &fn_file), None);
+        assert_eq!(
+            coordinator_type("update_post", &fn_file),
+            Some(String::from("UpdatePostInput"))
+        );
+        assert_eq!(
+            coordinator_type("get_posts_page", &fn_file),
+            Some(String::from("Page"))
+        );
+
+        // Types of the crate that are glob imported from one of its modules
+        let glob_file: syn::File = syn::parse_quote! {
+            use hdk::prelude::*;
+            use crate::pagination::*;
+        };
+        assert_eq!(
+            coordinator_type("get_posts_page", &glob_file),
+            Some(String::from("Page"))
+        );
+        assert_eq!(coordinator_type("get_post", &glob_file), None);
+    }
+}
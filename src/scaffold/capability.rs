@@ -0,0 +1,902 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use convert_case::{Case, Casing};
+use dialoguer::{theme::ColorfulTheme, MultiSelect, Select};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    visit::{self, Visit},
+    ItemFn,
+};
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::{
+        file_exists, find_map_rust_files, insert_file, map_file, map_rust_files, FileTree,
+    },
+    reserved_words::check_for_reserved_keywords,
+    utils::{check_case, check_interactive},
+};
+
+use super::zome::{
+    coordinator::{
+        extern_output_type, find_all_extern_functions, signature_type_idents, use_tree_leaves,
+        HOLOCHAIN_CALLBACKS,
+    },
+    module_path, ZomeFileTree,
+};
+
+/// Module of the coordinator zome with the grants, claims and remote calls of its capabilities
+const CAPABILITIES_MODULE: &str = "capabilities";
+
+/// Who can call the functions granted by a capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapabilityAccess {
+    /// Any agent can call the functions, the grant is created in `init`
+    Unrestricted,
+    /// Any agent that has the secret of the grant can call the functions
+    Transferable,
+    /// Only the agents the grant was assigned to can call the functions, with the secret of the grant
+    Assigned,
+}
+
+impl CapabilityAccess {
+    pub fn choose(no_interactive: bool) -> ScaffoldResult<Self> {
+        check_interactive("the `--access` option", no_interactive)?;
+        let options = [
+            CapabilityAccess::Unrestricted,
+            CapabilityAccess::Transferable,
+            CapabilityAccess::Assigned,
+        ];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Which agents should be able to call the functions of this capability?")
+            .default(0)
+            .items(&[
+                "Unrestricted (any agent)",
+                "Transferable (any agent with the secret of the grant)",
+                "Assigned (only the agents the grant is assigned to, with its secret)",
+            ])
+            .interact()?;
+
+        Ok(options[selection])
+    }
+}
+
+impl FromStr for CapabilityAccess {
+    type Err = ScaffoldError;
+
+    fn from_str(access_str: &str) -> Result<Self, Self::Err> {
+        match access_str {
+            "unrestricted" => Ok(CapabilityAccess::Unrestricted),
+            "transferable" => Ok(CapabilityAccess::Transferable),
+            "assigned" => Ok(CapabilityAccess::Assigned),
+            _ => Err(ScaffoldError::InvalidArguments(format!(
+                "Invalid access \"{access_str}\": only \"unrestricted\", \"transferable\" and \"assigned\" are allowed"
+            ))),
+        }
+    }
+}
+
+/// Prompts a MultiSelect dialog to select the extern functions of the zome that the capability grants
+pub fn choose_functions(
+    zome_file_tree: &ZomeFileTree,
+    no_interactive: bool,
+) -> ScaffoldResult<Vec<String>> {
+    let mut function_names: Vec<String> = find_all_extern_functions(zome_file_tree)?
+        .into_iter()
+        .map(|item_fn| item_fn.sig.ident.to_string())
+        .filter(|name| !HOLOCHAIN_CALLBACKS.contains(&name.as_str()))
+        .collect();
+    function_names.sort();
+
+    if function_names.is_empty() {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "The coordinator zome {} has no functions to grant",
+            zome_file_tree.zome_manifest.name.0
+        )));
+    }
+
+    check_interactive("the `--functions` option", no_interactive)?;
+    let selections = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Which functions should this capability grant (SPACE to select/unselect, ENTER to continue)?")
+        .items(&function_names)
+        .interact()?;
+
+    if selections.is_empty() {
+        return Err(ScaffoldError::InvalidArguments(String::from(
+            "A capability must grant at least one function",
+        )));
+    }
+
+    Ok(selections
+        .into_iter()
+        .map(|i| function_names[i].clone())
+        .collect())
+}
+
+/// Scaffolds the capability grant for the given functions of the coordinator zome, the claims of its secret for
+/// transferable and assigned grants, and the functions that call each granted function in the cell of another agent
+pub fn scaffold_capability(
+    zome_file_tree: ZomeFileTree,
+    capability_name: &str,
+    access: CapabilityAccess,
+    functions: &[String],
+) -> ScaffoldResult<FileTree> {
+    check_case(capability_name, "capability name", Case::Snake)?;
+    check_for_reserved_keywords(capability_name)?;
+
+    if functions.is_empty() {
+        return Err(ScaffoldError::InvalidArguments(String::from(
+            "A capability must grant at least one function",
+        )));
+    }
+
+    let extern_functions = find_all_extern_functions(&zome_file_tree)?;
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    let mut src_path = v.iter();
+    let src_file_tree = zome_file_tree
+        .dna_file_tree
+        .file_tree_ref()
+        .path(&mut src_path)
+        .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?;
+
+    let mut granted_functions: Vec<(ItemFn, PathBuf)> = Vec::new();
+    for fn_name in functions {
+        // Callbacks are called by the conductor, and can't be granted to other agents
+        if HOLOCHAIN_CALLBACKS.contains(&fn_name.as_str()) {
+            return Err(ScaffoldError::InvalidArguments(format!(
+                "{fn_name} is a callback, and can't be granted to other agents"
+            )));
+        }
+        let Some(item_fn) = extern_functions.iter().find(|f| f.sig.ident == fn_name) else {
+            return Err(ScaffoldError::InvalidArguments(format!(
+                "The coordinator zome {} has no extern function named {fn_name}",
+                zome_file_tree.zome_manifest.name.0
+            )));
+        };
+        let file_path = find_map_rust_files(src_file_tree, &|_file_path, file| {
+            file.items
+                .iter()
+                .any(|item| matches!(item, syn::Item::Fn(f) if f.sig.ident == fn_name))
+                .then_some(())
+        })
+        .into_keys()
+        .next()
+        .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?;
+
+        granted_functions.push((item_fn.clone(), file_path));
+    }
+
+    let mut new_functions: Vec<String> = granted_functions
+        .iter()
+        .map(|(f, _)| call_remote_name(&f.sig.ident.to_string()))
+        .collect();
+    if access != CapabilityAccess::Unrestricted {
+        new_functions.extend([
+            format!("create_{capability_name}_grant"),
+            format!("revoke_{capability_name}_grant"),
+            format!("create_{capability_name}_claim"),
+            format!("revoke_{capability_name}_claim"),
+        ]);
+    }
+    if let Some(existing) = new_functions
+        .iter()
+        .find(|name| extern_functions.iter().any(|f| f.sig.ident == name))
+    {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "The coordinator zome {} already has a function named {existing}",
+            zome_file_tree.zome_manifest.name.0
+        )));
+    }
+
+    // The signatures of the granted functions may use types imported or defined in their module
+    let mut imports: Vec<syn::Item> = Vec::new();
+    for (item_fn, file_path) in &granted_functions {
+        let file = find_map_rust_files(src_file_tree, &|path, file| {
+            (path == file_path).then(|| file.clone())
+        })
+        .into_values()
+        .next()
+        .ok_or(ScaffoldError::PathNotFound(crate_src_path.join(file_path)))?;
+        imports.extend(signature_type_imports(item_fn, file_path, &file)?);
+    }
+
+    let capability_code: syn::File = syn::parse2(capability_handlers(
+        capability_name,
+        access,
+        &granted_functions
+            .iter()
+            .map(|(f, _)| f.clone())
+            .collect::<Vec<ItemFn>>(),
+    )?)?;
+
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    let capabilities_path = crate_src_path.join(format!("{CAPABILITIES_MODULE}.rs"));
+    if !file_exists(&file_tree, &capabilities_path) {
+        insert_file(&mut file_tree, &capabilities_path, "use hdk::prelude::*;\n")?;
+        map_file(&mut file_tree, &crate_src_path.join("lib.rs"), |contents| {
+            Ok(format!("pub mod {CAPABILITIES_MODULE};\n{contents}"))
+        })?;
+    }
+
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        |file_path, mut file| {
+            if file_path == Path::new(&format!("{CAPABILITIES_MODULE}.rs")) {
+                for import in &imports {
+                    let import_str = import.to_token_stream().to_string();
+                    let already_imported = file
+                        .items
+                        .iter()
+                        .any(|item| item.to_token_stream().to_string() == import_str);
+                    if !already_imported {
+                        let index = file
+                            .items
+                            .iter()
+                            .take_while(|item| matches!(item, syn::Item::Use(_)))
+                            .count();
+                        file.items.insert(index, import.clone());
+                    }
+                }
+                file.items.extend(capability_code.items.clone());
+            }
+            Ok(file)
+        },
+    )?;
+
+    if access == CapabilityAccess::Unrestricted {
+        add_unrestricted_grant_to_init(
+            &mut file_tree,
+            &crate_src_path,
+            capability_name,
+            functions,
+            &format!("Let any agent call the functions of the {capability_name} capability"),
+        )?;
+    }
+
+    Ok(file_tree)
+}
+
+/// Adds the creation of an unrestricted capability grant for the given functions of the zome to its `init`
+/// callback, unless `init` already creates a grant with that tag, and adds `init` to lib.rs if the zome has none
+pub fn add_unrestricted_grant_to_init(
+    file_tree: &mut FileTree,
+    crate_src_path: &Path,
+    tag: &str,
+    functions: &[String],
+    comment: &str,
+) -> ScaffoldResult<()> {
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    let mut src_path = v.iter();
+    let src_file_tree = file_tree
+        .path_mut(&mut src_path)
+        .ok_or(ScaffoldError::PathNotFound(crate_src_path.to_path_buf()))?;
+
+    let init_file_path = find_map_rust_files(src_file_tree, &|_file_path, file| {
+        file.items
+            .iter()
+            .any(|item| matches!(item, syn::Item::Fn(item_fn) if item_fn.sig.ident == "init"))
+            .then_some(())
+    })
+    .into_keys()
+    .next();
+
+    let comment = format!(" {comment}");
+    let grant: syn::Block = syn::parse_quote! {{
+        #[doc = #comment]
+        let mut functions = BTreeSet::new();
+        #(functions.insert((zome_info()?.name, FunctionName::from(#functions)));)*
+        create_cap_grant(CapGrantEntry {
+            tag: String::from(#tag),
+            access: CapAccess::Unrestricted,
+            functions: GrantedFunctions::Listed(functions),
+        })?;
+    }};
+
+    map_rust_files(src_file_tree, |file_path, mut file| {
+        let Some(init_file_path) = &init_file_path else {
+            if file_path == Path::new("lib.rs") {
+                let grant_stmts = &grant.stmts;
+                file.items.push(syn::parse_quote! {
+                    /// Called the first time a zome call is made to the cell containing this zome
+                    #[hdk_extern]
+                    pub fn init() -> ExternResult<InitCallbackResult> {
+                        #(#grant_stmts)*
+                        Ok(InitCallbackResult::Pass)
+                    }
+                });
+            }
+            return Ok(file);
+        };
+        if &file_path != init_file_path {
+            return Ok(file);
+        }
+
+        for item in &mut file.items {
+            let syn::Item::Fn(item_fn) = item else {
+                continue;
+            };
+
+            if item_fn.sig.ident == "init" && !creates_cap_grant_with_tag(item_fn, tag) {
+                // The grant goes before the statement that returns the result of init
+                let index = item_fn
+                    .block
+                    .stmts
+                    .iter()
+                    .position(|stmt| {
+                        matches!(
+                            stmt,
+                            syn::Stmt::Expr(_, None) | syn::Stmt::Expr(syn::Expr::Return(_), _)
+                        )
+                    })
+                    .unwrap_or(item_fn.block.stmts.len());
+                item_fn
+                    .block
+                    .stmts
+                    .splice(index..index, grant.stmts.clone());
+            }
+        }
+
+        Ok(file)
+    })
+}
+
+/// Whether the function calls `create_cap_grant` with a `CapGrantEntry` whose tag is the given one
+fn creates_cap_grant_with_tag(item_fn: &ItemFn, tag: &str) -> bool {
+    struct CapGrantTagFinder<'a> {
+        tag: &'a str,
+        in_tag_field: bool,
+        found: bool,
+    }
+
+    impl<'ast> Visit<'ast> for CapGrantTagFinder<'_> {
+        fn visit_expr_call(&mut self, expr_call: &'ast syn::ExprCall) {
+            if let syn::Expr::Path(expr_path) = expr_call.func.as_ref() {
+                if expr_path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "create_cap_grant")
+                {
+                    for arg in &expr_call.args {
+                        let syn::Expr::Struct(expr_struct) = arg else {
+                            continue;
+                        };
+                        for field in &expr_struct.fields {
+                            if matches!(&field.member, syn::Member::Named(ident) if ident == "tag")
+                            {
+                                self.in_tag_field = true;
+                                self.visit_expr(&field.expr);
+                                self.in_tag_field = false;
+                            }
+                        }
+                    }
+                }
+            }
+            visit::visit_expr_call(self, expr_call);
+        }
+
+        fn visit_lit_str(&mut self, lit_str: &'ast syn::LitStr) {
+            if self.in_tag_field && lit_str.value() == self.tag {
+                self.found = true;
+            }
+        }
+    }
+
+    let mut finder = CapGrantTagFinder {
+        tag,
+        in_tag_field: false,
+        found: false,
+    };
+    finder.visit_item_fn(item_fn);
+    finder.found
+}
+
+fn call_remote_name(fn_name: &str) -> String {
+    format!("call_remote_{fn_name}")
+}
+
+/// The imports of the types used in the signature of the given function, from the file where it is defined
+///
+/// Types defined in the file are imported by their path, types imported by name keep their import, and any other
+/// type is expected to come from the glob imports of other crates in the file, like the one of the integrity zome
+fn signature_type_imports(
+    item_fn: &ItemFn,
+    file_path: &Path,
+    file: &syn::File,
+) -> ScaffoldResult<Vec<syn::Item>> {
+    let idents = signature_type_idents(item_fn)?;
+
+    let module = module_path(file_path);
+    let use_leaves: Vec<(Vec<syn::Ident>, syn::UseTree)> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Use(item_use) => Some(use_tree_leaves(&item_use.tree, vec![])),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    let mut imports: Vec<syn::Item> = Vec::new();
+    let mut needs_glob_imports = false;
+    for ident in idents {
+        let defined_item_vis = file.items.iter().find_map(|item| match item {
+            syn::Item::Struct(i) if i.ident == ident => Some(&i.vis),
+            syn::Item::Enum(i) if i.ident == ident => Some(&i.vis),
+            syn::Item::Type(i) if i.ident == ident => Some(&i.vis),
+            _ => None,
+        });
+        if let Some(vis) = defined_item_vis {
+            if !matches!(vis, syn::Visibility::Public(_)) {
+                return Err(ScaffoldError::InvalidArguments(format!(
+                    "The type {ident} of the function {} is private to its module, make it public to grant the function",
+                    item_fn.sig.ident
+                )));
+            }
+            imports.push(syn::parse_quote! { use crate #(::#module)*::#ident; });
+            continue;
+        }
+
+        let imported_leaf = use_leaves.iter().find(|(_, leaf)| match leaf {
+            syn::UseTree::Name(use_name) => use_name.ident == ident,
+            syn::UseTree::Rename(use_rename) => use_rename.rename == ident,
+            _ => false,
+        });
+        match imported_leaf {
+            Some((segments, leaf)) => {
+                let segments = absolute_use_path(segments, &module);
+                imports.push(syn::parse_quote! { use #(#segments::)*#leaf; });
+            }
+            None => needs_glob_imports = true,
+        }
+    }
+
+    if needs_glob_imports {
+        for (segments, leaf) in &use_leaves {
+            let from_other_crate = segments
+                .first()
+                .is_some_and(|s| s != "crate" && s != "self" && s != "super");
+            let is_hdk_prelude = segments
+                .iter()
+                .map(|s| s.to_string())
+                .eq(["hdk", "prelude"]);
+            if matches!(leaf, syn::UseTree::Glob(_)) && from_other_crate && !is_hdk_prelude {
+                imports.push(syn::parse_quote! { use #(#segments::)*#leaf; });
+            }
+        }
+    }
+
+    Ok(imports)
+}
+
+/// Resolves the `self` and `super` prefixes of an import of the given module, so that it can be used in another module
+fn absolute_use_path(segments: &[syn::Ident], module: &[syn::Ident]) -> Vec<syn::Ident> {
+    match segments.first() {
+        Some(first) if first == "self" => [vec![format_ident!("crate")], module.to_vec()]
+            .concat()
+            .into_iter()
+            .chain(segments[1..].iter().cloned())
+            .collect(),
+        Some(first) if first == "super" => {
+            let parent = &module[..module.len().saturating_sub(1)];
+            [vec![format_ident!("crate")], parent.to_vec()]
+                .concat()
+                .into_iter()
+                .chain(segments[1..].iter().cloned())
+                .collect()
+        }
+        _ => segments.to_vec(),
+    }
+}
+
+fn capability_handlers(
+    capability_name: &str,
+    access: CapabilityAccess,
+    granted_functions: &[ItemFn],
+) -> ScaffoldResult<TokenStream> {
+    let secret_fn = format_ident!("{capability_name}_secret");
+
+    let call_remote_handlers = granted_functions
+        .iter()
+        .map(|item_fn| {
+            let cap_secret = match access {
+                CapabilityAccess::Unrestricted => quote! { None },
+                _ => quote! { #secret_fn(&input.agent)? },
+            };
+            call_remote_handler(item_fn, cap_secret)
+        })
+        .collect::<ScaffoldResult<Vec<TokenStream>>>()?;
+
+    let grant_and_claim_handlers = match access {
+        CapabilityAccess::Unrestricted => quote! {},
+        _ => grant_and_claim_handlers(capability_name, access, granted_functions),
+    };
+
+    Ok(quote! {
+        #grant_and_claim_handlers
+
+        #(#call_remote_handlers)*
+    })
+}
+
+fn grant_and_claim_handlers(
+    capability_name: &str,
+    access: CapabilityAccess,
+    granted_functions: &[ItemFn],
+) -> TokenStream {
+    let pascal_capability_name = capability_name.to_case(Case::Pascal);
+    let grant_struct = format_ident!("{pascal_capability_name}Grant");
+    let claim_input = format_ident!("Create{pascal_capability_name}ClaimInput");
+    let create_grant = format_ident!("create_{capability_name}_grant");
+    let revoke_grant = format_ident!("revoke_{capability_name}_grant");
+    let create_claim = format_ident!("create_{capability_name}_claim");
+    let revoke_claim = format_ident!("revoke_{capability_name}_claim");
+    let secret_fn = format_ident!("{capability_name}_secret");
+    let function_names: Vec<String> = granted_functions
+        .iter()
+        .map(|f| f.sig.ident.to_string())
+        .collect();
+
+    let (create_grant_params, cap_access) = match access {
+        CapabilityAccess::Assigned => (
+            quote! { assignees: Vec<AgentPubKey> },
+            quote! {
+                CapAccess::Assigned {
+                    secret,
+                    assignees: assignees.into_iter().collect(),
+                }
+            },
+        ),
+        _ => (quote! {}, quote! { CapAccess::Transferable { secret } }),
+    };
+    let create_grant_doc = match access {
+        CapabilityAccess::Assigned => format!(
+            " Grants the functions of the {capability_name} capability to the given agents, who also need the secret of the grant to call them"
+        ),
+        _ => format!(
+            " Grants the functions of the {capability_name} capability to any agent that has the secret of the grant"
+        ),
+    };
+
+    quote! {
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct #grant_struct {
+            pub grant_hash: ActionHash,
+            pub secret: CapSecret,
+        }
+
+        #[doc = #create_grant_doc]
+        #[hdk_extern]
+        pub fn #create_grant(#create_grant_params) -> ExternResult<#grant_struct> {
+            let secret = generate_cap_secret()?;
+            let mut functions = BTreeSet::new();
+            #(functions.insert((zome_info()?.name, FunctionName::from(#function_names)));)*
+            let grant_hash = create_cap_grant(CapGrantEntry {
+                tag: String::from(#capability_name),
+                access: #cap_access,
+                functions: GrantedFunctions::Listed(functions),
+            })?;
+            Ok(#grant_struct { grant_hash, secret })
+        }
+
+        /// Any call made with the secret of the grant is unauthorized from now on
+        #[hdk_extern]
+        pub fn #revoke_grant(grant_hash: ActionHash) -> ExternResult<ActionHash> {
+            delete_cap_grant(grant_hash)
+        }
+
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct #claim_input {
+            pub grantor: AgentPubKey,
+            pub secret: CapSecret,
+        }
+
+        /// Stores the secret of a grant that another agent shared with this agent, to call its functions later
+        #[hdk_extern]
+        pub fn #create_claim(input: #claim_input) -> ExternResult<ActionHash> {
+            create_cap_claim(CapClaimEntry {
+                tag: String::from(#capability_name),
+                grantor: input.grantor,
+                secret: input.secret,
+            })
+        }
+
+        #[hdk_extern]
+        pub fn #revoke_claim(claim_hash: ActionHash) -> ExternResult<ActionHash> {
+            delete(claim_hash)
+        }
+
+        /// The secret of the latest claim that hasn't been revoked, of a grant created by the given agent
+        fn #secret_fn(grantor: &AgentPubKey) -> ExternResult<Option<CapSecret>> {
+            let revoked_claims: Vec<ActionHash> = query(ChainQueryFilter::new().action_type(ActionType::Delete))?
+                .into_iter()
+                .filter_map(|record| match record.action() {
+                    Action::Delete(delete) => Some(delete.deletes_address.clone()),
+                    _ => None,
+                })
+                .collect();
+            let claims = query(
+                ChainQueryFilter::new()
+                    .entry_type(EntryType::CapClaim)
+                    .include_entries(true),
+            )?;
+            Ok(claims
+                .into_iter()
+                .rev()
+                .filter(|record| !revoked_claims.contains(record.action_address()))
+                .find_map(|record| {
+                    let claim = record.entry().as_option()?.as_cap_claim()?;
+                    (claim.tag == #capability_name && &claim.grantor == grantor).then_some(claim.secret)
+                }))
+        }
+    }
+}
+
+/// A function calling the given extern function in the cell of another agent, with the same types
+fn call_remote_handler(item_fn: &ItemFn, cap_secret: TokenStream) -> ScaffoldResult<TokenStream> {
+    let fn_name = item_fn.sig.ident.to_string();
+    let function_name = format_ident!("{}", call_remote_name(&fn_name));
+    let input_struct = format_ident!("CallRemote{}Input", fn_name.to_case(Case::Pascal));
+    let output_type = extern_output_type(item_fn)?;
+    let input_type = item_fn.sig.inputs.first().and_then(|arg| match arg {
+        syn::FnArg::Typed(pat_type) => Some(pat_type.ty.clone()),
+        syn::FnArg::Receiver(_) => None,
+    });
+    let (input_field, payload) = match input_type {
+        Some(input_type) => (quote! { pub input: #input_type, }, quote! { input.input }),
+        None => (quote! {}, quote! { () }),
+    };
+    let doc = format!(" Calls {fn_name} in the cell of the given agent");
+    let error_message = format!("Failed to call {fn_name} remotely");
+
+    Ok(quote! {
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct #input_struct {
+            pub agent: AgentPubKey,
+            #input_field
+        }
+
+        #[doc = #doc]
+        #[hdk_extern]
+        pub fn #function_name(input: #input_struct) -> ExternResult<#output_type> {
+            let cap_secret = #cap_secret;
+            let response = call_remote(
+                input.agent,
+                zome_info()?.name,
+                FunctionName::from(#fn_name),
+                cap_secret,
+                #payload,
+            )?;
+            match response {
+                ZomeCallResponse::Ok(result) => result.decode().map_err(|e| wasm_error!(e)),
+                _ => Err(wasm_error!(WasmErrorInner::Guest(format!("{}: {:?}", #error_message, response)))),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_tree::file_content;
+    use build_fs_tree::{dir, file};
+
+    #[test]
+    fn test_call_remote_types() {
+        let item_fn: ItemFn = syn::parse_quote! {
+            #[hdk_extern]
+            pub fn get_latest_post(author: AgentPubKey) -> ExternResult<Option<Record>> {
+                Ok(None)
+            }
+        };
+        assert_eq!(
+            extern_output_type(&item_fn)
+                .unwrap()
+                .to_token_stream()
+                .to_string(),
+            "Option < Record >"
+        );
+
+        let item_fn: ItemFn = syn::parse_quote! {
+            fn helper() -> Option<Record> {
+                None
+            }
+        };
+        assert!(extern_output_type(&item_fn).is_err());
+
+        assert!(module_path(Path::new("lib.rs")).is_empty());
+        assert_eq!(module_path(Path::new("posts/mod.rs")), ["posts"]);
+        assert_eq!(
+            module_path(Path::new("posts/comments.rs")),
+            ["posts", "comments"]
+        );
+    }
+
+    #[test]
+    fn test_signature_type_imports() {
+        let file: syn::File = syn::parse_quote! {
+            use hdk::prelude::*;
+            use posts_integrity::*;
+            use super::pagination::{Page as PostsPage, Cursor};
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct GetPostsInput {
+                pub author: AgentPubKey,
+            }
+
+            #[hdk_extern]
+            pub fn get_posts(input: GetPostsInput) -> ExternResult<PostsPage<Post>> {
+                todo!()
+            }
+
+            #[hdk_extern]
+            pub fn get_post(post_hash: ActionHash) -> ExternResult<Option<Record>> {
+                todo!()
+            }
+
+            struct PrivateInput {}
+
+            #[hdk_extern]
+            pub fn get_private(input: PrivateInput) -> ExternResult<()> {
+                todo!()
+            }
+        };
+        let extern_fn = |name: &str| {
+            file.items
+                .iter()
+                .find_map(|item| match item {
+                    syn::Item::Fn(item_fn) if item_fn.sig.ident == name => Some(item_fn.clone()),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        let imports = |name: &str| {
+            signature_type_imports(&extern_fn(name), Path::new("posts/all_posts.rs"), &file).map(
+                |imports| {
+                    imports
+                        .into_iter()
+                        .map(|import| import.to_token_stream().to_string())
+                        .collect::<Vec<String>>()
+                },
+            )
+        };
+
+        assert_eq!(
+            imports("get_posts").unwrap(),
+            [
+                "use crate :: posts :: all_posts :: GetPostsInput ;",
+                "use crate :: posts :: pagination :: Page as PostsPage ;",
+                "use posts_integrity :: * ;",
+            ]
+        );
+        // Only the glob imports of other crates can bring the types of the signature, besides the hdk prelude
+        assert_eq!(imports("get_post").unwrap(), ["use posts_integrity :: * ;"]);
+        assert!(matches!(
+            imports("get_private"),
+            Err(ScaffoldError::InvalidArguments(_))
+        ));
+    }
+
+    #[test]
+    fn test_grant_and_claim_handlers() {
+        let get_posts: ItemFn = syn::parse_quote! {
+            #[hdk_extern]
+            pub fn get_posts(author: AgentPubKey) -> ExternResult<Vec<Record>> {
+                todo!()
+            }
+        };
+        let file: syn::File = syn::parse2(
+            capability_handlers(
+                "posts_reader",
+                CapabilityAccess::Assigned,
+                std::slice::from_ref(&get_posts),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let code = file.to_token_stream().to_string();
+
+        assert!(code.contains("pub fn create_posts_reader_grant (assignees : Vec < AgentPubKey >) -> ExternResult < PostsReaderGrant >"));
+        assert!(code.contains("CapAccess :: Assigned { secret , assignees : assignees . into_iter () . collect () , }"));
+        assert!(code.contains(
+            "functions . insert ((zome_info () ? . name , FunctionName :: from (\"get_posts\")))"
+        ));
+        assert!(code.contains("create_cap_claim (CapClaimEntry { tag : String :: from (\"posts_reader\") , grantor : input . grantor , secret : input . secret , })"));
+        assert!(code.contains("pub fn call_remote_get_posts (input : CallRemoteGetPostsInput) -> ExternResult < Vec < Record > >"));
+        assert!(code.contains("let cap_secret = posts_reader_secret (& input . agent) ? ;"));
+
+        let file: syn::File = syn::parse2(
+            capability_handlers("posts_reader", CapabilityAccess::Unrestricted, &[get_posts])
+                .unwrap(),
+        )
+        .unwrap();
+        let code = file.to_token_stream().to_string();
+        assert!(!code.contains("create_posts_reader_grant"));
+        assert!(code.contains("let cap_secret = None ;"));
+    }
+
+    #[test]
+    fn test_add_unrestricted_grant_to_init() {
+        let lib_rs = |file_tree: &FileTree| -> syn::File {
+            syn::parse_str(&file_content(file_tree, Path::new("src/lib.rs")).unwrap()).unwrap()
+        };
+        let init_stmts = |file: &syn::File| -> Vec<String> {
+            file.items
+                .iter()
+                .find_map(|item| match item {
+                    syn::Item::Fn(item_fn) if item_fn.sig.ident == "init" => Some(
+                        item_fn
+                            .block
+                            .stmts
+                            .iter()
+                            .map(|stmt| stmt.to_token_stream().to_string())
+                            .collect(),
+                    ),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        let add_grant = |file_tree: &mut FileTree, tag: &str| {
+            add_unrestricted_grant_to_init(
+                file_tree,
+                Path::new("src"),
+                tag,
+                &[String::from("get_posts")],
+                "Let any agent read the posts",
+            )
+            .unwrap()
+        };
+
+        let mut file_tree: FileTree = dir! {
+            "src" => dir! {
+                "lib.rs" => file!(quote! {
+                    use hdk::prelude::*;
+
+                    /// Mentions the "posts_reader" tag without creating its grant
+                    #[hdk_extern]
+                    pub fn init() -> ExternResult<InitCallbackResult> {
+                        Ok(InitCallbackResult::Pass)
+                    }
+                }
+                .to_string())
+            }
+        };
+
+        add_grant(&mut file_tree, "posts_reader");
+        let stmts = init_stmts(&lib_rs(&file_tree));
+        assert_eq!(stmts.len(), 4);
+        assert!(stmts[2].starts_with("create_cap_grant (CapGrantEntry { tag : String :: from (\"posts_reader\") , access : CapAccess :: Unrestricted"));
+        assert_eq!(stmts[3], "Ok (InitCallbackResult :: Pass)");
+
+        // The grant already exists
+        add_grant(&mut file_tree, "posts_reader");
+        assert_eq!(init_stmts(&lib_rs(&file_tree)).len(), 4);
+
+        add_grant(&mut file_tree, "recv_remote_signal");
+        let stmts = init_stmts(&lib_rs(&file_tree));
+        assert_eq!(stmts.len(), 7);
+        assert!(stmts[5].contains("String :: from (\"recv_remote_signal\")"));
+        assert_eq!(stmts[6], "Ok (InitCallbackResult :: Pass)");
+
+        // A zome without init gets one
+        let mut file_tree: FileTree = dir! {
+            "src" => dir! {
+                "lib.rs" => file!(String::from("use hdk::prelude::*;\n"))
+            }
+        };
+        add_grant(&mut file_tree, "posts_reader");
+        let stmts = init_stmts(&lib_rs(&file_tree));
+        assert_eq!(stmts.len(), 4);
+        assert_eq!(stmts[3], "Ok (InitCallbackResult :: Pass)");
+    }
+}
@@ -1,14 +1,17 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{ffi::OsString, path::PathBuf, str::FromStr};
 
 use convert_case::{Case, Casing};
 use dialoguer::{theme::ColorfulTheme, Select};
+use quote::{format_ident, ToTokens};
 use serde::Serialize;
+use syn::parse_quote;
 
 use crate::{
     error::{ScaffoldError, ScaffoldResult},
-    file_tree::FileTree,
+    file_tree::{find_map_rust_files, FileTree},
     reserved_words::check_for_reserved_keywords,
     templates::{collection::scaffold_collection_templates, ScaffoldedTemplate},
+    utils::check_interactive,
 };
 
 use self::coordinator::add_collection_to_coordinators;
@@ -16,21 +19,27 @@ use self::coordinator::add_collection_to_coordinators;
 use super::{
     app::AppFileTree,
     entry_type::{
-        definitions::{EntryTypeReference, Referenceable},
-        integrity::get_all_entry_types,
+        definitions::{EntryTypeReference, EntryVisibility, Referenceable},
+        integrity::{find_entry_type_struct, get_all_entry_types, get_entry_type_visibility},
         utils::choose_entry_type_reference,
     },
-    link_type::integrity::add_link_type_to_integrity_zome,
+    link_type::{integrity::add_link_type_to_integrity_zome, LinkValidation},
     zome::ZomeFileTree,
 };
 
 pub mod coordinator;
 
-#[derive(Serialize, Debug, Clone, Copy)]
+#[derive(Serialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum CollectionType {
     Global,
     ByAuthor,
+    /// Entries linked from the path made of the collection name and the value of their `key_field`
+    ByPath {
+        key_field: String,
+    },
+    /// Entries linked from the year/month/day/hour path of the timestamp of the action that created them
+    TimeIndexed,
 }
 
 impl FromStr for CollectionType {
@@ -40,24 +49,36 @@ impl FromStr for CollectionType {
         match s {
             "global" => Ok(CollectionType::Global),
             "by-author" => Ok(CollectionType::ByAuthor),
+            // The key field is chosen once the collected entry type is known
+            "by-path" => Ok(CollectionType::ByPath {
+                key_field: String::new(),
+            }),
+            "time-indexed" => Ok(CollectionType::TimeIndexed),
             _ => Err(ScaffoldError::InvalidCollectionType(
                 s.to_string(),
-                "global, by-author".to_string(),
+                "global, by-author, by-path, time-indexed".to_string(),
             )),
         }
     }
 }
 
-pub fn choose_collection_type() -> ScaffoldResult<CollectionType> {
+pub fn choose_collection_type(no_interactive: bool) -> ScaffoldResult<CollectionType> {
+    check_interactive("the `<collection-type>` argument", no_interactive)?;
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("Which type of collection should be scaffolded?")
         .default(0)
         .item("Global (get all entries of the selected entry types)")
         .item("By author (get entries of the selected entry types that a given author has created)")
+        .item("By path (get entries of the selected entry types that have a given value in one of their fields)")
+        .item("Time indexed (get entries of the selected entry types created in a given time range, newest first)")
         .interact()?;
     match selection {
         0 => Ok(CollectionType::Global),
         1 => Ok(CollectionType::ByAuthor),
+        2 => Ok(CollectionType::ByPath {
+            key_field: String::new(),
+        }),
+        3 => Ok(CollectionType::TimeIndexed),
         _ => Err(ScaffoldError::InvalidCollectionType(
             selection.to_string(),
             "".into(),
@@ -65,14 +86,272 @@ pub fn choose_collection_type() -> ScaffoldResult<CollectionType> {
     }
 }
 
+/// Predicate on a field of the collected entry type, that entries must match to be in the collection
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum CollectionFilter {
+    String {
+        field: String,
+        value: String,
+    },
+    Bool {
+        field: String,
+        value: bool,
+    },
+    Enum {
+        field: String,
+        label: String,
+        variant: String,
+    },
+}
+
+impl CollectionFilter {
+    /// Condition that checks the filter on the given entry expression, eg. `post.status == "published"`
+    pub fn condition(&self, entry: &syn::Expr) -> syn::Expr {
+        match self {
+            CollectionFilter::String { field, value } => {
+                let field = format_ident!("{field}");
+                parse_quote! { #entry.#field == #value }
+            }
+            CollectionFilter::Bool { field, value: true } => {
+                let field = format_ident!("{field}");
+                parse_quote! { #entry.#field }
+            }
+            CollectionFilter::Bool {
+                field,
+                value: false,
+            } => {
+                let field = format_ident!("{field}");
+                parse_quote! { !#entry.#field }
+            }
+            CollectionFilter::Enum {
+                field,
+                label,
+                variant,
+            } => {
+                let field = format_ident!("{field}");
+                let label = format_ident!("{label}");
+                let variant = format_ident!("{variant}");
+                parse_quote! { #entry.#field == #label::#variant }
+            }
+        }
+    }
+
+    /// Parses back a condition built by [`CollectionFilter::condition`]
+    pub fn from_condition(condition: &syn::Expr) -> Option<CollectionFilter> {
+        let field_name = |expr: &syn::Expr| match expr {
+            syn::Expr::Field(syn::ExprField {
+                member: syn::Member::Named(ident),
+                ..
+            }) => Some(ident.to_string()),
+            _ => None,
+        };
+
+        match condition {
+            syn::Expr::Field(_) => Some(CollectionFilter::Bool {
+                field: field_name(condition)?,
+                value: true,
+            }),
+            syn::Expr::Unary(syn::ExprUnary {
+                op: syn::UnOp::Not(_),
+                expr,
+                ..
+            }) => Some(CollectionFilter::Bool {
+                field: field_name(expr)?,
+                value: false,
+            }),
+            syn::Expr::Binary(syn::ExprBinary {
+                left,
+                op: syn::BinOp::Eq(_),
+                right,
+                ..
+            }) => match right.as_ref() {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(value),
+                    ..
+                }) => Some(CollectionFilter::String {
+                    field: field_name(left)?,
+                    value: value.value(),
+                }),
+                syn::Expr::Path(path) if path.path.segments.len() == 2 => {
+                    Some(CollectionFilter::Enum {
+                        field: field_name(left)?,
+                        label: path.path.segments[0].ident.to_string(),
+                        variant: path.path.segments[1].ident.to_string(),
+                    })
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Builds the filter for the given field and value, checking that the value fits the type of the field
+fn get_collection_filter(
+    zome_file_tree: &ZomeFileTree,
+    entry_type: &EntryTypeReference,
+    maybe_filter_field: Option<String>,
+    maybe_filter_value: Option<String>,
+) -> ScaffoldResult<Option<CollectionFilter>> {
+    let pascal_entry_type = entry_type.entry_type.to_case(Case::Pascal);
+
+    let (field, value) = match (maybe_filter_field, maybe_filter_value) {
+        (None, None) => return Ok(None),
+        (Some(field), Some(value)) => (field, value),
+        _ => {
+            return Err(ScaffoldError::InvalidArguments(
+                "--filter-field and --filter-value must be used together".to_string(),
+            ))
+        }
+    };
+
+    if entry_type.reference_entry_hash {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "filtered collections must reference the {pascal_entry_type} entries by action hash, as updates change their entry hash"
+        )));
+    }
+
+    let field_type = find_entry_type_struct(zome_file_tree, &entry_type.entry_type)?
+        .and_then(|(_, item_struct)| {
+            item_struct
+                .fields
+                .iter()
+                .find(|f| f.ident.as_ref().is_some_and(|i| *i == field))
+                .map(|f| f.ty.to_token_stream().to_string())
+        })
+        .ok_or_else(|| {
+            ScaffoldError::InvalidArguments(format!(
+                "{field} is not a field of the {pascal_entry_type} entry type"
+            ))
+        })?;
+
+    match field_type.as_str() {
+        "String" => Ok(Some(CollectionFilter::String { field, value })),
+        "bool" => match value.parse::<bool>() {
+            Ok(value) => Ok(Some(CollectionFilter::Bool { field, value })),
+            Err(_) => Err(ScaffoldError::InvalidArguments(format!(
+                "{field} is a bool field, so the filter value must be true or false"
+            ))),
+        },
+        label => {
+            let variants = find_enum_variants(zome_file_tree, label)?.ok_or_else(|| {
+                ScaffoldError::InvalidArguments(format!(
+                    "{field} can't be filtered on, only String, bool and enum fields can"
+                ))
+            })?;
+            let variant = value.to_case(Case::Pascal);
+            if !variants.contains(&variant) {
+                return Err(ScaffoldError::InvalidArguments(format!(
+                    "{value} is not a variant of {label}, the type of {field}: {}",
+                    variants.join(", ")
+                )));
+            }
+            Ok(Some(CollectionFilter::Enum {
+                field,
+                label: label.to_string(),
+                variant,
+            }))
+        }
+    }
+}
+
+/// Returns the variants of the enum with the given name in the integrity zome, if there is one
+fn find_enum_variants(
+    zome_file_tree: &ZomeFileTree,
+    label: &str,
+) -> ScaffoldResult<Option<Vec<String>>> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+    let enums = find_map_rust_files(
+        zome_file_tree
+            .dna_file_tree
+            .file_tree_ref()
+            .path(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        &|_, file| {
+            file.items.iter().find_map(|item| match item {
+                syn::Item::Enum(item_enum) if item_enum.ident == label => Some(
+                    item_enum
+                        .variants
+                        .iter()
+                        .map(|v| v.ident.to_string())
+                        .collect::<Vec<String>>(),
+                ),
+                _ => None,
+            })
+        },
+    );
+
+    Ok(enums.into_values().next())
+}
+
+/// Name of the link type that builds the tree of paths of a by-path collection, used to list its keys
+pub fn keys_link_type_name(link_type_name: &str) -> String {
+    format!("{link_type_name}Keys")
+}
+
+/// Name of the link type that builds the tree of time buckets of a time-indexed collection
+pub fn time_index_link_type_name(link_type_name: &str) -> String {
+    format!("{link_type_name}TimeIndex")
+}
+
+/// Returns the given key field if it's a `String` field of the entry type, or lets the user choose one
+fn get_or_choose_key_field(
+    zome_file_tree: &ZomeFileTree,
+    entry_type: &EntryTypeReference,
+    maybe_key_field: Option<String>,
+    no_interactive: bool,
+) -> ScaffoldResult<String> {
+    let pascal_entry_type = entry_type.entry_type.to_case(Case::Pascal);
+
+    let string_fields: Vec<String> =
+        find_entry_type_struct(zome_file_tree, &entry_type.entry_type)?
+            .map(|(_, item_struct)| {
+                item_struct
+                    .fields
+                    .iter()
+                    .filter(|f| f.ty.to_token_stream().to_string() == "String")
+                    .filter_map(|f| f.ident.as_ref().map(|i| i.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+    match maybe_key_field {
+        Some(key_field) if string_fields.contains(&key_field) => Ok(key_field),
+        Some(key_field) => Err(ScaffoldError::InvalidArguments(format!(
+            "{key_field} is not a String field of the {pascal_entry_type} entry type"
+        ))),
+        None if string_fields.is_empty() => Err(ScaffoldError::InvalidArguments(format!(
+            "{pascal_entry_type} has no String fields to use as the key of a by-path collection"
+        ))),
+        None => {
+            check_interactive("the `--key-field` option", no_interactive)?;
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Which field should the entries be collected by?")
+                .default(0)
+                .items(&string_fields[..])
+                .interact()?;
+            Ok(string_fields[selection].clone())
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn scaffold_collection(
     integrity_zome_file_tree: ZomeFileTree,
     template_file_tree: &FileTree,
     collection_name: &str,
     maybe_collection_type: Option<CollectionType>,
-    maybe_entry_type: Option<EntryTypeReference>,
+    entry_types: Vec<EntryTypeReference>,
+    maybe_key_field: Option<String>,
+    paginated: bool,
+    maybe_filter_field: Option<String>,
+    maybe_filter_value: Option<String>,
     no_ui: bool,
     no_spec: bool,
+    no_interactive: bool,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
     check_for_reserved_keywords(collection_name)?;
 
@@ -85,7 +364,7 @@ pub fn scaffold_collection(
 
     let collection_type = match maybe_collection_type {
         Some(t) => Ok(t),
-        None => choose_collection_type(),
+        None => choose_collection_type(no_interactive),
     }?;
 
     let all_entries_names: Vec<String> = all_entries
@@ -93,43 +372,178 @@ pub fn scaffold_collection(
         .into_iter()
         .map(|e| e.entry_type)
         .collect();
-    let entry_type = match maybe_entry_type {
-        Some(et) => {
-            if all_entries_names.contains(&et.entry_type.to_case(Case::Pascal)) {
-                Ok(et.clone())
-            } else {
-                Err(ScaffoldError::EntryTypeNotFound(
-                    et.entry_type.clone(),
-                    integrity_zome_file_tree.dna_file_tree.dna_manifest.name(),
-                    integrity_zome_file_tree.zome_manifest.name.0.to_string(),
-                ))
+    let entry_types = match entry_types.is_empty() {
+        false => {
+            for et in &entry_types {
+                if !all_entries_names.contains(&et.entry_type.to_case(Case::Pascal)) {
+                    return Err(ScaffoldError::EntryTypeNotFound(
+                        et.entry_type.clone(),
+                        integrity_zome_file_tree.dna_file_tree.dna_manifest.name(),
+                        integrity_zome_file_tree.zome_manifest.name.0.to_string(),
+                    ));
+                }
             }
+            entry_types
         }
-        None => choose_entry_type_reference(&all_entries, "Which entry type should be collected?"),
-    }?;
+        true => {
+            check_interactive("the `<entry-types>` argument", no_interactive)?;
+            vec![choose_entry_type_reference(
+                &all_entries,
+                "Which entry type should be collected?",
+                no_interactive,
+            )?]
+        }
+    };
+    let entry_type = entry_types[0].clone();
+
+    let collection_type = match collection_type {
+        CollectionType::ByPath { .. } => CollectionType::ByPath {
+            key_field: get_or_choose_key_field(
+                &integrity_zome_file_tree,
+                &entry_type,
+                maybe_key_field,
+                no_interactive,
+            )?,
+        },
+        _ if maybe_key_field.is_some() => {
+            return Err(ScaffoldError::InvalidArguments(
+                "--key-field can only be used with by-path collections".to_string(),
+            ));
+        }
+        t => t,
+    };
+
+    if paginated
+        && !matches!(
+            collection_type,
+            CollectionType::Global | CollectionType::ByAuthor
+        )
+    {
+        return Err(ScaffoldError::InvalidArguments(
+            "--paginated can only be used with global and by-author collections".to_string(),
+        ));
+    }
+
+    let filter = get_collection_filter(
+        &integrity_zome_file_tree,
+        &entry_type,
+        maybe_filter_field,
+        maybe_filter_value,
+    )?;
+
+    if filter.is_some()
+        && !matches!(
+            collection_type,
+            CollectionType::Global | CollectionType::ByAuthor
+        )
+    {
+        return Err(ScaffoldError::InvalidArguments(
+            "--filter-field can only be used with global and by-author collections".to_string(),
+        ));
+    }
+
+    if entry_types.len() > 1 {
+        if !matches!(
+            collection_type,
+            CollectionType::Global | CollectionType::ByAuthor
+        ) || paginated
+            || filter.is_some()
+        {
+            return Err(ScaffoldError::InvalidArguments(
+                "only global and by-author collections that are neither paginated nor filtered can collect more than one entry type".to_string(),
+            ));
+        }
+        if let Some(et) = entry_types.iter().find(|et| et.reference_entry_hash) {
+            return Err(ScaffoldError::InvalidArguments(format!(
+                "collections of more than one entry type must reference them by action hash, but {} is referenced by entry hash",
+                et.entry_type.to_case(Case::Pascal)
+            )));
+        }
+    }
+
+    if let CollectionType::Global | CollectionType::ByPath { .. } | CollectionType::TimeIndexed =
+        collection_type
+    {
+        for entry_type in &entry_types {
+            if get_entry_type_visibility(&integrity_zome_file_tree, &entry_type.entry_type)?
+                == EntryVisibility::Private
+            {
+                return Err(ScaffoldError::InvalidArguments(format!(
+                    "{} entries are private and can only be read by their author, collect them with a by-author collection instead",
+                    entry_type.entry_type.to_case(Case::Pascal)
+                )));
+            }
+        }
+    }
 
     let link_type_name = collection_name.to_case(Case::Pascal);
 
+    // The targets of a collection of several entry types can be any of them, so they are validated
+    // against the entry type their links are tagged with
+    let (to_referenceable, validation) = match entry_types.len() {
+        1 => (
+            Some(Referenceable::EntryType(entry_type.clone())),
+            LinkValidation::default(),
+        ),
+        _ => (
+            None,
+            LinkValidation {
+                tagged_target_entry_types: entry_types.clone(),
+                ..Default::default()
+            },
+        ),
+    };
+
     let zome_file_tree = add_link_type_to_integrity_zome(
         integrity_zome_file_tree,
         &link_type_name,
         &None,
-        &Some(Referenceable::EntryType(entry_type.clone())),
+        &to_referenceable,
         true,
+        None,
+        &validation,
+        &None,
+        &None,
         &PathBuf::from(format!("{}.rs", entry_type.entry_type.to_case(Case::Snake))),
     )?;
 
+    let path_link_type_name = match collection_type {
+        CollectionType::ByPath { .. } => Some(keys_link_type_name(&link_type_name)),
+        CollectionType::TimeIndexed => Some(time_index_link_type_name(&link_type_name)),
+        _ => None,
+    };
+
+    let zome_file_tree = match path_link_type_name {
+        Some(path_link_type_name) => add_link_type_to_integrity_zome(
+            zome_file_tree,
+            &path_link_type_name,
+            &None,
+            &None,
+            false,
+            None,
+            &LinkValidation::default(),
+            &None,
+            &None,
+            &PathBuf::from(format!("{}.rs", entry_type.entry_type.to_case(Case::Snake))),
+        )?,
+        None => zome_file_tree,
+    };
+
     let (dna_file_tree, coordinator_zome, deletable) = add_collection_to_coordinators(
         zome_file_tree,
         collection_name,
         &link_type_name,
         &collection_type,
-        &entry_type,
+        &entry_types,
+        paginated,
+        filter.as_ref(),
+        no_interactive,
     )?;
 
     let dna_name = dna_file_tree.dna_manifest.name();
 
-    let app_file_tree = AppFileTree::get_or_choose(dna_file_tree.file_tree(), None)?;
+    let app_file_tree =
+        AppFileTree::get_or_choose(dna_file_tree.file_tree(), None, no_interactive)?;
 
     let app_name = app_file_tree.app_manifest.app_name().to_string();
 
@@ -141,9 +555,51 @@ pub fn scaffold_collection(
         &coordinator_zome,
         &collection_type,
         collection_name,
-        &entry_type,
+        &entry_types,
         deletable,
+        paginated,
+        filter,
         no_ui,
         no_spec,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collection_filter_condition_round_trip() {
+        let entry: syn::Expr = parse_quote! { post };
+        let filters = [
+            CollectionFilter::String {
+                field: "status".to_string(),
+                value: "published".to_string(),
+            },
+            CollectionFilter::Bool {
+                field: "archived".to_string(),
+                value: false,
+            },
+            CollectionFilter::Enum {
+                field: "visibility".to_string(),
+                label: "Visibility".to_string(),
+                variant: "Public".to_string(),
+            },
+        ];
+
+        for filter in filters {
+            let condition = filter.condition(&entry);
+            assert_eq!(CollectionFilter::from_condition(&condition), Some(filter));
+        }
+
+        let condition = CollectionFilter::String {
+            field: "status".to_string(),
+            value: "published".to_string(),
+        }
+        .condition(&entry);
+        assert_eq!(
+            condition.to_token_stream().to_string(),
+            r#"post . status == "published""#
+        );
+    }
+}
@@ -4,32 +4,47 @@ use convert_case::{Case, Casing};
 use dialoguer::{theme::ColorfulTheme, Select};
 use holochain_types::prelude::ZomeManifest;
 use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
-use syn::parse_quote;
+use quote::{format_ident, quote, ToTokens};
+use regex::Regex;
+use syn::{
+    parse_quote,
+    punctuated::Punctuated,
+    visit::{self, Visit},
+    Token,
+};
 
 use crate::{
     error::{ScaffoldError, ScaffoldResult},
-    file_tree::{insert_file, map_file, map_rust_files},
+    file_tree::{file_exists, find_map_rust_files, insert_file, map_file, map_rust_files},
     scaffold::{
         dna::DnaFileTree,
         entry_type::definitions::EntryTypeReference,
         zome::{
-            coordinator::{find_extern_function_in_zomes, find_extern_function_or_choose},
+            coordinator::{
+                calls_function, find_extern_function_in_zomes, find_extern_function_or_choose,
+            },
             utils::get_coordinator_zomes_for_integrity,
             ZomeFileTree,
         },
     },
-    utils::unparse_pretty,
+    utils::{check_interactive, unparse_pretty},
 };
 
-use super::CollectionType;
+use super::{keys_link_type_name, time_index_link_type_name, CollectionFilter, CollectionType};
+
+/// Module of a coordinator zome with the helpers shared by its time-indexed collections
+pub const TIME_INDEX_MODULE: &str = "time_index";
 
+#[allow(clippy::too_many_arguments)]
 pub fn add_collection_to_coordinators(
     integrity_zome_file_tree: ZomeFileTree,
     collection_name: &str,
     link_type_name: &str,
     collection_type: &CollectionType,
-    entry_type: &EntryTypeReference,
+    entry_types: &[EntryTypeReference],
+    paginated: bool,
+    filter: Option<&CollectionFilter>,
+    no_interactive: bool,
 ) -> ScaffoldResult<(DnaFileTree, ZomeManifest, bool)> {
     let integrity_zome_name = integrity_zome_file_tree.zome_manifest.name.0.to_string();
     let dna_manifest_path = integrity_zome_file_tree
@@ -53,6 +68,10 @@ pub fn add_collection_to_coordinators(
                 .iter()
                 .map(|z| z.name.0.to_string())
                 .collect();
+            check_interactive(
+                "a choice of coordinator zome for the collection functions, as the integrity zome has more than one",
+                no_interactive,
+            )?;
             let selection = Select::with_theme(&ColorfulTheme::default())
                 .with_prompt(
                     "Which coordinator zome should the collection getter functions be scaffolded in?",
@@ -73,12 +92,36 @@ pub fn add_collection_to_coordinators(
 
     let snake_link_type_name = collection_name.to_case(Case::Snake);
 
+    // The links of a collection of several entry types are tagged with the type of their target
+    let tagged = entry_types.len() > 1;
+
     let getter = match collection_type {
-        CollectionType::Global => {
-            global_collection_getter(&integrity_zome_name, collection_name, link_type_name)
+        CollectionType::Global | CollectionType::ByAuthor if tagged => {
+            multi_type_collection_getter(
+                &integrity_zome_name,
+                collection_name,
+                link_type_name,
+                collection_type,
+                entry_types,
+            )
         }
-        CollectionType::ByAuthor => {
-            by_author_collection_getter(&integrity_zome_name, collection_name, link_type_name)
+        CollectionType::Global => global_collection_getter(
+            &integrity_zome_name,
+            collection_name,
+            link_type_name,
+            paginated,
+        ),
+        CollectionType::ByAuthor => by_author_collection_getter(
+            &integrity_zome_name,
+            collection_name,
+            link_type_name,
+            paginated,
+        ),
+        CollectionType::ByPath { .. } => {
+            by_path_collection_getter(&integrity_zome_name, collection_name, link_type_name)
+        }
+        CollectionType::TimeIndexed => {
+            time_indexed_collection_getter(&integrity_zome_name, collection_name, link_type_name)
         }
     };
 
@@ -101,33 +144,235 @@ pub fn add_collection_to_coordinators(
         ))
     })?;
 
+    // The helpers of time-indexed collections are generated once, along with the first of them
+    let time_index_path = crate_src_path.join(format!("{TIME_INDEX_MODULE}.rs"));
+    if matches!(collection_type, CollectionType::TimeIndexed)
+        && !file_exists(&file_tree, &time_index_path)
+    {
+        let time_index_module = time_index_module();
+        insert_file(
+            &mut file_tree,
+            &time_index_path,
+            &unparse_pretty(&syn::parse_quote! { #time_index_module }),
+        )?;
+        map_file(&mut file_tree, &lib_rs_path, |contents| {
+            Ok(format!(
+                r#"pub mod {TIME_INDEX_MODULE};
+{contents}"#,
+            ))
+        })?;
+    }
+
     let mut dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
 
-    dna_file_tree = add_create_link_in_create_function(
-        dna_file_tree,
-        &coordinator_zomes_for_integrity,
-        collection_name,
-        link_type_name,
-        collection_type,
-        entry_type,
-    )?;
+    let mut deletable = false;
 
-    let (dna_file_tree, deletable) = add_delete_link_in_delete_function(
-        dna_file_tree,
-        &coordinator_zomes_for_integrity,
-        collection_name,
-        link_type_name,
-        collection_type,
-        entry_type,
-    )?;
+    for entry_type in entry_types {
+        dna_file_tree = add_create_link_in_create_function(
+            dna_file_tree,
+            &coordinator_zomes_for_integrity,
+            collection_name,
+            link_type_name,
+            collection_type,
+            entry_type,
+            tagged,
+            filter,
+            no_interactive,
+        )?;
+
+        let update_link_stmts = update_link_stmts(
+            collection_name,
+            link_type_name,
+            collection_type,
+            entry_type,
+            filter,
+        );
+        if !update_link_stmts.is_empty() {
+            dna_file_tree = add_update_link_in_update_function(
+                dna_file_tree,
+                &coordinator_zomes_for_integrity,
+                entry_type,
+                &update_link_stmts,
+            )?;
+        }
+
+        let (new_dna_file_tree, entry_type_deletable) = add_delete_link_in_delete_function(
+            dna_file_tree,
+            &coordinator_zomes_for_integrity,
+            collection_name,
+            link_type_name,
+            collection_type,
+            entry_type,
+        )?;
+        dna_file_tree = new_dna_file_tree;
+        deletable |= entry_type_deletable;
+    }
 
     Ok((dna_file_tree, coordinator_zome, deletable))
 }
 
+/// Removes the statements that create, update and delete the links for the given collection
+/// from the functions of the coordinator zomes for the integrity zome
+pub fn remove_collection_from_coordinators(
+    integrity_zome_file_tree: ZomeFileTree,
+    collection_name: &str,
+    link_type_name: &str,
+    collection_type: &CollectionType,
+    entry_types: &[EntryTypeReference],
+    filter: Option<&CollectionFilter>,
+) -> ScaffoldResult<DnaFileTree> {
+    let dna_manifest_path = integrity_zome_file_tree
+        .dna_file_tree
+        .dna_manifest_path
+        .clone();
+    let coordinator_zomes_for_integrity = get_coordinator_zomes_for_integrity(
+        &integrity_zome_file_tree.dna_file_tree.dna_manifest,
+        integrity_zome_file_tree.zome_manifest.name.0.as_ref(),
+    );
+
+    let tagged = entry_types.len() > 1;
+    let mut collection_stmts = vec![];
+    for entry_type in entry_types {
+        collection_stmts.push(create_link_stmts(
+            collection_name,
+            link_type_name,
+            collection_type,
+            entry_type,
+            tagged,
+            filter,
+        ));
+        collection_stmts.push(delete_link_stmts(
+            collection_name,
+            link_type_name,
+            collection_type,
+            entry_type,
+        ));
+        let update_link_stmts = update_link_stmts(
+            collection_name,
+            link_type_name,
+            collection_type,
+            entry_type,
+            filter,
+        );
+        if !update_link_stmts.is_empty() {
+            collection_stmts.push(update_link_stmts);
+        }
+    }
+    let collection_stmts: Vec<Vec<String>> = collection_stmts
+        .iter()
+        .map(|stmts| stmts.iter().map(normalized_tokens).collect())
+        .collect();
+
+    let mut dna_file_tree = integrity_zome_file_tree.dna_file_tree;
+
+    for coordinator_zome in coordinator_zomes_for_integrity {
+        let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, coordinator_zome)?;
+        let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+
+        let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+        let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+        map_rust_files(
+            file_tree
+                .path_mut(&mut v.iter())
+                .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+            |_, mut file| {
+                for item in &mut file.items {
+                    if let syn::Item::Fn(item_fn) = item {
+                        for stmts in &collection_stmts {
+                            let fn_stmts: Vec<String> =
+                                item_fn.block.stmts.iter().map(normalized_tokens).collect();
+                            if let Some(position) = fn_stmts
+                                .windows(stmts.len())
+                                .position(|window| window == &stmts[..])
+                            {
+                                item_fn.block.stmts.drain(position..position + stmts.len());
+                            }
+                        }
+                    }
+                }
+                Ok(file)
+            },
+        )
+        .map_err(|e| match e {
+            ScaffoldError::MalformedFile(path, error) => {
+                ScaffoldError::MalformedFile(crate_src_path.join(path), error)
+            }
+            _ => e,
+        })?;
+
+        dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    }
+
+    Ok(dna_file_tree)
+}
+
+/// Token representation of the given statement that ignores the trailing commas added by rustfmt
+fn normalized_tokens(stmt: &syn::Stmt) -> String {
+    let trailing_comma = Regex::new(r"\s*,\s*([)\]}])").expect("Failed to create regex");
+    trailing_comma
+        .replace_all(&stmt.to_token_stream().to_string(), "$1")
+        .into_owned()
+}
+
+/// Page of links returned by the getters of paginated collections, and the input to request it
+fn pagination_items() -> TokenStream {
+    quote! {
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct Page {
+            pub links: Vec<Link>,
+            /// Cursor to pass to get the next page, if there are more links
+            pub next_cursor: Option<PageCursor>,
+        }
+
+        /// Position of the last link of a page: links created at the same time are ordered by their hash,
+        /// so that the next page starts right after it
+        #[derive(Serialize, Deserialize, Debug, Clone)]
+        pub struct PageCursor {
+            pub timestamp: Timestamp,
+            pub create_link_hash: ActionHash,
+        }
+
+        /// Sorts the links newest first and returns the ones after the cursor, up to the limit
+        fn page(mut links: Vec<Link>, cursor: Option<PageCursor>, limit: usize) -> ExternResult<Page> {
+            // An empty page would have no cursor, and end the pagination
+            if limit == 0 {
+                return Err(wasm_error!(WasmErrorInner::Guest("The limit of a page must be greater than 0".to_string())));
+            }
+            links.sort_by(|a, b| {
+                b.timestamp
+                    .cmp(&a.timestamp)
+                    .then_with(|| b.create_link_hash.cmp(&a.create_link_hash))
+            });
+            let mut links: Vec<Link> = links
+                .into_iter()
+                .filter(|link| {
+                    cursor.as_ref().map_or(true, |cursor| {
+                        (link.timestamp, &link.create_link_hash) < (cursor.timestamp, &cursor.create_link_hash)
+                    })
+                })
+                .take(limit + 1)
+                .collect();
+            let next_cursor = match links.len() > limit {
+                true => {
+                    links.truncate(limit);
+                    links.last().map(|link| PageCursor {
+                        timestamp: link.timestamp,
+                        create_link_hash: link.create_link_hash.clone(),
+                    })
+                }
+                false => None,
+            };
+            Ok(Page { links, next_cursor })
+        }
+    }
+}
+
 fn global_collection_getter(
     integrity_zome_name: &str,
     collection_name: &str,
     link_type_name: &str,
+    paginated: bool,
 ) -> TokenStream {
     let get_collection_function_name =
         format_ident!("get_{}", collection_name.to_case(Case::Snake));
@@ -135,6 +380,29 @@ fn global_collection_getter(
     let integrity_zome_name = format_ident!("{integrity_zome_name}");
     let snake_collection_name = collection_name.to_case(Case::Snake);
 
+    if paginated {
+        let pagination_items = pagination_items();
+        return quote! {
+            use hdk::prelude::*;
+            use #integrity_zome_name::*;
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct PageInput {
+                pub cursor: Option<PageCursor>,
+                pub limit: usize,
+            }
+
+            #pagination_items
+
+            #[hdk_extern]
+            pub fn #get_collection_function_name(input: PageInput) -> ExternResult<Page> {
+                let path = Path::from(#snake_collection_name);
+                let links = get_links(GetLinksInputBuilder::try_new(path.path_entry_hash()?, LinkTypes::#link_type_name)?.build())?;
+                page(links, input.cursor, input.limit)
+            }
+        };
+    }
+
     quote! {
         use hdk::prelude::*;
         use #integrity_zome_name::*;
@@ -151,12 +419,36 @@ fn by_author_collection_getter(
     integrity_zome_name: &str,
     collection_name: &str,
     link_type_name: &str,
+    paginated: bool,
 ) -> TokenStream {
     let get_collection_function_name =
         format_ident!("get_{}", collection_name.to_case(Case::Snake));
     let link_type_name = format_ident!("{link_type_name}");
     let integrity_zome_name = format_ident!("{integrity_zome_name}");
 
+    if paginated {
+        let pagination_items = pagination_items();
+        return quote! {
+            use hdk::prelude::*;
+            use #integrity_zome_name::*;
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct PageInput {
+                pub author: AgentPubKey,
+                pub cursor: Option<PageCursor>,
+                pub limit: usize,
+            }
+
+            #pagination_items
+
+            #[hdk_extern]
+            pub fn #get_collection_function_name(input: PageInput) -> ExternResult<Page> {
+                let links = get_links(GetLinksInputBuilder::try_new(input.author, LinkTypes::#link_type_name)?.build())?;
+                page(links, input.cursor, input.limit)
+            }
+        };
+    }
+
     quote! {
         use hdk::prelude::*;
         use #integrity_zome_name::*;
@@ -168,163 +460,979 @@ fn by_author_collection_getter(
     }
 }
 
-fn add_create_link_in_create_function(
-    dna_file_tree: DnaFileTree,
-    coordinator_zomes_for_integrity: &Vec<ZomeManifest>,
+fn by_path_collection_getter(
+    integrity_zome_name: &str,
     collection_name: &str,
     link_type_name: &str,
-    collection_type: &CollectionType,
-    entry_type_reference: &EntryTypeReference,
-) -> ScaffoldResult<DnaFileTree> {
-    let dna_manifest_path = dna_file_tree.dna_manifest_path.clone();
-
-    let (chosen_coordinator_zome, fn_name) = find_extern_function_or_choose(
-        &dna_file_tree,
-        coordinator_zomes_for_integrity,
-        &format!(
-            "create_{}",
-            entry_type_reference.entry_type.to_case(Case::Snake)
-        ),
-        &format!(
-            "At the end of which function should the {} entries be collected?",
-            entry_type_reference.entry_type.to_case(Case::Pascal)
-        ),
-    )?;
+) -> TokenStream {
+    let snake_collection_name = collection_name.to_case(Case::Snake);
+    let get_collection_function_name = format_ident!("get_{snake_collection_name}");
+    let get_collection_keys_function_name = format_ident!("get_{snake_collection_name}_keys");
+    let keys_link_type_name = format_ident!("{}", keys_link_type_name(link_type_name));
+    let link_type_name = format_ident!("{link_type_name}");
+    let integrity_zome_name = format_ident!("{integrity_zome_name}");
 
-    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, chosen_coordinator_zome)?;
+    quote! {
+        use hdk::prelude::*;
+        use #integrity_zome_name::*;
 
-    let snake_case_entry_type = entry_type_reference.entry_type.to_case(Case::Snake);
+        #[hdk_extern]
+        pub fn #get_collection_function_name(key: String) -> ExternResult<Vec<Link>> {
+            let path = Path::from(vec![Component::from(#snake_collection_name), Component::from(key)]);
+            get_links(GetLinksInputBuilder::try_new(path.path_entry_hash()?, LinkTypes::#link_type_name)?.build())
+        }
 
-    let mut create_link_stmts: Vec<syn::Stmt> = if entry_type_reference.reference_entry_hash {
-        let entry_hash_variable_name = format_ident!("{snake_case_entry_type}_entry_hash");
-        let snake_case_entry_type = format_ident!("{snake_case_entry_type}");
-        vec![parse_quote! {
-            let #entry_hash_variable_name = hash_entry(&#snake_case_entry_type)?;
-        }]
-    } else {
-        vec![]
-    };
+        #[hdk_extern]
+        pub fn #get_collection_keys_function_name() -> ExternResult<Vec<String>> {
+            let path = Path::from(#snake_collection_name).typed(LinkTypes::#keys_link_type_name)?;
+            path.children_paths()?
+                .into_iter()
+                .filter_map(|path| path.leaf().cloned())
+                .map(|component| String::try_from(&component).map_err(|e| wasm_error!(e)))
+                .collect()
+        }
+    }
+}
 
-    let link_to_variable = if entry_type_reference.reference_entry_hash {
-        format_ident!("{snake_case_entry_type}_entry_hash")
-    } else {
-        format_ident!("{snake_case_entry_type}_hash")
-    };
+fn time_indexed_collection_getter(
+    integrity_zome_name: &str,
+    collection_name: &str,
+    link_type_name: &str,
+) -> TokenStream {
+    let snake_collection_name = collection_name.to_case(Case::Snake);
+    let get_collection_function_name = format_ident!("get_{snake_collection_name}");
+    let time_index_link_type_name = format_ident!("{}", time_index_link_type_name(link_type_name));
     let link_type_name = format_ident!("{link_type_name}");
+    let integrity_zome_name = format_ident!("{integrity_zome_name}");
+    let time_index_module = format_ident!("{TIME_INDEX_MODULE}");
 
-    match collection_type {
-        CollectionType::Global => {
-            create_link_stmts.push(parse_quote! {let path = Path::from(#collection_name);});
-            create_link_stmts.push(parse_quote! {
-                create_link(path.path_entry_hash()?, #link_to_variable.clone(), LinkTypes::#link_type_name, ())?;
-            });
+    quote! {
+        use hdk::prelude::*;
+        use #integrity_zome_name::*;
+
+        use crate::#time_index_module::{buckets_in_range, tag_timestamp, time_bucket, TimeRange};
+
+        /// Path of the hour bucket in which the entries created at the given timestamp are collected
+        pub fn time_index_path(timestamp: Timestamp) -> ExternResult<TypedPath> {
+            let mut components = vec![Component::from(#snake_collection_name)];
+            components.extend(time_bucket(&timestamp).into_iter().map(Component::from));
+            Path::from(components).typed(LinkTypes::#time_index_link_type_name)
         }
-        CollectionType::ByAuthor => {
-            create_link_stmts.push(parse_quote! {
-                let my_agent_pub_key = agent_info()?.agent_latest_pubkey;
-            });
-            create_link_stmts.push(parse_quote! {
-                create_link(my_agent_pub_key, #link_to_variable.clone(), LinkTypes::#link_type_name, ())?;
-            });
+
+        #[hdk_extern]
+        pub fn #get_collection_function_name(range: TimeRange) -> ExternResult<Vec<Link>> {
+            let root = Path::from(#snake_collection_name).typed(LinkTypes::#time_index_link_type_name)?;
+
+            let mut links = vec![];
+            for bucket in buckets_in_range(root, &time_bucket(&range.from), &time_bucket(&range.to))? {
+                for link in get_links(GetLinksInputBuilder::try_new(bucket.path_entry_hash()?, LinkTypes::#link_type_name)?.build())? {
+                    let timestamp = tag_timestamp(&link.tag)?;
+                    if timestamp >= range.from && timestamp <= range.to {
+                        links.push((timestamp, link));
+                    }
+                }
+            }
+            links.sort_by(|(a, _), (b, _)| b.cmp(a));
+            Ok(links.into_iter().map(|(_, link)| link).collect())
         }
-    };
+    }
+}
 
-    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+/// Helpers shared by the time-indexed collections of a coordinator zome, whose links are bucketed by the
+/// UTC hour in which their target was created and tagged with its exact timestamp
+fn time_index_module() -> TokenStream {
+    quote! {
+        use hdk::prelude::*;
 
-    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct TimeRange {
+            pub from: Timestamp,
+            pub to: Timestamp,
+        }
 
-    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
-    map_rust_files(
-        file_tree
-            .path_mut(&mut v.iter())
-            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
-        |_, mut file| {
-            file.items = file
-                .items
+        /// Tag of the links of time-indexed collections, with the timestamp of the action that created their target
+        pub fn timestamp_tag(timestamp: Timestamp) -> LinkTag {
+            LinkTag::new(timestamp.as_micros().to_be_bytes().to_vec())
+        }
+
+        /// Timestamp of the action that created the target of a link of a time-indexed collection
+        pub fn tag_timestamp(tag: &LinkTag) -> ExternResult<Timestamp> {
+            let bytes: [u8; 8] = tag
+                .clone()
+                .into_inner()
+                .try_into()
+                .map_err(|_| wasm_error!(WasmErrorInner::Guest("Malformed time index tag".to_string())))?;
+            Ok(Timestamp::from_micros(i64::from_be_bytes(bytes)))
+        }
+
+        /// Zero-padded UTC year, month, day and hour of the given timestamp
+        pub fn time_bucket(timestamp: &Timestamp) -> Vec<String> {
+            let (seconds, _) = timestamp.as_seconds_and_nanos();
+            let days = seconds.div_euclid(86_400);
+            let hour = seconds.rem_euclid(86_400) / 3_600;
+
+            // Civil date from the number of days since the unix epoch
+            let z = days + 719_468;
+            let era = z.div_euclid(146_097);
+            let day_of_era = z.rem_euclid(146_097);
+            let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+            let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+            let shifted_month = (5 * day_of_year + 2) / 153;
+            let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+            let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+            let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+            vec![
+                format!("{year:04}"),
+                format!("{month:02}"),
+                format!("{day:02}"),
+                format!("{hour:02}"),
+            ]
+        }
+
+        /// Hour buckets under the given path that overlap the given range, newest first
+        pub fn buckets_in_range(path: TypedPath, from: &[String], to: &[String]) -> ExternResult<Vec<TypedPath>> {
+            let depth = path.as_ref().len() - 1;
+            if depth == from.len() {
+                return Ok(vec![path]);
+            }
+
+            let mut children = path
+                .children_paths()?
                 .into_iter()
-                .map(|item| {
-                    if let syn::Item::Fn(mut item_fn) = item.clone() {
-                        if item_fn
-                            .attrs
-                            .iter()
-                            .any(|a| a.path().segments.iter().any(|s| s.ident == "hdk_extern"))
-                            && item_fn.sig.ident == fn_name.sig.ident
-                        {
-                            if let Some(return_stmt) = item_fn.block.stmts.pop() {
-                                item_fn
-                                    .block
-                                    .stmts
-                                    .extend(create_link_stmts.clone().into_iter());
-                                item_fn.block.stmts.push(return_stmt);
-                            }
-                            return syn::Item::Fn(item_fn);
-                        }
-                    }
-                    item
+                .map(|child| {
+                    let bucket = child.as_ref()[1..]
+                        .iter()
+                        .map(String::try_from)
+                        .collect::<Result<Vec<String>, _>>()
+                        .map_err(|e| wasm_error!(e))?;
+                    Ok((bucket, child))
                 })
-                .collect();
-            Ok(file)
-        },
-    )
-    .map_err(|e| match e {
-        ScaffoldError::MalformedFile(path, error) => {
-            ScaffoldError::MalformedFile(crate_src_path.join(path), error)
-        }
-        _ => e,
-    })?;
+                .collect::<ExternResult<Vec<(Vec<String>, TypedPath)>>>()?;
+            children.retain(|(bucket, _)| bucket[..] >= from[..=depth] && bucket[..] <= to[..=depth]);
+            children.sort_by(|(a, _), (b, _)| b.cmp(a));
 
-    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+            let mut buckets = vec![];
+            for (_, child) in children {
+                buckets.extend(buckets_in_range(child, from, to)?);
+            }
+            Ok(buckets)
+        }
+    }
+}
 
-    Ok(dna_file_tree)
+/// Name of the enum returned by the getter of a collection of several entry types
+fn item_type_name(collection_name: &str) -> String {
+    format!("{}Item", collection_name.to_case(Case::Pascal))
 }
 
-fn add_delete_link_in_delete_function(
-    dna_file_tree: DnaFileTree,
-    coordinator_zomes_for_integrity: &Vec<ZomeManifest>,
+/// Getter for a global or by-author collection of several entry types, which returns each link tagged with
+/// the type of the entry it points to
+fn multi_type_collection_getter(
+    integrity_zome_name: &str,
     collection_name: &str,
     link_type_name: &str,
     collection_type: &CollectionType,
-    entry_type_reference: &EntryTypeReference,
-) -> ScaffoldResult<(DnaFileTree, bool)> {
-    let dna_manifest_path = dna_file_tree.dna_manifest_path.clone();
+    entry_types: &[EntryTypeReference],
+) -> TokenStream {
+    let snake_collection_name = collection_name.to_case(Case::Snake);
+    let get_collection_function_name = format_ident!("get_{snake_collection_name}");
+    let item_type_name = format_ident!("{}", item_type_name(collection_name));
+    let link_type_name = format_ident!("{link_type_name}");
+    let integrity_zome_name = format_ident!("{integrity_zome_name}");
 
-    let Some((chosen_coordinator_zome, fn_name)) = find_extern_function_in_zomes(
-        &dna_file_tree,
-        coordinator_zomes_for_integrity,
-        &format!(
-            "delete_{}",
-            entry_type_reference.entry_type.to_case(Case::Snake)
+    let variants: Vec<syn::Ident> = entry_types
+        .iter()
+        .map(|et| format_ident!("{}", et.entry_type.to_case(Case::Pascal)))
+        .collect();
+    let tags: Vec<syn::LitByteStr> = variants
+        .iter()
+        .map(|v| syn::LitByteStr::new(v.to_string().as_bytes(), v.span()))
+        .collect();
+
+    let (input, base): (TokenStream, TokenStream) = match collection_type {
+        CollectionType::ByAuthor => (quote! { author: AgentPubKey }, quote! { author }),
+        _ => (
+            quote! {},
+            quote! { Path::from(#snake_collection_name).path_entry_hash()? },
         ),
-    )?
-    else {
-        return Ok((dna_file_tree, false));
     };
 
-    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, chosen_coordinator_zome)?;
+    quote! {
+        use hdk::prelude::*;
+        use #integrity_zome_name::*;
 
-    let snake_case_entry_type = entry_type_reference.entry_type.to_case(Case::Snake);
-    let pascal_entry_def_name = entry_type_reference.entry_type.to_case(Case::Pascal);
+        /// Link of the collection, tagged with the type of the entry it points to
+        #[derive(Serialize, Deserialize, Debug)]
+        #[serde(tag = "type")]
+        pub enum #item_type_name {
+            #(#variants { link: Link },)*
+        }
 
-    let target_hash_variable = if entry_type_reference.reference_entry_hash {
-        quote! {
-            record
-                .action()
-                .entry_hash()
-                .ok_or(wasm_error!(WasmErrorInner::Guest("Record does not have an entry".to_string())))?
-                .clone()
+        impl TryFrom<Link> for #item_type_name {
+            type Error = WasmError;
+
+            fn try_from(link: Link) -> Result<Self, Self::Error> {
+                match link.tag.0.as_slice() {
+                    #(#tags => Ok(Self::#variants { link }),)*
+                    _ => Err(wasm_error!(WasmErrorInner::Guest("Unknown entry type in the link tag".to_string()))),
+                }
+            }
         }
-    } else {
-        let original_hash = format_ident!("original_{snake_case_entry_type}_hash");
-        quote! {#original_hash}
-    };
 
-    let into_hash_fn = if entry_type_reference.reference_entry_hash {
+        #[hdk_extern]
+        pub fn #get_collection_function_name(#input) -> ExternResult<Vec<#item_type_name>> {
+            let links = get_links(GetLinksInputBuilder::try_new(#base, LinkTypes::#link_type_name)?.build())?;
+            links.into_iter().map(#item_type_name::try_from).collect()
+        }
+    }
+}
+
+/// Returns the entry types of the given collection if it collects more than one, from the variants of the enum
+/// returned by its getter
+pub fn find_collection_entry_types(
+    dna_file_tree: &DnaFileTree,
+    coordinator_zomes_for_integrity: &[ZomeManifest],
+    collection_name: &str,
+) -> ScaffoldResult<Option<Vec<EntryTypeReference>>> {
+    let item_type_name = item_type_name(collection_name);
+
+    for coordinator_zome in coordinator_zomes_for_integrity {
+        let zome_file_tree =
+            ZomeFileTree::from_zome_manifest(dna_file_tree.clone(), coordinator_zome.clone())?;
+        let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+        let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+        let entry_types = find_map_rust_files(
+            dna_file_tree
+                .file_tree_ref()
+                .path(&mut v.iter())
+                .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+            &|_, file| {
+                file.items.iter().find_map(|item| match item {
+                    syn::Item::Enum(item_enum) if item_enum.ident == item_type_name => Some(
+                        item_enum
+                            .variants
+                            .iter()
+                            .map(|variant| EntryTypeReference {
+                                entry_type: variant.ident.to_string(),
+                                reference_entry_hash: false,
+                            })
+                            .collect::<Vec<EntryTypeReference>>(),
+                    ),
+                    _ => None,
+                })
+            },
+        );
+
+        if let Some(entry_types) = entry_types.into_values().next() {
+            return Ok(Some(entry_types));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Returns the key field of the given by-path collection, from the statements that create its links
+pub fn find_by_path_key_field(
+    dna_file_tree: &DnaFileTree,
+    coordinator_zomes_for_integrity: &[ZomeManifest],
+    collection_name: &str,
+) -> ScaffoldResult<Option<String>> {
+    for coordinator_zome in coordinator_zomes_for_integrity {
+        let zome_file_tree =
+            ZomeFileTree::from_zome_manifest(dna_file_tree.clone(), coordinator_zome.clone())?;
+        let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+        let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+        let key_fields = find_map_rust_files(
+            dna_file_tree
+                .file_tree_ref()
+                .path(&mut v.iter())
+                .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+            &|_, file| {
+                let mut finder = KeyFieldFinder {
+                    collection_name,
+                    key_field: None,
+                };
+                finder.visit_file(file);
+                finder.key_field
+            },
+        );
+
+        if let Some(key_field) = key_fields.into_values().next() {
+            return Ok(Some(key_field));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Finds the key field of a by-path collection in the `vec![Component::from("<collection>"), Component::from(<entry>.<key_field>.clone())]`
+/// that builds the path of its links
+struct KeyFieldFinder<'a> {
+    collection_name: &'a str,
+    key_field: Option<String>,
+}
+
+impl<'a> KeyFieldFinder<'a> {
+    /// Argument of the given `Component::from(..)` call
+    fn component_from_argument(expr: &syn::Expr) -> Option<&syn::Expr> {
+        let syn::Expr::Call(call) = expr else {
+            return None;
+        };
+        let syn::Expr::Path(function) = call.func.as_ref() else {
+            return None;
+        };
+        let segments: Vec<String> = function
+            .path
+            .segments
+            .iter()
+            .map(|s| s.ident.to_string())
+            .collect();
+        (segments == ["Component", "from"] && call.args.len() == 1).then(|| &call.args[0])
+    }
+
+    fn path_key_field(&self, path_components: &Punctuated<syn::Expr, Token![,]>) -> Option<String> {
+        if path_components.len() != 2 {
+            return None;
+        }
+        let (collection, key) = (&path_components[0], &path_components[1]);
+        match Self::component_from_argument(collection)? {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(name),
+                ..
+            }) if name.value() == self.collection_name => {}
+            _ => return None,
+        }
+        let syn::Expr::MethodCall(clone_call) = Self::component_from_argument(key)? else {
+            return None;
+        };
+        match clone_call.receiver.as_ref() {
+            syn::Expr::Field(syn::ExprField {
+                member: syn::Member::Named(field),
+                ..
+            }) if clone_call.method == "clone" => Some(field.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl<'a, 'ast> Visit<'ast> for KeyFieldFinder<'a> {
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if self.key_field.is_none() && mac.path.is_ident("vec") {
+            if let Ok(path_components) =
+                mac.parse_body_with(Punctuated::<syn::Expr, Token![,]>::parse_terminated)
+            {
+                self.key_field = self.path_key_field(&path_components);
+            }
+        }
+        visit::visit_macro(self, mac);
+    }
+}
+
+/// Returns the filter of the given collection, from the condition around the statements that create its links
+pub fn find_collection_filter(
+    dna_file_tree: &DnaFileTree,
+    coordinator_zomes_for_integrity: &[ZomeManifest],
+    collection_name: &str,
+    link_type_name: &str,
+    collection_type: &CollectionType,
+    entry_type: &EntryTypeReference,
+) -> ScaffoldResult<Option<CollectionFilter>> {
+    let unfiltered_stmts: Vec<String> = create_link_stmts(
+        collection_name,
+        link_type_name,
+        collection_type,
+        entry_type,
+        false,
+        None,
+    )
+    .iter()
+    .map(normalized_tokens)
+    .collect();
+
+    for coordinator_zome in coordinator_zomes_for_integrity {
+        let zome_file_tree =
+            ZomeFileTree::from_zome_manifest(dna_file_tree.clone(), coordinator_zome.clone())?;
+        let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+        let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+        let filters = find_map_rust_files(
+            dna_file_tree
+                .file_tree_ref()
+                .path(&mut v.iter())
+                .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+            &|_, file| {
+                file.items.iter().find_map(|item| match item {
+                    syn::Item::Fn(item_fn) => item_fn.block.stmts.iter().find_map(|stmt| {
+                        let syn::Stmt::Expr(syn::Expr::If(expr_if), _) = stmt else {
+                            return None;
+                        };
+                        let stmts: Vec<String> = expr_if
+                            .then_branch
+                            .stmts
+                            .iter()
+                            .map(normalized_tokens)
+                            .collect();
+                        (expr_if.else_branch.is_none() && stmts == unfiltered_stmts)
+                            .then(|| CollectionFilter::from_condition(&expr_if.cond))
+                            .flatten()
+                    }),
+                    _ => None,
+                })
+            },
+        );
+
+        if let Some(filter) = filters.into_values().next() {
+            return Ok(Some(filter));
+        }
+    }
+
+    Ok(None)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_create_link_in_create_function(
+    dna_file_tree: DnaFileTree,
+    coordinator_zomes_for_integrity: &Vec<ZomeManifest>,
+    collection_name: &str,
+    link_type_name: &str,
+    collection_type: &CollectionType,
+    entry_type_reference: &EntryTypeReference,
+    tagged: bool,
+    filter: Option<&CollectionFilter>,
+    no_interactive: bool,
+) -> ScaffoldResult<DnaFileTree> {
+    let dna_manifest_path = dna_file_tree.dna_manifest_path.clone();
+
+    let (chosen_coordinator_zome, fn_name) = find_extern_function_or_choose(
+        &dna_file_tree,
+        coordinator_zomes_for_integrity,
+        &format!(
+            "create_{}",
+            entry_type_reference.entry_type.to_case(Case::Snake)
+        ),
+        &format!(
+            "At the end of which function should the {} entries be collected?",
+            entry_type_reference.entry_type.to_case(Case::Pascal)
+        ),
+        no_interactive,
+    )?;
+
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, chosen_coordinator_zome)?;
+
+    let create_link_stmts = create_link_stmts(
+        collection_name,
+        link_type_name,
+        collection_type,
+        entry_type_reference,
+        tagged,
+        filter,
+    );
+
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        |_, mut file| {
+            file.items = file
+                .items
+                .into_iter()
+                .map(|item| {
+                    if let syn::Item::Fn(mut item_fn) = item.clone() {
+                        if item_fn
+                            .attrs
+                            .iter()
+                            .any(|a| a.path().segments.iter().any(|s| s.ident == "hdk_extern"))
+                            && item_fn.sig.ident == fn_name.sig.ident
+                        {
+                            if let Some(return_stmt) = item_fn.block.stmts.pop() {
+                                item_fn.block.stmts.extend(create_link_stmts.clone());
+                                item_fn.block.stmts.push(return_stmt);
+                            }
+                            return syn::Item::Fn(item_fn);
+                        }
+                    }
+                    item
+                })
+                .collect();
+            Ok(file)
+        },
+    )
+    .map_err(|e| match e {
+        ScaffoldError::MalformedFile(path, error) => {
+            ScaffoldError::MalformedFile(crate_src_path.join(path), error)
+        }
+        _ => e,
+    })?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+
+    Ok(dna_file_tree)
+}
+
+/// Adds the statements that keep the collection links of an entry up to date to the function that updates it
+fn add_update_link_in_update_function(
+    dna_file_tree: DnaFileTree,
+    coordinator_zomes_for_integrity: &Vec<ZomeManifest>,
+    entry_type_reference: &EntryTypeReference,
+    update_link_stmts: &[syn::Stmt],
+) -> ScaffoldResult<DnaFileTree> {
+    let dna_manifest_path = dna_file_tree.dna_manifest_path.clone();
+
+    let Some((chosen_coordinator_zome, fn_name)) = find_extern_function_in_zomes(
+        &dna_file_tree,
+        coordinator_zomes_for_integrity,
+        &format!(
+            "update_{}",
+            entry_type_reference.entry_type.to_case(Case::Snake)
+        ),
+    )?
+    else {
+        return Ok(dna_file_tree);
+    };
+
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, chosen_coordinator_zome)?;
+
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        |file_path, mut file| {
+            file.items = file
+                .items
+                .into_iter()
+                .map(|item| {
+                    if let syn::Item::Fn(mut item_fn) = item.clone() {
+                        if item_fn
+                            .attrs
+                            .iter()
+                            .any(|a| a.path().segments.iter().any(|s| s.ident == "hdk_extern"))
+                            && item_fn.sig.ident == fn_name.sig.ident
+                        {
+                            if !insert_update_link_stmts(&mut item_fn, update_link_stmts) {
+                                return Err(ScaffoldError::MalformedFile(
+                                    file_path.clone(),
+                                    format!("{} doesn't call update_entry", item_fn.sig.ident),
+                                ));
+                            }
+                            return Ok(syn::Item::Fn(item_fn));
+                        }
+                    }
+                    Ok(item)
+                })
+                .collect::<ScaffoldResult<Vec<syn::Item>>>()?;
+            Ok(file)
+        },
+    )
+    .map_err(|e| match e {
+        ScaffoldError::MalformedFile(path, error) => {
+            ScaffoldError::MalformedFile(crate_src_path.join(path), error)
+        }
+        _ => e,
+    })?;
+
+    DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)
+}
+
+fn add_delete_link_in_delete_function(
+    dna_file_tree: DnaFileTree,
+    coordinator_zomes_for_integrity: &Vec<ZomeManifest>,
+    collection_name: &str,
+    link_type_name: &str,
+    collection_type: &CollectionType,
+    entry_type_reference: &EntryTypeReference,
+) -> ScaffoldResult<(DnaFileTree, bool)> {
+    let dna_manifest_path = dna_file_tree.dna_manifest_path.clone();
+
+    let Some((chosen_coordinator_zome, fn_name)) = find_extern_function_in_zomes(
+        &dna_file_tree,
+        coordinator_zomes_for_integrity,
+        &format!(
+            "delete_{}",
+            entry_type_reference.entry_type.to_case(Case::Snake)
+        ),
+    )?
+    else {
+        return Ok((dna_file_tree, false));
+    };
+
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, chosen_coordinator_zome)?;
+
+    let delete_link_stmts = delete_link_stmts(
+        collection_name,
+        link_type_name,
+        collection_type,
+        entry_type_reference,
+    );
+
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        |_, mut file| {
+            file.items = file
+                .items
+                .into_iter()
+                .map(|item| {
+                    if let syn::Item::Fn(mut item_fn) = item.clone() {
+                        if item_fn
+                            .attrs
+                            .iter()
+                            .any(|a| a.path().segments.iter().any(|s| s.ident == "hdk_extern"))
+                            && item_fn.sig.ident == fn_name.sig.ident
+                        {
+                            if let Some(delete_stmt) = item_fn.block.stmts.pop() {
+                                item_fn.block.stmts.extend(delete_link_stmts.clone());
+                                item_fn.block.stmts.push(delete_stmt);
+                            }
+                            return syn::Item::Fn(item_fn);
+                        }
+                    }
+                    item
+                })
+                .collect();
+            Ok(file)
+        },
+    )
+    .map_err(|e| match e {
+        ScaffoldError::MalformedFile(path, error) => {
+            ScaffoldError::MalformedFile(crate_src_path.join(path), error)
+        }
+        _ => e,
+    })?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+
+    Ok((dna_file_tree, true))
+}
+
+/// Statements that link a newly created entry to the collection
+///
+/// When `tagged` is set, the links are tagged with the entry type so that the getter of a collection
+/// of several entry types can tell them apart
+fn create_link_stmts(
+    collection_name: &str,
+    link_type_name: &str,
+    collection_type: &CollectionType,
+    entry_type_reference: &EntryTypeReference,
+    tagged: bool,
+    filter: Option<&CollectionFilter>,
+) -> Vec<syn::Stmt> {
+    let snake_case_entry_type = entry_type_reference.entry_type.to_case(Case::Snake);
+    let tag: syn::Expr = match tagged {
+        true => {
+            let pascal_entry_type = entry_type_reference.entry_type.to_case(Case::Pascal);
+            parse_quote! { LinkTag::new(#pascal_entry_type) }
+        }
+        false => parse_quote! { () },
+    };
+
+    let mut create_link_stmts: Vec<syn::Stmt> = if entry_type_reference.reference_entry_hash {
+        let entry_hash_variable_name = format_ident!("{snake_case_entry_type}_entry_hash");
+        let snake_case_entry_type = format_ident!("{snake_case_entry_type}");
+        vec![parse_quote! {
+            let #entry_hash_variable_name = hash_entry(&#snake_case_entry_type)?;
+        }]
+    } else {
+        vec![]
+    };
+
+    let link_to_variable = if entry_type_reference.reference_entry_hash {
+        format_ident!("{snake_case_entry_type}_entry_hash")
+    } else {
+        format_ident!("{snake_case_entry_type}_hash")
+    };
+    let link_type_name = format_ident!("{link_type_name}");
+
+    match collection_type {
+        CollectionType::Global => {
+            create_link_stmts.push(parse_quote! {let path = Path::from(#collection_name);});
+            create_link_stmts.push(parse_quote! {
+                create_link(path.path_entry_hash()?, #link_to_variable.clone(), LinkTypes::#link_type_name, #tag)?;
+            });
+        }
+        CollectionType::ByAuthor => {
+            create_link_stmts.push(parse_quote! {
+                let my_agent_pub_key = agent_info()?.agent_latest_pubkey;
+            });
+            create_link_stmts.push(parse_quote! {
+                create_link(my_agent_pub_key, #link_to_variable.clone(), LinkTypes::#link_type_name, #tag)?;
+            });
+        }
+        CollectionType::ByPath { key_field } => {
+            let snake_case_entry_type = format_ident!("{snake_case_entry_type}");
+            let key_field = format_ident!("{key_field}");
+            let keys_link_type_name =
+                format_ident!("{}", keys_link_type_name(&link_type_name.to_string()));
+            create_link_stmts.push(parse_quote! {
+                let path = Path::from(vec![Component::from(#collection_name), Component::from(#snake_case_entry_type.#key_field.clone())]);
+            });
+            create_link_stmts.push(parse_quote! {
+                path.clone().typed(LinkTypes::#keys_link_type_name)?.ensure()?;
+            });
+            create_link_stmts.push(parse_quote! {
+                create_link(path.path_entry_hash()?, #link_to_variable.clone(), LinkTypes::#link_type_name, #tag)?;
+            });
+        }
+        CollectionType::TimeIndexed => {
+            let collection_module = format_ident!("{}", collection_name.to_case(Case::Snake));
+            let time_index_module = format_ident!("{TIME_INDEX_MODULE}");
+            let action_hash_variable = format_ident!("{snake_case_entry_type}_hash");
+            // The bucket and the tag of the link come from the same timestamp, which the getter filters by
+            create_link_stmts.push(parse_quote! {
+                let timestamp = must_get_action(#action_hash_variable.clone())?.action().timestamp();
+            });
+            create_link_stmts.push(parse_quote! {
+                let path = crate::#collection_module::time_index_path(timestamp)?;
+            });
+            create_link_stmts.push(parse_quote! {
+                path.ensure()?;
+            });
+            create_link_stmts.push(parse_quote! {
+                create_link(path.path_entry_hash()?, #link_to_variable.clone(), LinkTypes::#link_type_name, crate::#time_index_module::timestamp_tag(timestamp))?;
+            });
+        }
+    };
+
+    match filter {
+        Some(filter) => {
+            let condition = filter.condition(
+                &syn::parse_str(&snake_case_entry_type).expect("Failed to parse entry variable"),
+            );
+            vec![parse_quote! {
+                if #condition {
+                    #(#create_link_stmts)*
+                }
+            }]
+        }
+        None => create_link_stmts,
+    }
+}
+
+/// Inserts the statements that update the collection links right before the entry is updated,
+/// so they can still use the input of the function. Returns false if the function doesn't update the entry
+fn insert_update_link_stmts(item_fn: &mut syn::ItemFn, update_link_stmts: &[syn::Stmt]) -> bool {
+    let Some(index) = item_fn
+        .block
+        .stmts
+        .iter()
+        .position(|stmt| calls_function(stmt, "update_entry"))
+    else {
+        return false;
+    };
+    item_fn
+        .block
+        .stmts
+        .splice(index..index, update_link_stmts.iter().cloned());
+    true
+}
+
+/// Statements that keep the collection links of an entry up to date when it's updated, if the collection
+/// depends on its contents
+fn update_link_stmts(
+    collection_name: &str,
+    link_type_name: &str,
+    collection_type: &CollectionType,
+    entry_type_reference: &EntryTypeReference,
+    filter: Option<&CollectionFilter>,
+) -> Vec<syn::Stmt> {
+    match (collection_type, filter) {
+        (CollectionType::ByPath { key_field }, _) => move_path_link_stmts(
+            collection_name,
+            link_type_name,
+            entry_type_reference,
+            key_field,
+        ),
+        (_, Some(filter)) => filter_link_stmts(
+            collection_name,
+            link_type_name,
+            collection_type,
+            entry_type_reference,
+            filter,
+        ),
+        _ => vec![],
+    }
+}
+
+/// Statements that keep the link of an updated entry in a filtered collection only if it still matches the filter
+///
+/// The collection links target the original action of the entry, which is found walking back its chain of updates
+fn filter_link_stmts(
+    collection_name: &str,
+    link_type_name: &str,
+    collection_type: &CollectionType,
+    entry_type_reference: &EntryTypeReference,
+    filter: &CollectionFilter,
+) -> Vec<syn::Stmt> {
+    let snake_case_entry_type = entry_type_reference.entry_type.to_case(Case::Snake);
+    let original_hash = format_ident!("original_{snake_case_entry_type}_hash");
+    let previous_hash = format_ident!("previous_{snake_case_entry_type}_hash");
+    let condition = filter.condition(
+        &syn::parse_str(&format!("input.updated_{snake_case_entry_type}"))
+            .expect("Failed to parse updated entry expression"),
+    );
+    let link_type_name = format_ident!("{link_type_name}");
+
+    let (base_stmt, base): (syn::Stmt, syn::Expr) = match collection_type {
+        CollectionType::ByAuthor => (
+            parse_quote! {
+                let author = must_get_action(#original_hash.clone())?.action().author().clone();
+            },
+            parse_quote! { author.clone() },
+        ),
+        _ => (
+            parse_quote! { let path = Path::from(#collection_name); },
+            parse_quote! { path.path_entry_hash()? },
+        ),
+    };
+
+    vec![
+        parse_quote! {
+            let mut #original_hash = input.#previous_hash.clone();
+        },
+        parse_quote! {
+            while let Action::Update(update) = must_get_action(#original_hash.clone())?.action().clone() {
+                #original_hash = update.original_action_address;
+            }
+        },
+        base_stmt,
+        parse_quote! {
+            let collection_links: Vec<Link> = get_links(
+                GetLinksInputBuilder::try_new(#base, LinkTypes::#link_type_name)?.build(),
+            )?
+            .into_iter()
+            .filter(|link| link.target.clone().into_action_hash().as_ref() == Some(&#original_hash))
+            .collect();
+        },
+        parse_quote! {
+            if #condition {
+                if collection_links.is_empty() {
+                    create_link(#base, #original_hash.clone(), LinkTypes::#link_type_name, ())?;
+                }
+            } else {
+                for link in collection_links {
+                    delete_link(link.create_link_hash)?;
+                }
+            }
+        },
+    ]
+}
+
+/// Statements that move the link of an updated entry in a by-path collection to the path of its new key,
+/// if the key field was changed
+///
+/// The links are created with the original entry as their target, so they keep it when they are moved
+fn move_path_link_stmts(
+    collection_name: &str,
+    link_type_name: &str,
+    entry_type_reference: &EntryTypeReference,
+    key_field: &str,
+) -> Vec<syn::Stmt> {
+    let snake_case_entry_type = entry_type_reference.entry_type.to_case(Case::Snake);
+    let pascal_entry_def_name =
+        format_ident!("{}", entry_type_reference.entry_type.to_case(Case::Pascal));
+    let error_message = format!("{pascal_entry_def_name} not found");
+    let entry_error_message = format!("Could not find the {snake_case_entry_type} entry");
+    let previous_hash = format_ident!("previous_{snake_case_entry_type}_hash");
+    let updated_entry = format_ident!("updated_{snake_case_entry_type}");
+    let previous_entry = format_ident!("previous_{snake_case_entry_type}");
+    let original_hash = format_ident!("original_{snake_case_entry_type}_hash");
+    let key_field = format_ident!("{key_field}");
+    let link_type_name = format_ident!("{link_type_name}");
+    let keys_link_type_name = format_ident!("{}", keys_link_type_name(&link_type_name.to_string()));
+
+    let target: syn::Expr = if entry_type_reference.reference_entry_hash {
+        parse_quote! {
+            must_get_action(#original_hash.clone())?
+                .action()
+                .entry_hash()
+                .ok_or(wasm_error!(WasmErrorInner::Guest("Record does not have an entry".to_string())))?
+                .clone()
+                .into()
+        }
+    } else {
+        parse_quote! { #original_hash.clone().into() }
+    };
+
+    vec![
+        parse_quote! {
+            let #previous_entry: #pascal_entry_def_name = get(input.#previous_hash.clone(), GetOptions::default())?
+                .ok_or(wasm_error!(WasmErrorInner::Guest(#error_message.to_string())))?
+                .entry()
+                .to_app_option()
+                .map_err(|e| wasm_error!(e))?
+                .ok_or(wasm_error!(WasmErrorInner::Guest(#entry_error_message.to_string())))?;
+        },
+        parse_quote! {
+            if #previous_entry.#key_field != input.#updated_entry.#key_field {
+                let mut #original_hash = input.#previous_hash.clone();
+                while let Action::Update(update) = must_get_action(#original_hash.clone())?.action().clone() {
+                    #original_hash = update.original_action_address;
+                }
+                let target: AnyLinkableHash = #target;
+                let previous_path = Path::from(vec![Component::from(#collection_name), Component::from(#previous_entry.#key_field.clone())]);
+                let links = get_links(
+                    GetLinksInputBuilder::try_new(previous_path.path_entry_hash()?, LinkTypes::#link_type_name)?.build(),
+                )?;
+                for link in links {
+                    if link.target == target {
+                        delete_link(link.create_link_hash)?;
+                    }
+                }
+                let path = Path::from(vec![Component::from(#collection_name), Component::from(input.#updated_entry.#key_field.clone())]);
+                path.clone().typed(LinkTypes::#keys_link_type_name)?.ensure()?;
+                create_link(path.path_entry_hash()?, target, LinkTypes::#link_type_name, ())?;
+            }
+        },
+    ]
+}
+
+fn delete_link_stmts(
+    collection_name: &str,
+    link_type_name: &str,
+    collection_type: &CollectionType,
+    entry_type_reference: &EntryTypeReference,
+) -> Vec<syn::Stmt> {
+    let snake_case_entry_type = entry_type_reference.entry_type.to_case(Case::Snake);
+    let pascal_entry_def_name = entry_type_reference.entry_type.to_case(Case::Pascal);
+
+    let target_hash_variable = if entry_type_reference.reference_entry_hash {
+        quote! {
+            record
+                .action()
+                .entry_hash()
+                .ok_or(wasm_error!(WasmErrorInner::Guest("Record does not have an entry".to_string())))?
+                .clone()
+        }
+    } else {
+        let original_hash = format_ident!("original_{snake_case_entry_type}_hash");
+        quote! {#original_hash}
+    };
+
+    let into_hash_fn = if entry_type_reference.reference_entry_hash {
         format_ident!("into_entry_hash")
     } else {
         format_ident!("into_action_hash")
     };
 
-    let delete_link_stmts: Vec<syn::Stmt> = match collection_type {
+    match collection_type {
         CollectionType::Global => {
             let link_type_name = format_ident!("{link_type_name}");
             vec![
@@ -378,53 +1486,278 @@ fn add_delete_link_in_delete_function(
                 },
             ]
         }
-    };
+        CollectionType::ByPath { key_field } => {
+            let original_hash = format_ident!("original_{snake_case_entry_type}_hash");
+            let error_message = format!("{pascal_entry_def_name} not found");
+            let entry_error_message = format!("Could not find the {snake_case_entry_type} entry");
+            let link_type_name = format_ident!("{link_type_name}");
+            let key_field = format_ident!("{key_field}");
+            let pascal_entry_def_name = format_ident!("{pascal_entry_def_name}");
+            let snake_case_entry_type = format_ident!("{snake_case_entry_type}");
+            vec![
+                parse_quote! {
+                    let details = get_details(#original_hash.clone(), GetOptions::default())?
+                    .ok_or(
+                        wasm_error!(WasmErrorInner::Guest(#error_message.to_string()))
+                    )?;
+                },
+                parse_quote! {
+                    let record = match details {
+                        Details::Record(details) => Ok(details.record),
+                        _ => Err(wasm_error!(WasmErrorInner::Guest("Malformed get details response".to_string()))),
+                    }?;
+                },
+                parse_quote! {
+                    let #snake_case_entry_type: #pascal_entry_def_name = record
+                        .entry()
+                        .to_app_option()
+                        .map_err(|e| wasm_error!(e))?
+                        .ok_or(wasm_error!(WasmErrorInner::Guest(#entry_error_message.to_string())))?;
+                },
+                parse_quote! {
+                    let path = Path::from(vec![Component::from(#collection_name), Component::from(#snake_case_entry_type.#key_field.clone())]);
+                },
+                parse_quote! {
+                    let links = get_links(
+                        GetLinksInputBuilder::try_new(path.path_entry_hash()?, LinkTypes::#link_type_name)?.build(),
+                    )?;
+                },
+                parse_quote! {
+                    for link in links {
+                        if let Some(hash) = link.target.#into_hash_fn() {
+                           if hash == #target_hash_variable {
+                                delete_link(link.create_link_hash)?;
+                            }
+                        }
+                    }
+                },
+            ]
+        }
+        CollectionType::TimeIndexed => {
+            let original_hash = format_ident!("original_{snake_case_entry_type}_hash");
+            let error_message = format!("{pascal_entry_def_name} not found");
+            let collection_module = format_ident!("{}", collection_name.to_case(Case::Snake));
+            let link_type_name = format_ident!("{link_type_name}");
+            vec![
+                parse_quote! {
+                    let record = get(#original_hash.clone(), GetOptions::default())?
+                        .ok_or(wasm_error!(WasmErrorInner::Guest(#error_message.to_string())))?;
+                },
+                parse_quote! {
+                    let path = crate::#collection_module::time_index_path(record.action().timestamp())?;
+                },
+                parse_quote! {
+                    let links = get_links(
+                        GetLinksInputBuilder::try_new(path.path_entry_hash()?, LinkTypes::#link_type_name)?.build(),
+                    )?;
+                },
+                parse_quote! {
+                    for link in links {
+                        if let Some(hash) = link.target.#into_hash_fn() {
+                           if hash == #target_hash_variable {
+                                delete_link(link.create_link_hash)?;
+                            }
+                        }
+                    }
+                },
+            ]
+        }
+    }
+}
 
-    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scaffold::entry_type::{
+        coordinator::update_handler,
+        crud::Policy,
+        definitions::{Cardinality, EntryDefinition, EntryVisibility, FieldDefinition, FieldType},
+    };
 
-    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+    #[test]
+    fn test_update_link_stmts_run_before_update_entry() {
+        let entry_def = EntryDefinition {
+            name: "post".to_string(),
+            fields: vec![FieldDefinition {
+                field_name: "status".to_string(),
+                field_type: FieldType::String,
+                widget: None,
+                cardinality: Cardinality::Single,
+                linked_from: None,
+                constraints: vec![],
+            }],
+            reference_entry_hash: false,
+            update_policy: Policy::Anyone,
+            delete_policy: Policy::Anyone,
+            immutable_fields: vec![],
+            visibility: EntryVisibility::Public,
+            required_validations: None,
+            entry_def_name: None,
+        };
+        let entry_type = EntryTypeReference {
+            entry_type: "post".to_string(),
+            reference_entry_hash: false,
+        };
+        let filter = CollectionFilter::String {
+            field: "status".to_string(),
+            value: "published".to_string(),
+        };
+        let stmts = update_link_stmts(
+            "published_posts",
+            "PublishedPosts",
+            &CollectionType::Global,
+            &entry_type,
+            Some(&filter),
+        );
 
-    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
-    map_rust_files(
-        file_tree
-            .path_mut(&mut v.iter())
-            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
-        |_, mut file| {
-            file.items = file
+        for link_from_original_to_each_update in [false, true] {
+            let file: syn::File = syn::parse2(update_handler(
+                &entry_def,
+                link_from_original_to_each_update,
+            ))
+            .unwrap();
+            let mut item_fn = file
                 .items
                 .into_iter()
-                .map(|item| {
-                    if let syn::Item::Fn(mut item_fn) = item.clone() {
-                        if item_fn
-                            .attrs
-                            .iter()
-                            .any(|a| a.path().segments.iter().any(|s| s.ident == "hdk_extern"))
-                            && item_fn.sig.ident == fn_name.sig.ident
-                        {
-                            if let Some(delete_stmt) = item_fn.block.stmts.pop() {
-                                item_fn
-                                    .block
-                                    .stmts
-                                    .extend(delete_link_stmts.clone().into_iter());
-                                item_fn.block.stmts.push(delete_stmt);
-                            }
-                            return syn::Item::Fn(item_fn);
-                        }
-                    }
-                    item
+                .find_map(|item| match item {
+                    syn::Item::Fn(item_fn) => Some(item_fn),
+                    _ => None,
                 })
+                .unwrap();
+
+            assert!(insert_update_link_stmts(&mut item_fn, &stmts));
+
+            let stmts: Vec<String> = item_fn
+                .block
+                .stmts
+                .iter()
+                .map(|stmt| stmt.to_token_stream().to_string())
                 .collect();
-            Ok(file)
-        },
-    )
-    .map_err(|e| match e {
-        ScaffoldError::MalformedFile(path, error) => {
-            ScaffoldError::MalformedFile(crate_src_path.join(path), error)
+            let original_hash_index = stmts
+                .iter()
+                .position(|stmt| {
+                    stmt.starts_with(
+                        "let mut original_post_hash = input . previous_post_hash . clone ()",
+                    )
+                })
+                .unwrap();
+            let update_entry_index = item_fn
+                .block
+                .stmts
+                .iter()
+                .position(|stmt| calls_function(stmt, "update_entry"))
+                .unwrap();
+            assert!(original_hash_index < update_entry_index);
+            // The previous hash must not be moved into update_entry, the input is also used after it
+            assert!(stmts[update_entry_index].contains("input . previous_post_hash . clone ()"));
         }
-        _ => e,
-    })?;
 
-    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+        let mut item_fn: syn::ItemFn = parse_quote! {
+            pub fn update_post(input: UpdatePostInput) -> ExternResult<Record> {
+                Err(wasm_error!(WasmErrorInner::Guest("Unimplemented".to_string())))
+            }
+        };
+        assert!(!insert_update_link_stmts(&mut item_fn, &stmts));
+    }
 
-    Ok((dna_file_tree, true))
+    #[test]
+    fn test_by_path_links_are_moved_when_the_key_field_changes() {
+        let entry_type = EntryTypeReference {
+            entry_type: "post".to_string(),
+            reference_entry_hash: false,
+        };
+        let collection_type = CollectionType::ByPath {
+            key_field: "category".to_string(),
+        };
+        let stmts = update_link_stmts(
+            "posts_by_category",
+            "PostsByCategory",
+            &collection_type,
+            &entry_type,
+            None,
+        );
+        let tokens: Vec<String> = stmts
+            .iter()
+            .map(|stmt| stmt.to_token_stream().to_string())
+            .collect();
+        assert!(
+            tokens[1].starts_with("if previous_post . category != input . updated_post . category")
+        );
+        assert!(tokens[1].contains("delete_link (link . create_link_hash) ?"));
+        assert!(tokens[1].contains("create_link (path . path_entry_hash () ? , target"));
+
+        // The key field is found both in the create and in the update statements
+        for stmts in [
+            create_link_stmts(
+                "posts_by_category",
+                "PostsByCategory",
+                &collection_type,
+                &entry_type,
+                false,
+                None,
+            ),
+            stmts,
+        ] {
+            let file: syn::File = parse_quote! {
+                pub fn handler(input: PostInput) -> ExternResult<()> {
+                    #(#stmts)*
+                    Ok(())
+                }
+            };
+            let mut finder = KeyFieldFinder {
+                collection_name: "posts_by_category",
+                key_field: None,
+            };
+            finder.visit_file(&file);
+            assert_eq!(finder.key_field, Some("category".to_string()));
+
+            let mut finder = KeyFieldFinder {
+                collection_name: "posts_by_author",
+                key_field: None,
+            };
+            finder.visit_file(&file);
+            assert_eq!(finder.key_field, None);
+        }
+    }
+
+    #[test]
+    fn test_time_indexed_links_are_bucketed_and_filtered_by_the_same_timestamp() {
+        let entry_type = EntryTypeReference {
+            entry_type: "post".to_string(),
+            reference_entry_hash: false,
+        };
+        let tokens: Vec<String> = create_link_stmts(
+            "recent_posts",
+            "RecentPosts",
+            &CollectionType::TimeIndexed,
+            &entry_type,
+            false,
+            None,
+        )
+        .iter()
+        .map(|stmt| stmt.to_token_stream().to_string())
+        .collect();
+        assert_eq!(
+            tokens[0],
+            "let timestamp = must_get_action (post_hash . clone ()) ? . action () . timestamp () ;"
+        );
+        assert!(tokens[1].contains("crate :: recent_posts :: time_index_path (timestamp)"));
+        assert!(tokens[3].contains("crate :: time_index :: timestamp_tag (timestamp)"));
+
+        let getter =
+            time_indexed_collection_getter("posts_integrity", "recent_posts", "RecentPosts")
+                .to_string();
+        assert!(getter.contains("let timestamp = tag_timestamp (& link . tag) ? ;"));
+        assert!(getter.contains("if timestamp >= range . from && timestamp <= range . to"));
+        assert!(!getter.contains("link . timestamp"));
+
+        // The date computations are only generated in the shared module
+        assert!(getter.contains(
+            "use crate :: time_index :: { buckets_in_range , tag_timestamp , time_bucket , TimeRange } ;"
+        ));
+        assert!(!getter.contains("fn time_bucket"));
+        assert!(time_index_module()
+            .to_string()
+            .contains("pub fn time_bucket (timestamp : & Timestamp) -> Vec < String >"));
+    }
 }
@@ -8,7 +8,7 @@ use crate::{
     },
     reserved_words::check_for_reserved_keywords,
     templates::{dna::scaffold_dna_templates, ScaffoldedTemplate},
-    utils::choose_directory_path,
+    utils::{check_interactive, choose_directory_path},
 };
 use build_fs_tree::{dir, file};
 use dialoguer::{theme::ColorfulTheme, Select};
@@ -39,6 +39,7 @@ impl DnaFileTree {
     pub fn get_or_choose(
         file_tree: FileTree,
         dna_name: Option<&str>,
+        no_interactive: bool,
     ) -> ScaffoldResult<DnaFileTree> {
         let dna_manifests = find_dna_manifests(&file_tree)?;
 
@@ -48,7 +49,7 @@ impl DnaFileTree {
                 .into_iter()
                 .last()
                 .ok_or(ScaffoldError::NoDnasFound),
-            (_, None) => choose_dna(dna_manifests.into_iter().collect()),
+            (_, None) => choose_dna(dna_manifests.into_iter().collect(), no_interactive),
             (_, Some(name)) => dna_manifests
                 .into_iter()
                 .find(|(_, m)| m.name().to_string().eq(name))
@@ -127,12 +128,14 @@ pub fn find_dna_manifests(
 
 fn choose_dna(
     dna_manifests: Vec<(PathBuf, DnaManifest)>,
+    no_interactive: bool,
 ) -> ScaffoldResult<(PathBuf, DnaManifest)> {
     let dna_names: Vec<String> = dna_manifests
         .iter()
         .map(|(_, m)| m.name().to_string())
         .collect();
 
+    check_interactive("the `--dna` option", no_interactive)?;
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("Multiple DNAs were found in this repository, choose one:")
         .default(0)
@@ -151,7 +154,10 @@ pub fn read_dna_manifest(
     Ok(manifest)
 }
 
-pub fn get_or_choose_dnas_dir_path(app_file_tree: &FileTree) -> ScaffoldResult<PathBuf> {
+pub fn get_or_choose_dnas_dir_path(
+    app_file_tree: &FileTree,
+    no_interactive: bool,
+) -> ScaffoldResult<PathBuf> {
     let default_path = default_dnas_dir_path();
     if dir_exists(app_file_tree, &default_path) {
         Ok(default_path.clone())
@@ -159,6 +165,7 @@ pub fn get_or_choose_dnas_dir_path(app_file_tree: &FileTree) -> ScaffoldResult<P
         choose_directory_path(
             &String::from("Which directory should the DNA be scaffolded in?"),
             app_file_tree,
+            no_interactive,
         )
     }
 }
@@ -167,6 +174,7 @@ pub fn scaffold_dna(
     app_file_tree: AppFileTree,
     template_file_tree: &FileTree,
     dna_name: &str,
+    no_interactive: bool,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
     check_for_reserved_keywords(dna_name)?;
 
@@ -180,7 +188,7 @@ pub fn scaffold_dna(
         }
     };
 
-    let dnas_path = get_or_choose_dnas_dir_path(app_file_tree.file_tree_ref())?;
+    let dnas_path = get_or_choose_dnas_dir_path(app_file_tree.file_tree_ref(), no_interactive)?;
 
     let dna_workdir_path = PathBuf::new()
         .join(&dnas_path)
@@ -3,28 +3,48 @@ use std::{ffi::OsString, path::PathBuf};
 use crate::{
     file_tree::FileTree,
     reserved_words::check_for_reserved_keywords,
-    templates::{entry_type::scaffold_entry_type_templates, ScaffoldedTemplate},
+    templates::{
+        entry_type::{scaffold_entry_type_field_templates, scaffold_entry_type_templates},
+        ScaffoldedTemplate,
+    },
+    utils::check_interactive,
 };
 
 use build_fs_tree::dir;
 use colored::Colorize;
 use convert_case::{Case, Casing};
 use dialoguer::{theme::ColorfulTheme, Select};
+use holochain_types::prelude::ZomeManifest;
 
 use crate::error::{ScaffoldError, ScaffoldResult};
 
 use self::{
-    coordinator::{add_crud_functions_to_coordinator, updates_link_name},
-    crud::Crud,
-    definitions::{EntryDefinition, EntryTypeReference, FieldDefinition, Referenceable},
+    coordinator::{
+        add_crud_functions_to_coordinator, add_fields_to_crud_functions_in_coordinator,
+        updates_link_name,
+    },
+    crud::{Crud, Policy},
+    definitions::{
+        EntryDefinition, EntryTypeReference, EntryVisibility, FieldDefinition, Referenceable,
+    },
     fields::choose_fields,
-    integrity::{add_entry_type_to_integrity_zome, get_all_entry_types},
+    integrity::{
+        add_entry_type_to_integrity_zome, add_field_constraints_dependencies_to_integrity_zome,
+        add_fields_to_entry_type_in_integrity_zome, get_all_entry_types, get_entry_type_policies,
+        get_entry_type_visibility,
+    },
 };
 
 use super::{
     app::AppFileTree,
-    link_type::{integrity::add_link_type_to_integrity_zome, link_type_name},
-    zome::{utils::get_coordinator_zomes_for_integrity, ZomeFileTree},
+    link_type::{
+        integrity::{add_link_type_to_integrity_zome, get_all_link_types},
+        link_type_name, LinkValidation,
+    },
+    zome::{
+        coordinator::find_extern_function_in_zomes, utils::get_coordinator_zomes_for_integrity,
+        ZomeFileTree,
+    },
 };
 
 pub mod coordinator;
@@ -34,19 +54,53 @@ pub mod fields;
 pub mod integrity;
 pub mod utils;
 
-// TODO: group some params into a new-type or prefer builder pattern
-#[allow(clippy::too_many_arguments)]
+/// The choices for the entry type being scaffolded, the ones left as `None` are asked for interactively
+#[derive(Debug, Default, Clone)]
+pub struct EntryTypeOptions {
+    /// The CRUD functions to scaffold for the entry type
+    pub crud: Option<Crud>,
+    /// Whether the entries are referenced by their `EntryHash` instead of their `ActionHash`
+    pub reference_entry_hash: Option<bool>,
+    /// Whether to create a link from the original entry to each update
+    pub link_from_original_to_each_update: Option<bool>,
+    /// Who can update the entries
+    pub update_policy: Policy,
+    /// Who can delete the entries
+    pub delete_policy: Policy,
+    /// The fields whose values can't change when an entry is updated
+    pub immutable_fields: Vec<String>,
+    /// Whether the entries are public or private
+    pub visibility: EntryVisibility,
+    /// The number of validations required before the entries stop being published
+    pub required_validations: Option<u8>,
+    /// Custom name for the entry definition
+    pub entry_def_name: Option<String>,
+    /// The fields of the entry type struct
+    pub fields: Option<Vec<FieldDefinition>>,
+}
+
 pub fn scaffold_entry_type(
     zome_file_tree: ZomeFileTree,
     template_file_tree: &FileTree,
     name: &str,
-    maybe_crud: Option<Crud>,
-    maybe_reference_entry_hash: Option<bool>,
-    maybe_link_from_original_to_each_update: Option<bool>,
-    maybe_fields: Option<&Vec<FieldDefinition>>,
+    options: EntryTypeOptions,
     no_ui: bool,
     no_spec: bool,
+    no_interactive: bool,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
+    let EntryTypeOptions {
+        crud: maybe_crud,
+        reference_entry_hash: maybe_reference_entry_hash,
+        link_from_original_to_each_update: maybe_link_from_original_to_each_update,
+        update_policy,
+        delete_policy,
+        immutable_fields,
+        visibility,
+        required_validations,
+        entry_def_name,
+        fields: maybe_fields,
+    } = options;
+
     check_for_reserved_keywords(name)?;
 
     if no_ui {
@@ -60,8 +114,8 @@ inadvertently reference or expect elements from the skipped entry type."#
 
     let fields = match maybe_fields {
         Some(f) => {
-            check_field_definitions(name, &zome_file_tree, f)?;
-            f.clone()
+            check_field_definitions(name, &zome_file_tree, &f)?;
+            f
         }
         None => {
             let v: Vec<OsString> = PathBuf::from("field-types")
@@ -73,6 +127,7 @@ inadvertently reference or expect elements from the skipped entry type."#
                 &zome_file_tree,
                 template_file_tree.path(&mut v.iter()).unwrap_or(&dir! {}),
                 no_ui,
+                no_interactive,
             )?
         }
     };
@@ -81,13 +136,17 @@ inadvertently reference or expect elements from the skipped entry type."#
 
     let crud = match maybe_crud {
         Some(c) => c,
-        None => Crud::choose()?,
+        None => Crud::choose(no_interactive)?,
     };
 
     let link_from_original_to_each_update = if crud.update {
         if let Some(l) = maybe_link_from_original_to_each_update {
             l
         } else {
+            check_interactive(
+                "the `--link-from-original-to-each-update` option",
+                no_interactive,
+            )?;
             let selection = Select::with_theme(&ColorfulTheme::default())
                 .with_prompt(
                     "Should a link from the original entry be created when this entry is updated?",
@@ -103,16 +162,38 @@ inadvertently reference or expect elements from the skipped entry type."#
         false
     };
 
+    if entry_def_name.as_ref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(ScaffoldError::InvalidArguments(
+            "The name of the entry definition can't be empty".to_string(),
+        ));
+    }
+
+    check_policies(
+        &crud,
+        update_policy,
+        delete_policy,
+        &immutable_fields,
+        &fields,
+    )?;
+
     let entry_def = EntryDefinition {
         name: name.to_owned(),
         fields,
         reference_entry_hash,
+        update_policy,
+        delete_policy,
+        immutable_fields,
+        visibility,
+        required_validations,
+        entry_def_name,
     };
     let entry_def_ts_types = entry_def.ts_type_codegen();
 
     let integrity_zome_name = zome_file_tree.zome_manifest.name.0.to_string();
 
-    let mut zome_file_tree = add_entry_type_to_integrity_zome(zome_file_tree, &entry_def, &crud)?;
+    let zome_file_tree = add_entry_type_to_integrity_zome(zome_file_tree, &entry_def, &crud)?;
+    let mut zome_file_tree =
+        add_field_constraints_dependencies_to_integrity_zome(zome_file_tree, &entry_def.fields)?;
 
     let linked_from: Vec<Referenceable> = entry_def
         .fields
@@ -127,35 +208,15 @@ inadvertently reference or expect elements from the skipped entry type."#
             &Some(l),
             &Some(entry_def.referenceable()),
             crud.delete,
+            None,
+            &LinkValidation::default(),
+            &None,
+            &None,
             &PathBuf::from(format!("{}.rs", entry_def.name.to_case(Case::Snake))),
         )?;
     }
 
-    let coordinator_zomes_for_integrity = get_coordinator_zomes_for_integrity(
-        &zome_file_tree.dna_file_tree.dna_manifest,
-        zome_file_tree.zome_manifest.name.0.as_ref(),
-    );
-
-    let coordinator_zome = match coordinator_zomes_for_integrity.len() {
-        0 => Err(ScaffoldError::NoCoordinatorZomesFoundForIntegrityZome(
-            zome_file_tree.dna_file_tree.dna_manifest.name(),
-            zome_file_tree.zome_manifest.name.to_string(),
-        )),
-        1 => Ok(coordinator_zomes_for_integrity[0].clone()),
-        _ => {
-            let names: Vec<String> = coordinator_zomes_for_integrity
-                .iter()
-                .map(|z| z.name.to_string())
-                .collect();
-            let selection = Select::with_theme(&ColorfulTheme::default())
-                .with_prompt("Which coordinator zome should the CRUD functions be scaffolded in?")
-                .default(0)
-                .items(&names[..])
-                .interact()?;
-
-            Ok(coordinator_zomes_for_integrity[selection].clone())
-        }
-    }?;
+    let coordinator_zome = choose_coordinator_zome_for_integrity(&zome_file_tree, no_interactive)?;
 
     if link_from_original_to_each_update {
         zome_file_tree = add_link_type_to_integrity_zome(
@@ -164,6 +225,10 @@ inadvertently reference or expect elements from the skipped entry type."#
             &Some(entry_def.referenceable()),
             &Some(entry_def.referenceable()),
             false,
+            None,
+            &LinkValidation::default(),
+            &None,
+            &None,
             &PathBuf::from(format!("{}.rs", entry_def.name.to_case(Case::Snake))),
         )?;
     }
@@ -181,7 +246,11 @@ inadvertently reference or expect elements from the skipped entry type."#
 
     let dna_manifest = zome_file_tree.dna_file_tree.dna_manifest.clone();
 
-    let app_file_tree = AppFileTree::get_or_choose(zome_file_tree.dna_file_tree.file_tree(), None)?;
+    let app_file_tree = AppFileTree::get_or_choose(
+        zome_file_tree.dna_file_tree.file_tree(),
+        None,
+        no_interactive,
+    )?;
 
     let app_name = app_file_tree.app_manifest.app_name().to_string();
 
@@ -200,6 +269,211 @@ inadvertently reference or expect elements from the skipped entry type."#
     )
 }
 
+/// Adds new fields to an already existing entry type, together with the links for the linked ones
+pub fn scaffold_entry_type_fields(
+    zome_file_tree: ZomeFileTree,
+    template_file_tree: &FileTree,
+    entry_type_name: &str,
+    maybe_fields: Option<&Vec<FieldDefinition>>,
+    no_ui: bool,
+    no_spec: bool,
+    no_interactive: bool,
+) -> ScaffoldResult<ScaffoldedTemplate> {
+    let pascal_entry_type_name = entry_type_name.to_case(Case::Pascal);
+
+    let entry_type_reference = get_all_entry_types(&zome_file_tree)?
+        .unwrap_or_default()
+        .into_iter()
+        .find(|et| et.entry_type == pascal_entry_type_name)
+        .ok_or(ScaffoldError::EntryTypeNotFound(
+            entry_type_name.to_owned(),
+            zome_file_tree.dna_file_tree.dna_manifest.name(),
+            zome_file_tree.zome_manifest.name.0.to_string(),
+        ))?;
+
+    let fields = match maybe_fields {
+        Some(f) => {
+            check_field_definitions(entry_type_name, &zome_file_tree, f)?;
+            f.clone()
+        }
+        None => {
+            let v: Vec<OsString> = PathBuf::from("field-types")
+                .iter()
+                .map(|s| s.to_os_string())
+                .collect();
+            choose_fields(
+                entry_type_name,
+                &zome_file_tree,
+                template_file_tree.path(&mut v.iter()).unwrap_or(&dir! {}),
+                no_ui,
+                no_interactive,
+            )?
+        }
+    };
+
+    let (update_policy, delete_policy, immutable_fields) =
+        get_entry_type_policies(&zome_file_tree, entry_type_name)?;
+
+    let entry_def = EntryDefinition {
+        name: entry_type_name.to_owned(),
+        fields,
+        reference_entry_hash: entry_type_reference.reference_entry_hash,
+        update_policy,
+        delete_policy,
+        immutable_fields,
+        visibility: get_entry_type_visibility(&zome_file_tree, entry_type_name)?,
+        required_validations: None,
+        entry_def_name: None,
+    };
+
+    let coordinator_zomes_for_integrity = get_coordinator_zomes_for_integrity(
+        &zome_file_tree.dna_file_tree.dna_manifest,
+        zome_file_tree.zome_manifest.name.0.as_ref(),
+    );
+
+    // The CRUD functions were scaffolded along with the entry type, find out which ones
+    let coordinator_zome = match find_extern_function_in_zomes(
+        &zome_file_tree.dna_file_tree,
+        &coordinator_zomes_for_integrity,
+        &format!("create_{}", entry_def.snake_case_name()),
+    )? {
+        Some((coordinator_zome, _)) => coordinator_zome,
+        None => choose_coordinator_zome_for_integrity(&zome_file_tree, no_interactive)?,
+    };
+    let has_function = |fn_name: String| -> ScaffoldResult<bool> {
+        Ok(find_extern_function_in_zomes(
+            &zome_file_tree.dna_file_tree,
+            &vec![coordinator_zome.clone()],
+            &fn_name,
+        )?
+        .is_some())
+    };
+    let crud = Crud {
+        update: has_function(format!("update_{}", entry_def.snake_case_name()))?,
+        delete: has_function(format!("delete_{}", entry_def.snake_case_name()))?,
+    };
+    let link_from_original_to_each_update = get_all_link_types(&zome_file_tree)?
+        .unwrap_or_default()
+        .contains(&updates_link_name(&entry_def.name));
+
+    let (zome_file_tree, entry_def_path) =
+        add_fields_to_entry_type_in_integrity_zome(zome_file_tree, &entry_def, &crud)?;
+    let mut zome_file_tree =
+        add_field_constraints_dependencies_to_integrity_zome(zome_file_tree, &entry_def.fields)?;
+
+    for l in entry_def
+        .fields
+        .iter()
+        .filter_map(|f| f.linked_from.clone())
+    {
+        zome_file_tree = add_link_type_to_integrity_zome(
+            zome_file_tree,
+            &link_type_name(&l, &entry_def.referenceable()),
+            &Some(l),
+            &Some(entry_def.referenceable()),
+            crud.delete,
+            None,
+            &LinkValidation::default(),
+            &None,
+            &None,
+            &entry_def_path,
+        )?;
+    }
+
+    let zome_file_tree =
+        ZomeFileTree::from_zome_manifest(zome_file_tree.dna_file_tree, coordinator_zome.clone())?;
+
+    let zome_file_tree =
+        add_fields_to_crud_functions_in_coordinator(zome_file_tree, &entry_def, &crud)?;
+
+    let dna_manifest = zome_file_tree.dna_file_tree.dna_manifest.clone();
+
+    let app_file_tree = AppFileTree::get_or_choose(
+        zome_file_tree.dna_file_tree.file_tree(),
+        None,
+        no_interactive,
+    )?;
+
+    let app_name = app_file_tree.app_manifest.app_name().to_string();
+
+    scaffold_entry_type_field_templates(
+        app_file_tree.file_tree(),
+        template_file_tree,
+        &app_name,
+        &dna_manifest.name(),
+        &coordinator_zome,
+        &entry_def,
+        &crud,
+        link_from_original_to_each_update,
+        no_ui,
+        no_spec,
+    )
+}
+
+fn choose_coordinator_zome_for_integrity(
+    zome_file_tree: &ZomeFileTree,
+    no_interactive: bool,
+) -> ScaffoldResult<ZomeManifest> {
+    let coordinator_zomes_for_integrity = get_coordinator_zomes_for_integrity(
+        &zome_file_tree.dna_file_tree.dna_manifest,
+        zome_file_tree.zome_manifest.name.0.as_ref(),
+    );
+
+    match coordinator_zomes_for_integrity.len() {
+        0 => Err(ScaffoldError::NoCoordinatorZomesFoundForIntegrityZome(
+            zome_file_tree.dna_file_tree.dna_manifest.name(),
+            zome_file_tree.zome_manifest.name.to_string(),
+        )),
+        1 => Ok(coordinator_zomes_for_integrity[0].clone()),
+        _ => {
+            let names: Vec<String> = coordinator_zomes_for_integrity
+                .iter()
+                .map(|z| z.name.to_string())
+                .collect();
+            check_interactive(
+                "a choice of coordinator zome for the CRUD functions, as the integrity zome has more than one",
+                no_interactive,
+            )?;
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Which coordinator zome should the CRUD functions be scaffolded in?")
+                .default(0)
+                .items(&names[..])
+                .interact()?;
+
+            Ok(coordinator_zomes_for_integrity[selection].clone())
+        }
+    }
+}
+
+/// Checks that the policies apply to the scaffolded CRUD functions and that the immutable fields exist
+fn check_policies(
+    crud: &Crud,
+    update_policy: Policy,
+    delete_policy: Policy,
+    immutable_fields: &[String],
+    fields: &[FieldDefinition],
+) -> ScaffoldResult<()> {
+    if !crud.update && (update_policy != Policy::Anyone || !immutable_fields.is_empty()) {
+        return Err(ScaffoldError::InvalidArguments(String::from(
+            "The update policy and the immutable fields only apply if update is selected in the crud argument",
+        )));
+    }
+    if !crud.delete && delete_policy != Policy::Anyone {
+        return Err(ScaffoldError::InvalidArguments(String::from(
+            "The delete policy only applies if delete is selected in the crud argument",
+        )));
+    }
+    if let Some(immutable_field) = immutable_fields
+        .iter()
+        .find(|f| !fields.iter().any(|field| field.field_name == **f))
+    {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "The immutable field {immutable_field} is not one of the fields of the entry type"
+        )));
+    }
+    Ok(())
+}
+
 fn check_field_definitions(
     entry_type_name: &str,
     zome_file_tree: &ZomeFileTree,
@@ -217,7 +491,9 @@ fn check_field_definitions(
         .iter()
         .filter_map(|f| f.linked_from.clone())
         .filter_map(|t| match t {
-            Referenceable::Agent { .. } | Referenceable::ExternalHash { .. } => None,
+            Referenceable::Agent { .. }
+            | Referenceable::ExternalHash { .. }
+            | Referenceable::Dna { .. } => None,
             Referenceable::EntryType(et) => Some(et),
         })
         .collect();
@@ -237,3 +513,160 @@ fn check_field_definitions(
         None => Ok(()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{
+        file_tree::file_content,
+        scaffold::{
+            dna::DnaFileTree,
+            entry_type::definitions::{Cardinality, FieldType},
+            happ_spec::{apply_happ_spec, HappSpec},
+            web_app::{
+                package_manager::PackageManager, scaffold_web_app, template_type::TemplateType,
+            },
+        },
+    };
+
+    fn posts_integrity_zome(file_tree: FileTree) -> ZomeFileTree {
+        let dna_file_tree = DnaFileTree::get_or_choose(file_tree, Some("forum"), true).unwrap();
+        ZomeFileTree::get_or_choose_integrity(dna_file_tree, Some("posts_integrity"), true).unwrap()
+    }
+
+    fn post_rs(file_tree: &FileTree) -> String {
+        file_content(
+            file_tree,
+            Path::new("dnas/forum/zomes/integrity/posts/src/post.rs"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_adding_fields_keeps_the_policies_of_the_entry_type() {
+        let template_file_tree = TemplateType::Vanilla.file_tree().unwrap();
+        let ScaffoldedTemplate { file_tree, .. } = scaffold_web_app(
+            "forum",
+            None,
+            PackageManager::Npm,
+            true,
+            &template_file_tree,
+            false,
+        )
+        .unwrap();
+        let spec = HappSpec::from_file_content(
+            Path::new("happ-spec.yml"),
+            r#"
+dnas:
+  - name: forum
+    zomes:
+      - name: posts
+        entry_types:
+          - name: post
+            update_policy: author
+            delete_policy: author
+            immutable_fields: [title]
+            fields: ["title:String:TextField"]
+          - name: comment
+            fields: ["comment:String:TextArea"]
+"#,
+        )
+        .unwrap();
+        let file_tree =
+            apply_happ_spec(file_tree, &template_file_tree, &spec, true, true, true).unwrap();
+
+        let post_rs_content = post_rs(&file_tree);
+        assert!(post_rs_content.contains("Posts can only be updated by their author"));
+        assert!(post_rs_content.contains("The title field cannot be updated"));
+        assert!(post_rs_content.contains("Posts can only be deleted by their author"));
+
+        let zome_file_tree = posts_integrity_zome(file_tree);
+        assert_eq!(
+            get_entry_type_policies(&zome_file_tree, "post").unwrap(),
+            (Policy::Author, Policy::Author, vec![String::from("title")])
+        );
+        assert_eq!(
+            get_entry_type_policies(&zome_file_tree, "comment").unwrap(),
+            (Policy::Anyone, Policy::Anyone, vec![])
+        );
+
+        let content = FieldDefinition {
+            field_name: "content".to_string(),
+            field_type: FieldType::String,
+            widget: None,
+            cardinality: Cardinality::Single,
+            linked_from: None,
+            constraints: vec![],
+        };
+        let ScaffoldedTemplate { file_tree, .. } = scaffold_entry_type_fields(
+            zome_file_tree,
+            &template_file_tree,
+            "post",
+            Some(&vec![content]),
+            true,
+            true,
+            true,
+        )
+        .unwrap();
+
+        assert!(post_rs(&file_tree).contains("pub content: String"));
+        assert_eq!(
+            get_entry_type_policies(&posts_integrity_zome(file_tree), "post").unwrap(),
+            (Policy::Author, Policy::Author, vec![String::from("title")])
+        );
+    }
+
+    #[test]
+    fn test_entry_type_linked_from_a_dna() {
+        let template_file_tree = TemplateType::Svelte.file_tree().unwrap();
+        let ScaffoldedTemplate { file_tree, .. } = scaffold_web_app(
+            "forum",
+            None,
+            PackageManager::Npm,
+            true,
+            &template_file_tree,
+            false,
+        )
+        .unwrap();
+        let spec = HappSpec::from_file_content(
+            Path::new("happ-spec.yml"),
+            r#"
+dnas:
+  - name: forum
+    zomes:
+      - name: posts
+        entry_types:
+          - name: post
+            fields: ["title:String", "community_dna:DnaHash::community"]
+"#,
+        )
+        .unwrap();
+        let file_tree =
+            apply_happ_spec(file_tree, &template_file_tree, &spec, false, false, true).unwrap();
+
+        let coordinator_post_rs = file_content(
+            &file_tree,
+            Path::new("dnas/forum/zomes/coordinator/posts/src/post.rs"),
+        )
+        .unwrap();
+        assert!(coordinator_post_rs.contains(
+            "ExternalHash::from_raw_36(post.community_dna.clone().get_raw_36().to_vec())"
+        ));
+        assert!(coordinator_post_rs.contains("pub fn get_posts_for_community(community: DnaHash)"));
+        assert!(coordinator_post_rs
+            .contains("ExternalHash::from_raw_36(community.get_raw_36().to_vec())"));
+
+        let common_ts =
+            file_content(&file_tree, Path::new("tests/src/forum/posts/common.ts")).unwrap();
+        assert!(common_ts.contains("community_dna: cell.cell_id[0],"));
+
+        let posts_for_community = file_content(
+            &file_tree,
+            Path::new("ui/src/forum/posts/PostsForCommunity.svelte"),
+        )
+        .unwrap();
+        assert!(posts_for_community.contains("export let community: DnaHash;"));
+    }
+}
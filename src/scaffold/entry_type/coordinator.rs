@@ -11,14 +11,18 @@ use crate::{
         dna::DnaFileTree,
         entry_type::definitions::FieldDefinition,
         link_type::{coordinator::get_links_handler, link_type_name},
-        zome::ZomeFileTree,
+        zome::{
+            coordinator::{calls_function, remove_signals_from_coordinator},
+            utils::remove_module_from_crate,
+            ZomeFileTree,
+        },
     },
     utils::unparse_pretty,
 };
 
 use super::{
     crud::Crud,
-    definitions::{Cardinality, EntryDefinition, FieldType},
+    definitions::{Cardinality, EntryDefinition, FieldType, Referenceable},
     integrity::find_ending_match_expr_in_block,
 };
 
@@ -144,6 +148,208 @@ pub fn add_crud_functions_to_coordinator(
     Ok(zome_file_tree)
 }
 
+/// Removes the file with the CRUD functions for the given entry type from the coordinator zome,
+/// along with the entry signals if there are no entry types left in its integrity zome
+pub fn remove_crud_functions_from_coordinator(
+    zome_file_tree: ZomeFileTree,
+    entry_type: &str,
+    no_entry_types_left: bool,
+) -> ScaffoldResult<ZomeFileTree> {
+    let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
+    let zome_manifest = zome_file_tree.zome_manifest.clone();
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    remove_module_from_crate(
+        &mut file_tree,
+        &crate_src_path,
+        &entry_type.to_case(Case::Snake),
+    )?;
+
+    if no_entry_types_left {
+        remove_signals_from_coordinator(
+            &mut file_tree,
+            &crate_src_path,
+            &["EntryCreated", "EntryUpdated", "EntryDeleted"],
+            &["Create", "Update", "Delete"],
+            &["get_entry_for_action"],
+        )?;
+    }
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)?;
+
+    Ok(zome_file_tree)
+}
+
+/// Creates, deletes and gets the links for the new linked fields of an already existing entry type,
+/// inside the CRUD functions that were scaffolded for it
+pub fn add_fields_to_crud_functions_in_coordinator(
+    zome_file_tree: ZomeFileTree,
+    entry_def: &EntryDefinition,
+    crud: &Crud,
+) -> ScaffoldResult<ZomeFileTree> {
+    let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
+    let zome_manifest = zome_file_tree.zome_manifest.clone();
+
+    let linked_fields: Vec<&FieldDefinition> = entry_def
+        .fields
+        .iter()
+        .filter(|f| f.linked_from.is_some())
+        .collect();
+
+    if linked_fields.is_empty() {
+        return Ok(zome_file_tree);
+    }
+
+    let snake_entry_def_name = format_ident!("{}", entry_def.snake_case_name());
+    let entry_hash_variable_name = format_ident!("{}_entry_hash", entry_def.snake_case_name());
+    let create_function_name = format_ident!("create_{snake_entry_def_name}");
+    let delete_function_name = format_ident!("delete_{snake_entry_def_name}");
+
+    let create_links: Vec<TokenStream> = linked_fields
+        .iter()
+        .filter_map(|f| {
+            f.linked_from.as_ref().map(|linked_from| {
+                create_link_for_cardinality(entry_def, &f.field_name, linked_from, &f.cardinality)
+            })
+        })
+        .collect();
+    let delete_links: Vec<TokenStream> = linked_fields
+        .iter()
+        .filter_map(|f| delete_link_for_field(entry_def, f))
+        .collect();
+    let get_links_handlers = linked_fields
+        .iter()
+        .filter_map(|f| {
+            f.linked_from.as_ref().map(|linked_from| {
+                syn::parse2::<syn::File>(get_links_handler(
+                    linked_from,
+                    &entry_def.referenceable(),
+                    crud.delete,
+                    None,
+                ))
+            })
+        })
+        .collect::<Result<Vec<syn::File>, syn::Error>>()?;
+
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        |file_path, mut file| {
+            let mut found_create_function = false;
+
+            for item in &mut file.items {
+                let syn::Item::Fn(item_fn) = item else {
+                    continue;
+                };
+
+                if item_fn.sig.ident == create_function_name {
+                    found_create_function = true;
+
+                    let mut stmts = Vec::new();
+                    if entry_def.reference_entry_hash
+                        && !declares_variable(&item_fn.block, &entry_hash_variable_name)
+                    {
+                        stmts.push(quote! {
+                            let #entry_hash_variable_name = hash_entry(&#snake_entry_def_name)?;
+                        });
+                    }
+                    stmts.extend(create_links.clone());
+
+                    // Right after the entry is created and hashed, so the new links can use its hashes
+                    let index = item_fn
+                        .block
+                        .stmts
+                        .iter()
+                        .rposition(|stmt| {
+                            calls_function(stmt, "create_entry")
+                                || calls_function(stmt, "hash_entry")
+                        })
+                        .ok_or(ScaffoldError::MalformedFile(
+                            file_path.clone(),
+                            format!("{create_function_name} doesn't call create_entry"),
+                        ))?;
+                    insert_stmts(&mut item_fn.block, index + 1, stmts)?;
+                }
+
+                if item_fn.sig.ident == delete_function_name && !delete_links.is_empty() {
+                    let mut stmts = Vec::new();
+                    if !declares_variable(&item_fn.block, &snake_entry_def_name) {
+                        stmts.push(get_original_entry_to_delete(entry_def));
+                    }
+                    stmts.extend(delete_links.clone());
+
+                    // Right before deleting the entry
+                    let index = item_fn
+                        .block
+                        .stmts
+                        .iter()
+                        .position(|stmt| calls_function(stmt, "delete_entry"))
+                        .ok_or(ScaffoldError::MalformedFile(
+                            file_path.clone(),
+                            format!("{delete_function_name} doesn't call delete_entry"),
+                        ))?;
+                    insert_stmts(&mut item_fn.block, index, stmts)?;
+                }
+            }
+
+            if found_create_function {
+                for handlers in &get_links_handlers {
+                    file.items.extend(handlers.items.clone());
+                }
+            }
+
+            Ok(file)
+        },
+    )
+    .map_err(|e| match e {
+        ScaffoldError::MalformedFile(path, error) => {
+            ScaffoldError::MalformedFile(crate_src_path.join(path), error)
+        }
+        _ => e,
+    })?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)?;
+
+    Ok(zome_file_tree)
+}
+
+fn declares_variable(block: &syn::Block, variable_name: &syn::Ident) -> bool {
+    block.stmts.iter().any(|stmt| match stmt {
+        syn::Stmt::Local(local) => match &local.pat {
+            syn::Pat::Ident(pat_ident) => pat_ident.ident == *variable_name,
+            syn::Pat::Type(pat_type) => {
+                matches!(&*pat_type.pat, syn::Pat::Ident(pat_ident) if pat_ident.ident == *variable_name)
+            }
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+fn insert_stmts(
+    block: &mut syn::Block,
+    index: usize,
+    stmts: Vec<TokenStream>,
+) -> ScaffoldResult<()> {
+    let new_block: syn::Block = syn::parse2(quote! { { #(#stmts)* } })?;
+
+    for (i, stmt) in new_block.stmts.into_iter().enumerate() {
+        block.stmts.insert(index + i, stmt);
+    }
+
+    Ok(())
+}
+
 fn no_update_read_handler(entry_def: &EntryDefinition) -> TokenStream {
     let hash_type = entry_def.referenceable().field_type().to_string();
     let snake_entry_def_name = entry_def.name.to_case(Case::Snake);
@@ -350,7 +556,7 @@ fn read_handler_with_linking_to_updates(entry_def: &EntryDefinition) -> TokenStr
 fn create_link_for_cardinality(
     entry_def: &EntryDefinition,
     field_name: &str,
-    link_type_name: &str,
+    linked_from: &Referenceable,
     cardinality: &Cardinality,
 ) -> TokenStream {
     let snake_entry_def_name = entry_def.snake_case_name();
@@ -360,29 +566,42 @@ fn create_link_for_cardinality(
         format_ident!("{snake_entry_def_name}_hash")
     };
 
-    let link_type_name = format_ident!("{link_type_name}");
+    let link_type_name = format_ident!(
+        "{}",
+        link_type_name(linked_from, &entry_def.referenceable())
+    );
     let field_name = format_ident!("{field_name}");
     let snake_entry_def_name = format_ident!("{snake_entry_def_name}");
 
     match cardinality {
-        Cardinality::Single => quote! {
-            create_link(
-                #snake_entry_def_name.#field_name.clone(),
-                #link_target.clone(),
-                LinkTypes::#link_type_name,
-                (),
-            )?;
-        },
-        Cardinality::Option => quote! {
-            if let Some(base) = #snake_entry_def_name.#field_name.clone() {
-                create_link(base, #link_target.clone(), LinkTypes::#link_type_name, ())?;
+        Cardinality::Single => {
+            let base = linked_from.link_base(quote!(#snake_entry_def_name.#field_name.clone()));
+            quote! {
+                create_link(
+                    #base,
+                    #link_target.clone(),
+                    LinkTypes::#link_type_name,
+                    (),
+                )?;
             }
-        },
-        Cardinality::Vector => quote! {
-            for base in #snake_entry_def_name.#field_name.clone() {
-                create_link(base, #link_target.clone(), LinkTypes::#link_type_name, ())?;
+        }
+        Cardinality::Option => {
+            let base = linked_from.link_base(quote!(base));
+            quote! {
+                if let Some(base) = #snake_entry_def_name.#field_name.clone() {
+                    create_link(#base, #link_target.clone(), LinkTypes::#link_type_name, ())?;
+                }
             }
-        },
+        }
+        Cardinality::Vector => {
+            let base = linked_from.link_base(quote!(base));
+            quote! {
+                for base in #snake_entry_def_name.#field_name.clone() {
+                    create_link(#base, #link_target.clone(), LinkTypes::#link_type_name, ())?;
+                }
+            }
+        }
+        Cardinality::Map => unreachable!("map fields can't be linked from"),
     }
 }
 
@@ -409,7 +628,7 @@ fn create_handler(entry_def: &EntryDefinition) -> TokenStream {
             create_links.push(create_link_for_cardinality(
                 entry_def,
                 &f.field_name,
-                &link_type_name(linked_from, &entry_def.referenceable()),
+                linked_from,
                 &f.cardinality,
             ));
         }
@@ -437,7 +656,7 @@ fn create_handler(entry_def: &EntryDefinition) -> TokenStream {
     }
 }
 
-fn update_handler(
+pub fn update_handler(
     entry_def: &EntryDefinition,
     link_from_original_to_each_update: bool,
 ) -> TokenStream {
@@ -473,7 +692,7 @@ fn update_handler_without_linking_on_each_update(entry_def: &EntryDefinition) ->
         #[hdk_extern]
         pub fn #update_entry_def_function_name(input: #update_input_struct) -> ExternResult<Record> {
             let #updated_entry_hash_variable_name = update_entry(
-                input.#previous_entry_def_hash, &input.#updated_entry_def
+                input.#previous_entry_def_hash.clone(), &input.#updated_entry_def
             )?;
 
             let record = get(#updated_entry_hash_variable_name.clone(), GetOptions::default())?
@@ -535,93 +754,110 @@ fn update_handler_linking_on_each_update(entry_def: &EntryDefinition) -> TokenSt
     }
 }
 
-fn delete_handler(entry_def: &EntryDefinition) -> TokenStream {
-    let pascal_entry_def_name = format_ident!("{}", entry_def.name.to_case(Case::Pascal));
-    let snake_entry_def_name = format_ident!("{}", entry_def.name.to_case(Case::Snake));
-
+/// Deletes the link created from the base referenced by the given field when the entry was created
+fn delete_link_for_field(
+    entry_def: &EntryDefinition,
+    field_def: &FieldDefinition,
+) -> Option<TokenStream> {
+    let linked_from = field_def.linked_from.as_ref()?;
+    let snake_entry_def_name = format_ident!("{}", entry_def.snake_case_name());
     let original_entry_hash = format_ident!("original_{}_hash", snake_entry_def_name);
+    let field_name = format_ident!("{}", field_def.field_name);
+    let link_type = format_ident!(
+        "{}",
+        link_type_name(linked_from, &entry_def.referenceable())
+    );
 
-    let linked_from_fields: Vec<FieldDefinition> = entry_def
-        .fields
-        .iter()
-        .filter(|field| field.linked_from.is_some())
-        .cloned()
-        .collect();
+    let field_base = linked_from.link_base(quote!(#snake_entry_def_name.#field_name.clone()));
+    let base = linked_from.link_base(quote!(base_address));
 
-    let delete_depending_links = if !linked_from_fields.is_empty() {
-        let mut delete_links = Vec::new();
-        for linked_from_field in linked_from_fields {
-            let linked_from = linked_from_field
-                .linked_from
-                .expect("Linked from is none after we filtered for some");
-            let field_name = format_ident!("{}", linked_from_field.field_name);
-            let link_type = format_ident!(
-                "{}",
-                link_type_name(&linked_from, &entry_def.referenceable())
-            );
-            let delete_this_link = match linked_from_field.cardinality {
-                Cardinality::Single => quote! {
-                    let links = get_links(
-                        GetLinksInputBuilder::try_new(#snake_entry_def_name.#field_name.clone(), LinkTypes::#link_type)?.build(),
-                    )?;
-                    for link in links {
-                        if let Some(action_hash) = link.target.into_action_hash() {
-                            if action_hash == #original_entry_hash {
-                                delete_link(link.create_link_hash)?;
-                            }
-                        }
+    Some(match field_def.cardinality {
+        Cardinality::Single => quote! {
+            let links = get_links(
+                GetLinksInputBuilder::try_new(#field_base, LinkTypes::#link_type)?.build(),
+            )?;
+            for link in links {
+                if let Some(action_hash) = link.target.into_action_hash() {
+                    if action_hash == #original_entry_hash {
+                        delete_link(link.create_link_hash)?;
                     }
-                },
-                Cardinality::Option => quote! {
-                    if let Some(base_address) = #snake_entry_def_name.#field_name.clone() {
-                        let links = get_links(
-                            GetLinksInputBuilder::try_new(base_address, LinkTypes::#link_type)?.build(),
-                        )?;
-                        for link in links {
-                            if let Some(action_hash) = link.target.into_action_hash() {
-                                if action_hash == #original_entry_hash {
-                                    delete_link(link.create_link_hash)?;
-                                }
-                            }
+                }
+            }
+        },
+        Cardinality::Option => quote! {
+            if let Some(base_address) = #snake_entry_def_name.#field_name.clone() {
+                let links = get_links(
+                    GetLinksInputBuilder::try_new(#base, LinkTypes::#link_type)?.build(),
+                )?;
+                for link in links {
+                    if let Some(action_hash) = link.target.into_action_hash() {
+                        if action_hash == #original_entry_hash {
+                            delete_link(link.create_link_hash)?;
                         }
                     }
-                },
-                Cardinality::Vector => quote! {
-                    for base_address in #snake_entry_def_name.#field_name {
-                        let links = get_links(
-                            GetLinksInputBuilder::try_new(base_address.clone(), LinkTypes::#link_type)?.build(),
-                        )?;
-                        for link in links {
-                            if let Some(action_hash) = link.target.into_action_hash() {
-                                if action_hash == #original_entry_hash {
-                                    delete_link(link.create_link_hash)?;
-                                }
-                            }
+                }
+            }
+        },
+        Cardinality::Vector => quote! {
+            for base_address in #snake_entry_def_name.#field_name {
+                let links = get_links(
+                    GetLinksInputBuilder::try_new(#base, LinkTypes::#link_type)?.build(),
+                )?;
+                for link in links {
+                    if let Some(action_hash) = link.target.into_action_hash() {
+                        if action_hash == #original_entry_hash {
+                            delete_link(link.create_link_hash)?;
                         }
                     }
-                },
-            };
-            delete_links.push(delete_this_link);
-        }
+                }
+            }
+        },
+        Cardinality::Map => unreachable!("map fields can't be linked from"),
+    })
+}
+
+/// Gets the entry about to be deleted, so that the links referencing it can be deleted too
+fn get_original_entry_to_delete(entry_def: &EntryDefinition) -> TokenStream {
+    let pascal_entry_def_name = format_ident!("{}", entry_def.pascal_case_name());
+    let snake_entry_def_name = format_ident!("{}", entry_def.snake_case_name());
+    let original_entry_hash = format_ident!("original_{}_hash", snake_entry_def_name);
+
+    let get_details_error_message = format!("{pascal_entry_def_name} not found");
+    let entry_from_record_error_message =
+        format!("{} record has no entry", entry_def.pascal_case_name());
+
+    quote! {
+        let details = get_details(#original_entry_hash.clone(), GetOptions::default())?
+            .ok_or(wasm_error!(WasmErrorInner::Guest(#get_details_error_message.to_string())))?;
+        let record = match details {
+            Details::Record(details) => Ok(details.record),
+            _ => Err(wasm_error!(WasmErrorInner::Guest("Malformed get details response".to_string()))),
+        }?;
+        let entry = record
+            .entry()
+            .as_option()
+            .ok_or(wasm_error!(
+                WasmErrorInner::Guest(#entry_from_record_error_message.to_string())
+            ))?;
+        let #snake_entry_def_name = <#pascal_entry_def_name>::try_from(entry)?;
+    }
+}
+
+fn delete_handler(entry_def: &EntryDefinition) -> TokenStream {
+    let snake_entry_def_name = format_ident!("{}", entry_def.name.to_case(Case::Snake));
+
+    let original_entry_hash = format_ident!("original_{}_hash", snake_entry_def_name);
 
-        let get_details_error_message = format!("{pascal_entry_def_name} not found");
-        let entry_from_record_error_message =
-            format!("{} record has no entry", entry_def.pascal_case_name());
+    let delete_links: Vec<TokenStream> = entry_def
+        .fields
+        .iter()
+        .filter_map(|field_def| delete_link_for_field(entry_def, field_def))
+        .collect();
 
+    let delete_depending_links = if !delete_links.is_empty() {
+        let get_original_entry = get_original_entry_to_delete(entry_def);
         quote! {
-            let details = get_details(#original_entry_hash.clone(), GetOptions::default())?
-                .ok_or(wasm_error!(WasmErrorInner::Guest(#get_details_error_message.to_string())))?;
-            let record = match details {
-                Details::Record(details) => Ok(details.record),
-                _ => Err(wasm_error!(WasmErrorInner::Guest("Malformed get details response".to_string()))),
-            }?;
-            let entry = record
-                .entry()
-                .as_option()
-                .ok_or(wasm_error!(
-                    WasmErrorInner::Guest(#entry_from_record_error_message.to_string())
-                ))?;
-            let #snake_entry_def_name = <#pascal_entry_def_name>::try_from(entry)?;
+            #get_original_entry
             #(#delete_links)*
         }
     } else {
@@ -701,6 +937,7 @@ fn initial_crud_handlers(
                 linked_from,
                 &entry_def.referenceable(),
                 crud.delete,
+                None,
             ));
         }
     }
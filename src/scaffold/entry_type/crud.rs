@@ -2,9 +2,10 @@ use std::str::FromStr;
 
 use anyhow::anyhow;
 use dialoguer::{theme::ColorfulTheme, MultiSelect};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::error::{ScaffoldError, ScaffoldResult};
+use crate::utils::check_interactive;
 
 #[derive(Debug, Default, Serialize, Clone, Copy)]
 pub struct Crud {
@@ -14,7 +15,8 @@ pub struct Crud {
 }
 
 impl Crud {
-    pub fn choose() -> ScaffoldResult<Self> {
+    pub fn choose(no_interactive: bool) -> ScaffoldResult<Self> {
+        check_interactive("the `--crud` option", no_interactive)?;
         let selections = MultiSelect::with_theme(&ColorfulTheme::default())
             .with_prompt("Which CRUD functions should be scaffolded (SPACE to select/unselect, ENTER to continue)?")
             .item_checked("Update", true)
@@ -30,6 +32,31 @@ impl Crud {
     }
 }
 
+/// Who is allowed to update or delete the entries of an entry type, checked in its integrity validation
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Policy {
+    /// Any agent can update or delete any entry
+    #[default]
+    Anyone,
+    /// Only the author of the original entry can update or delete it
+    Author,
+}
+
+impl FromStr for Policy {
+    type Err = ScaffoldError;
+
+    fn from_str(policy_str: &str) -> Result<Self, Self::Err> {
+        match policy_str {
+            "anyone" => Ok(Policy::Anyone),
+            "author" => Ok(Policy::Author),
+            _ => Err(ScaffoldError::InvalidArguments(format!(
+                "Invalid policy \"{policy_str}\": only \"anyone\" and \"author\" are allowed"
+            ))),
+        }
+    }
+}
+
 impl FromStr for Crud {
     type Err = ScaffoldError;
 
@@ -2,7 +2,7 @@ use anyhow::Context;
 use colored::Colorize;
 use convert_case::{Case, Casing};
 use holochain::test_utils::itertools::Itertools;
-use proc_macro2::TokenStream;
+use proc_macro2::{Literal, TokenStream};
 use quote::{format_ident, quote};
 use regex::Regex;
 use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
@@ -14,6 +14,8 @@ use crate::{
     utils::check_case,
 };
 
+use super::crud::Policy;
+
 #[derive(Deserialize, Debug, Clone, Serialize, Eq, PartialEq)]
 #[serde(tag = "type")]
 pub enum FieldType {
@@ -26,6 +28,15 @@ pub enum FieldType {
     I32,
     #[serde(rename = "f32")]
     F32,
+    #[serde(rename = "u64")]
+    U64,
+    #[serde(rename = "i64")]
+    I64,
+    #[serde(rename = "f64")]
+    F64,
+    #[serde(rename = "usize")]
+    Usize,
+    SerializedBytes,
     Timestamp,
     AgentPubKey,
     ActionHash,
@@ -36,6 +47,10 @@ pub enum FieldType {
         label: String,
         variants: Vec<String>,
     },
+    Struct {
+        label: String,
+        fields: Vec<StructField>,
+    },
 }
 
 impl FromStr for FieldType {
@@ -64,6 +79,11 @@ impl std::fmt::Display for FieldType {
             FieldType::U32 => "u32",
             FieldType::I32 => "i32",
             FieldType::F32 => "f32",
+            FieldType::U64 => "u64",
+            FieldType::I64 => "i64",
+            FieldType::F64 => "f64",
+            FieldType::Usize => "usize",
+            FieldType::SerializedBytes => "SerializedBytes",
             FieldType::Timestamp => "Timestamp",
             FieldType::ActionHash => "ActionHash",
             FieldType::EntryHash => "EntryHash",
@@ -71,6 +91,7 @@ impl std::fmt::Display for FieldType {
             FieldType::ExternalHash => "ExternalHash",
             FieldType::AgentPubKey => "AgentPubKey",
             FieldType::Enum { .. } => "Enum",
+            FieldType::Struct { .. } => "Struct",
         };
         write!(f, "{str}")
     }
@@ -84,6 +105,11 @@ impl FieldType {
             FieldType::U32,
             FieldType::I32,
             FieldType::F32,
+            FieldType::U64,
+            FieldType::I64,
+            FieldType::F64,
+            FieldType::Usize,
+            FieldType::SerializedBytes,
             FieldType::Timestamp,
             FieldType::ActionHash,
             FieldType::EntryHash,
@@ -94,9 +120,36 @@ impl FieldType {
                 label: String::new(),
                 variants: Vec::new(),
             },
+            FieldType::Struct {
+                label: String::new(),
+                fields: Vec::new(),
+            },
         ]
     }
 
+    /// The field types that the fields of a struct can have
+    pub fn struct_field_types() -> Vec<FieldType> {
+        FieldType::list()
+            .into_iter()
+            .filter(|field_type| field_type.can_be_struct_field())
+            .collect()
+    }
+
+    /// Composite types and hashes can't be nested inside a struct, as they need extra
+    /// code generation (type definitions and links) that only happens for top-level fields
+    fn can_be_struct_field(&self) -> bool {
+        !matches!(
+            self,
+            FieldType::Enum { .. }
+                | FieldType::Struct { .. }
+                | FieldType::ActionHash
+                | FieldType::EntryHash
+                | FieldType::DnaHash
+                | FieldType::ExternalHash
+                | FieldType::AgentPubKey
+        )
+    }
+
     pub fn parse_enum(fields_str: &str) -> ScaffoldResult<FieldType> {
         let mut str_path = fields_str.split(':');
 
@@ -115,6 +168,34 @@ impl FieldType {
         Ok(FieldType::Enum { label, variants })
     }
 
+    /// Parses a struct type, eg. "Address{street:String:TextField,zip:Option<u32>}"
+    pub fn parse_struct(struct_str: &str) -> ScaffoldResult<FieldType> {
+        let struct_regex = Regex::new(r"\A(?P<label>\w+)\{(?P<fields>.*)\}\z").unwrap();
+
+        let captures = struct_regex.captures(struct_str).ok_or_else(|| {
+            ScaffoldError::InvalidArguments(format!(
+                "Invalid struct type: {struct_str}\nExample: \"{}\"",
+                "Address{street:String,zip:String}".italic()
+            ))
+        })?;
+        let label = captures["label"].to_string();
+        check_case(&label, "struct name", Case::Pascal)?;
+
+        let fields = split_top_level(&captures["fields"], ',')
+            .into_iter()
+            .filter(|f| !f.is_empty())
+            .map(|f| StructField::try_from(FieldDefinition::from_str(f)?))
+            .collect::<ScaffoldResult<Vec<StructField>>>()?;
+
+        if fields.is_empty() {
+            return Err(ScaffoldError::InvalidArguments(format!(
+                "The struct {label} needs at least one field"
+            )));
+        }
+
+        Ok(FieldType::Struct { label, fields })
+    }
+
     pub fn rust_type(&self) -> TokenStream {
         use FieldType::*;
 
@@ -124,19 +205,86 @@ impl FieldType {
             U32 => quote!(u32),
             I32 => quote!(i32),
             F32 => quote!(f32),
+            U64 => quote!(u64),
+            I64 => quote!(i64),
+            F64 => quote!(f64),
+            Usize => quote!(usize),
+            SerializedBytes => quote!(SerializedBytes),
             Timestamp => quote!(Timestamp),
             ActionHash => quote!(ActionHash),
             DnaHash => quote!(DnaHash),
             EntryHash => quote!(EntryHash),
             ExternalHash => quote!(ExternalHash),
             AgentPubKey => quote!(AgentPubKey),
-            Enum { label, .. } => {
+            Enum { label, .. } | Struct { label, .. } => {
                 let ident = format_ident!("{}", label);
                 quote!(#ident)
             }
         }
     }
 
+    /// Rust literal for the given value of this numeric type, eg. to compare the field against it
+    pub fn literal(&self, value: &str) -> ScaffoldResult<TokenStream> {
+        let invalid_value =
+            || ScaffoldError::InvalidArguments(format!("\"{value}\" is not a valid {self} value"));
+
+        let (is_negative, literal) = match self {
+            FieldType::U32 => (
+                false,
+                Literal::u32_unsuffixed(value.parse().map_err(|_| invalid_value())?),
+            ),
+            FieldType::U64 => (
+                false,
+                Literal::u64_unsuffixed(value.parse().map_err(|_| invalid_value())?),
+            ),
+            FieldType::Usize => (
+                false,
+                Literal::usize_unsuffixed(value.parse().map_err(|_| invalid_value())?),
+            ),
+            FieldType::I32 => {
+                let value: i32 = value.parse().map_err(|_| invalid_value())?;
+                (value < 0, Literal::i64_unsuffixed((value as i64).abs()))
+            }
+            FieldType::I64 => {
+                let value: i64 = value.parse().map_err(|_| invalid_value())?;
+                (value < 0, Literal::u64_unsuffixed(value.unsigned_abs()))
+            }
+            FieldType::F32 => {
+                let value: f32 = value.parse().map_err(|_| invalid_value())?;
+                if !value.is_finite() {
+                    return Err(invalid_value());
+                }
+                (value < 0.0, Literal::f32_unsuffixed(value.abs()))
+            }
+            FieldType::F64 => {
+                let value: f64 = value.parse().map_err(|_| invalid_value())?;
+                if !value.is_finite() {
+                    return Err(invalid_value());
+                }
+                (value < 0.0, Literal::f64_unsuffixed(value.abs()))
+            }
+            _ => return Err(invalid_value()),
+        };
+
+        Ok(match is_negative {
+            true => quote!(-#literal),
+            false => quote!(#literal),
+        })
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            FieldType::U32
+                | FieldType::I32
+                | FieldType::F32
+                | FieldType::U64
+                | FieldType::I64
+                | FieldType::F64
+                | FieldType::Usize
+        )
+    }
+
     pub fn ts_type(&self) -> &str {
         use FieldType::*;
 
@@ -146,13 +294,41 @@ impl FieldType {
             U32 => "number",
             I32 => "number",
             F32 => "number",
+            U64 => "number",
+            I64 => "number",
+            F64 => "number",
+            Usize => "number",
+            SerializedBytes => "Uint8Array",
             Timestamp => "number",
             AgentPubKey => "AgentPubKey",
             ActionHash => "ActionHash",
             EntryHash => "EntryHash",
             DnaHash => "DnaHash",
             ExternalHash => "ExternalHash",
-            Enum { label, .. } => label,
+            Enum { label, .. } | Struct { label, .. } => label,
+        }
+    }
+
+    /// Typescript definition for a non-primitive type, eg. the union type for an enum
+    pub fn ts_type_definition(&self) -> Option<String> {
+        match self {
+            FieldType::Enum { label, variants } => Some(format!(
+                "export type {label} = {};",
+                variants
+                    .iter()
+                    .map(|v| format!("{{type: '{}'}}", v))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            )),
+            FieldType::Struct { label, fields } => Some(format!(
+                "export interface {label} {{\n{}\n}}",
+                fields
+                    .iter()
+                    .map(|f| ts_field_codegen(&f.field_name, &f.field_type, &f.cardinality))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )),
+            _ => None,
         }
     }
 
@@ -180,12 +356,26 @@ impl FieldType {
                 };
                 Some(enum_definition)
             }
+            FieldType::Struct { label, fields } => {
+                let label_ident = format_ident!("{}", label);
+                let fields = fields.iter().map(|f| {
+                    let name = format_ident!("{}", f.field_name);
+                    let rust_type = rust_type_with_cardinality(&f.field_type, &f.cardinality);
+                    quote! { pub #name: #rust_type }
+                });
+                Some(quote! {
+                    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+                    pub struct #label_ident {
+                      #(#fields),*
+                    }
+                })
+            }
             _ => None,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum Cardinality {
     #[serde(rename = "single")]
     Single,
@@ -193,6 +383,216 @@ pub enum Cardinality {
     Vector,
     #[serde(rename = "option")]
     Option,
+    #[serde(rename = "map")]
+    Map,
+}
+
+/// Field of a struct field type, which has no constraints nor links of its own
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StructField {
+    pub field_name: String,
+    pub field_type: FieldType,
+    pub widget: Option<String>,
+    pub cardinality: Cardinality,
+}
+
+impl TryFrom<FieldDefinition> for StructField {
+    type Error = ScaffoldError;
+
+    fn try_from(field_def: FieldDefinition) -> Result<Self, Self::Error> {
+        if !field_def.field_type.can_be_struct_field() {
+            return Err(ScaffoldError::InvalidArguments(format!(
+                "The field {} of a struct can't be of type {}, use one of: {}",
+                field_def.field_name,
+                field_def.field_type,
+                FieldType::struct_field_types()
+                    .iter()
+                    .map(|f| f.to_string())
+                    .join(", ")
+            )));
+        }
+        if field_def.linked_from.is_some() || !field_def.constraints.is_empty() {
+            return Err(ScaffoldError::InvalidArguments(format!(
+                "The field {} of a struct can't have links or constraints",
+                field_def.field_name
+            )));
+        }
+        Ok(StructField {
+            field_name: field_def.field_name,
+            field_type: field_def.field_type,
+            widget: field_def.widget,
+            cardinality: field_def.cardinality,
+        })
+    }
+}
+
+/// Validation rule for the values of a field, checked in the validation functions of its entry type
+///
+/// For `Vec` fields, `min_len`, `max_len` and `non_empty` apply to the number of items,
+/// and the other constraints apply to each of the items
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FieldConstraint {
+    MinLen {
+        value: usize,
+    },
+    MaxLen {
+        value: usize,
+    },
+    NonEmpty,
+    Regex {
+        pattern: String,
+    },
+    Range {
+        min: Option<String>,
+        max: Option<String>,
+    },
+    OneOf {
+        values: Vec<String>,
+    },
+}
+
+impl FromStr for FieldConstraint {
+    type Err = ScaffoldError;
+
+    fn from_str(constraint_str: &str) -> Result<Self, Self::Err> {
+        let (name, value) = match constraint_str.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (constraint_str, None),
+        };
+
+        let missing_value = || {
+            ScaffoldError::InvalidArguments(format!(
+                "The {name} constraint is missing its value\nExample: \"{}\"",
+                "title:String;min_len=3;max_len=100".italic()
+            ))
+        };
+        let parse_length = |value: Option<&str>| -> ScaffoldResult<usize> {
+            let value = value.ok_or_else(missing_value)?;
+            value.parse().map_err(|_| {
+                ScaffoldError::InvalidArguments(format!(
+                    "The value of the {name} constraint must be a positive integer, got \"{value}\""
+                ))
+            })
+        };
+
+        match name {
+            "min_len" => Ok(FieldConstraint::MinLen {
+                value: parse_length(value)?,
+            }),
+            "max_len" => Ok(FieldConstraint::MaxLen {
+                value: parse_length(value)?,
+            }),
+            "non_empty" => Ok(FieldConstraint::NonEmpty),
+            "regex" => {
+                let pattern = value.ok_or_else(missing_value)?;
+                Regex::new(pattern).map_err(|e| {
+                    ScaffoldError::InvalidArguments(format!(
+                        "Invalid pattern for the regex constraint: {e}"
+                    ))
+                })?;
+                Ok(FieldConstraint::Regex {
+                    pattern: pattern.to_string(),
+                })
+            }
+            "range" => {
+                let (min, max) = value
+                    .ok_or_else(missing_value)?
+                    .split_once("..")
+                    .ok_or(ScaffoldError::InvalidArguments(format!(
+                        "The value of the range constraint must be of the form <min>..<max>\nExample: \"{}\"",
+                        "age:u32;range=18..130".italic()
+                    )))?;
+                let bound = |b: &str| (!b.is_empty()).then(|| b.to_string());
+                Ok(FieldConstraint::Range {
+                    min: bound(min),
+                    max: bound(max),
+                })
+            }
+            "one_of" => Ok(FieldConstraint::OneOf {
+                values: value
+                    .ok_or_else(missing_value)?
+                    .split('|')
+                    .map(|v| v.to_string())
+                    .collect(),
+            }),
+            _ => Err(ScaffoldError::InvalidArguments(format!(
+                "Invalid field constraint \"{name}\": only {} are allowed",
+                FieldConstraint::list().join(", ").italic()
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for FieldConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldConstraint::MinLen { value } => write!(f, "min_len={value}"),
+            FieldConstraint::MaxLen { value } => write!(f, "max_len={value}"),
+            FieldConstraint::NonEmpty => write!(f, "non_empty"),
+            FieldConstraint::Regex { pattern } => write!(f, "regex={pattern}"),
+            FieldConstraint::Range { min, max } => write!(
+                f,
+                "range={}..{}",
+                min.as_deref().unwrap_or_default(),
+                max.as_deref().unwrap_or_default()
+            ),
+            FieldConstraint::OneOf { values } => write!(f, "one_of={}", values.join("|")),
+        }
+    }
+}
+
+impl FieldConstraint {
+    pub fn list() -> Vec<&'static str> {
+        vec![
+            "min_len",
+            "max_len",
+            "non_empty",
+            "regex",
+            "range",
+            "one_of",
+        ]
+    }
+
+    /// Checks that this constraint can be applied to a field of the given type and cardinality
+    pub fn check_field_type(
+        &self,
+        field_name: &str,
+        field_type: &FieldType,
+        cardinality: &Cardinality,
+    ) -> ScaffoldResult<()> {
+        let is_vector = matches!(cardinality, Cardinality::Vector | Cardinality::Map);
+
+        let applies = match self {
+            FieldConstraint::MinLen { .. }
+            | FieldConstraint::MaxLen { .. }
+            | FieldConstraint::NonEmpty => is_vector || *field_type == FieldType::String,
+            FieldConstraint::Regex { .. } => *field_type == FieldType::String,
+            FieldConstraint::Range { min, max } => {
+                if field_type.is_numeric() {
+                    for bound in min.iter().chain(max.iter()) {
+                        field_type.literal(bound)?;
+                    }
+                }
+                field_type.is_numeric()
+            }
+            FieldConstraint::OneOf { values } => {
+                if field_type.is_numeric() {
+                    for value in values {
+                        field_type.literal(value)?;
+                    }
+                }
+                field_type.is_numeric() || *field_type == FieldType::String
+            }
+        };
+
+        match applies {
+            true => Ok(()),
+            false => Err(ScaffoldError::InvalidArguments(format!(
+                "The {self} constraint can't be applied to the field {field_name} of type {field_type}"
+            ))),
+        }
+    }
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -202,6 +602,7 @@ pub struct FieldDefinition {
     pub widget: Option<String>,
     pub cardinality: Cardinality,
     pub linked_from: Option<Referenceable>,
+    pub constraints: Vec<FieldConstraint>,
 }
 
 impl FieldDefinition {
@@ -211,41 +612,117 @@ impl FieldDefinition {
         widget: Option<String>,
         cardinality: Cardinality,
         linked_from: Option<Referenceable>,
+        constraints: Vec<FieldConstraint>,
     ) -> Result<Self, ScaffoldError> {
         check_for_reserved_keywords(&field_name)?;
+        if linked_from.is_some() && cardinality == Cardinality::Map {
+            return Err(ScaffoldError::InvalidArguments(format!(
+                "The map field {field_name} can't be linked from, use a Vec field instead"
+            )));
+        }
+        for constraint in &constraints {
+            constraint.check_field_type(&field_name, &field_type, &cardinality)?;
+        }
         Ok(FieldDefinition {
             field_name,
             field_type,
             widget,
             cardinality,
             linked_from,
+            constraints,
         })
     }
 }
 
 impl FieldDefinition {
     pub fn rust_type(&self) -> TokenStream {
-        match self.cardinality {
-            Cardinality::Single => self.field_type.rust_type(),
-            Cardinality::Option => {
-                let rust_representation_type = self.field_type.rust_type();
+        rust_type_with_cardinality(&self.field_type, &self.cardinality)
+    }
 
-                quote! {Option<#rust_representation_type>}
-            }
-            Cardinality::Vector => {
-                let rust_representation_type = self.field_type.rust_type();
+    /// Parses the values of a `--fields` option, which can contain several comma separated fields
+    /// and struct fields with commas between their braces, eg. "address:Address{street:String,zip:String}"
+    pub fn parse_list(values: &[String]) -> ScaffoldResult<Vec<FieldDefinition>> {
+        values
+            .iter()
+            .flat_map(|value| split_top_level(value, ','))
+            .filter(|field_str| !field_str.is_empty())
+            .map(FieldDefinition::from_str)
+            .collect()
+    }
+}
+
+fn rust_type_with_cardinality(field_type: &FieldType, cardinality: &Cardinality) -> TokenStream {
+    let rust_representation_type = field_type.rust_type();
+
+    match cardinality {
+        Cardinality::Single => rust_representation_type,
+        Cardinality::Option => quote! {Option<#rust_representation_type>},
+        Cardinality::Vector => quote! {Vec<#rust_representation_type>},
+        Cardinality::Map => {
+            quote! {std::collections::BTreeMap<String, #rust_representation_type>}
+        }
+    }
+}
+
+impl FieldDefinition {
+    /// Generate the declaration of this field inside the typescript interface of its entry type
+    pub fn ts_field_codegen(&self) -> String {
+        ts_field_codegen(&self.field_name, &self.field_type, &self.cardinality)
+    }
+}
+
+fn ts_field_codegen(field_name: &str, field_type: &FieldType, cardinality: &Cardinality) -> String {
+    let field_name = field_name.to_case(Case::Snake);
+    let ts_type = field_type.ts_type();
+
+    match cardinality {
+        Cardinality::Single => format!("  {field_name}: {ts_type};"),
+        Cardinality::Option => format!("  {field_name}: {ts_type} | undefined;"),
+        Cardinality::Vector => format!("  {field_name}: Array<{ts_type}>;"),
+        Cardinality::Map => format!("  {field_name}: Record<string, {ts_type}>;"),
+    }
+}
 
-                quote! {Vec<#rust_representation_type>}
+/// Splits the string by the separator, except where it appears between the braces of a struct type
+/// or between the brackets and parentheses of a regex constraint, eg. "regex=^[a-z]{2,5}$"
+fn split_top_level(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut escaped = false;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            // Escaped brackets of a regex, eg. "\(", don't open or close anything
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' | '[' | '(' => depth += 1,
+            '}' | ']' | ')' => depth -= 1,
+            c if c == separator && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
             }
+            _ => {}
         }
     }
+    parts.push(&s[start..]);
+
+    parts
 }
 
 impl FromStr for FieldDefinition {
     type Err = ScaffoldError;
 
     fn from_str(fields_str: &str) -> Result<Self, Self::Err> {
-        let mut str_path = fields_str.split(':');
+        // The constraints come after the field, separated by semicolons, eg. "title:String;min_len=3"
+        let mut constraints_str = split_top_level(fields_str, ';').into_iter();
+        let fields_str = constraints_str.next().unwrap_or_default();
+        let constraints = constraints_str
+            .filter(|c| !c.is_empty())
+            .map(FieldConstraint::from_str)
+            .collect::<ScaffoldResult<Vec<FieldConstraint>>>()?;
+
+        let mut str_path = split_top_level(fields_str, ':').into_iter();
 
         let field_name = str_path.next().context(format!(
             "field_name is missing from: {}\nExample: \"{}\"",
@@ -265,29 +742,34 @@ impl FromStr for FieldDefinition {
             "title:String".italic()
         ))?;
 
-        let vec_regex = Regex::new(r"Vec<(?P<a>(.)*)>\z").unwrap();
-        let option_regex = Regex::new(r"Option<(?P<a>(.)*)>\z").unwrap();
-
-        let (field_type, cardinality) = if vec_regex.is_match(field_type_str) {
-            let field_type = vec_regex.replace(field_type_str, "${a}");
+        let vec_regex = Regex::new(r"\AVec<(?P<a>(.)*)>\z").unwrap();
+        let option_regex = Regex::new(r"\AOption<(?P<a>(.)*)>\z").unwrap();
+        let map_regex = Regex::new(r"\AMap<(?P<a>(.)*)>\z").unwrap();
 
+        let parse_field_type = |field_type: &str| {
             if field_type == "Enum" {
-                (FieldType::parse_enum(fields_str)?, Cardinality::Vector)
+                FieldType::parse_enum(fields_str)
+            } else if field_type.contains('{') {
+                FieldType::parse_struct(field_type)
             } else {
-                (FieldType::from_str(&field_type)?, Cardinality::Vector)
+                FieldType::from_str(field_type)
             }
+        };
+
+        let (field_type, cardinality) = if field_type_str == "Vec<u8>" {
+            // Byte blobs are stored as SerializedBytes so that they get serialized as binary
+            (FieldType::SerializedBytes, Cardinality::Single)
+        } else if map_regex.is_match(field_type_str) {
+            let field_type = map_regex.replace(field_type_str, "${a}");
+            (parse_field_type(&field_type)?, Cardinality::Map)
+        } else if vec_regex.is_match(field_type_str) {
+            let field_type = vec_regex.replace(field_type_str, "${a}");
+            (parse_field_type(&field_type)?, Cardinality::Vector)
         } else if option_regex.is_match(field_type_str) {
             let field_type = option_regex.replace(field_type_str, "${a}");
-
-            if field_type == "Enum" {
-                (FieldType::parse_enum(fields_str)?, Cardinality::Option)
-            } else {
-                (FieldType::from_str(&field_type)?, Cardinality::Option)
-            }
-        } else if field_type_str == "Enum" {
-            (FieldType::parse_enum(fields_str)?, Cardinality::Single)
+            (parse_field_type(&field_type)?, Cardinality::Option)
         } else {
-            (FieldType::from_str(field_type_str)?, Cardinality::Single)
+            (parse_field_type(field_type_str)?, Cardinality::Single)
         };
 
         // XXX: perhaps widget-types can be validated at this level rather than
@@ -310,6 +792,9 @@ impl FromStr for FieldDefinition {
                         reference_entry_hash: matches!(field_type, FieldType::EntryHash),
                     }))
                 }
+                FieldType::DnaHash => Some(Referenceable::Dna {
+                    name: v.to_string(),
+                }),
                 _ => None,
             })
             .unwrap_or_default();
@@ -320,6 +805,7 @@ impl FromStr for FieldDefinition {
             widget,
             cardinality,
             linked_from,
+            constraints,
         )
     }
 }
@@ -381,9 +867,18 @@ impl FromStr for EntryTypeReference {
 
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub enum Referenceable {
-    Agent { role: String },
+    Agent {
+        role: String,
+    },
     EntryType(EntryTypeReference),
-    ExternalHash { name: String },
+    ExternalHash {
+        name: String,
+    },
+    /// A DNA referenced by its `DnaHash`, which can't be the base of links, so they are created from the
+    /// `ExternalHash` with the same raw bytes
+    Dna {
+        name: String,
+    },
 }
 
 impl Serialize for Referenceable {
@@ -434,6 +929,7 @@ impl Referenceable {
             Referenceable::Agent { .. } => FieldType::AgentPubKey,
             Referenceable::EntryType(r) => r.field_type(),
             Referenceable::ExternalHash { .. } => FieldType::ExternalHash,
+            Referenceable::Dna { .. } => FieldType::DnaHash,
         }
     }
 
@@ -441,7 +937,9 @@ impl Referenceable {
         let s = self.to_string(c).to_case(Case::Snake);
 
         match self {
-            Referenceable::Agent { .. } | Referenceable::ExternalHash { .. } => s,
+            Referenceable::Agent { .. }
+            | Referenceable::ExternalHash { .. }
+            | Referenceable::Dna { .. } => s,
             Referenceable::EntryType(e) => e.field_name(c),
         }
     }
@@ -450,7 +948,7 @@ impl Referenceable {
         let singular = match self {
             Referenceable::Agent { role } => role.clone(),
             Referenceable::EntryType(r) => r.entry_type.clone(),
-            Referenceable::ExternalHash { name } => name.clone(),
+            Referenceable::ExternalHash { name } | Referenceable::Dna { name } => name.clone(),
         };
 
         match c {
@@ -458,6 +956,40 @@ impl Referenceable {
             _ => singular,
         }
     }
+
+    /// The hash that the links are created from, given the expression of the hash referencing this
+    pub fn link_base(&self, hash: TokenStream) -> TokenStream {
+        match self {
+            Referenceable::Dna { .. } => quote! {
+                ExternalHash::from_raw_36(#hash.get_raw_36().to_vec())
+            },
+            _ => hash,
+        }
+    }
+}
+
+/// Whether the entries of an entry type are published to the DHT or only kept in the source chain of their author
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryVisibility {
+    #[default]
+    Public,
+    /// Private entries can only be read by their author
+    Private,
+}
+
+impl FromStr for EntryVisibility {
+    type Err = ScaffoldError;
+
+    fn from_str(visibility_str: &str) -> Result<Self, Self::Err> {
+        match visibility_str {
+            "public" => Ok(EntryVisibility::Public),
+            "private" => Ok(EntryVisibility::Private),
+            _ => Err(ScaffoldError::InvalidArguments(format!(
+                "Invalid visibility \"{visibility_str}\": only \"public\" and \"private\" are allowed"
+            ))),
+        }
+    }
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -465,6 +997,15 @@ pub struct EntryDefinition {
     pub name: String,
     pub fields: Vec<FieldDefinition>,
     pub reference_entry_hash: bool,
+    pub update_policy: Policy,
+    pub delete_policy: Policy,
+    /// Fields whose values can't change when the entry is updated
+    pub immutable_fields: Vec<String>,
+    pub visibility: EntryVisibility,
+    /// Number of validations required before the entries stop being published, holochain's default if `None`
+    pub required_validations: Option<u8>,
+    /// Name of the entry definition in the DNA, the name of the entry type if `None`
+    pub entry_def_name: Option<String>,
 }
 
 impl EntryDefinition {
@@ -493,36 +1034,11 @@ impl EntryDefinition {
         let mut ts_enums = String::new();
 
         for field in &self.fields {
-            let ts_type = field.field_type.ts_type();
-            if let FieldType::Enum { label, variants } = &field.field_type {
-                let enum_definition = format!(
-                    "export type {label} = {};\n",
-                    variants
-                        .iter()
-                        .map(|v| format!("{{type: '{}'}}", v))
-                        .collect::<Vec<_>>()
-                        .join(" | ")
-                );
-                ts_enums.push_str(&enum_definition);
+            if let Some(type_definition) = field.field_type.ts_type_definition() {
+                ts_enums.push_str(&type_definition);
+                ts_enums.push('\n');
             }
-            let ts_field = match field.cardinality {
-                Cardinality::Single => {
-                    format!("  {}: {};", &field.field_name.to_case(Case::Snake), ts_type)
-                }
-                Cardinality::Option => format!(
-                    "  {}: {} | undefined;",
-                    &field.field_name.to_case(Case::Snake),
-                    ts_type
-                ),
-                Cardinality::Vector => {
-                    format!(
-                        "  {}: Array<{}>;",
-                        &field.field_name.to_case(Case::Snake),
-                        ts_type
-                    )
-                }
-            };
-            ts_interface.push_str(&ts_field);
+            ts_interface.push_str(&field.ts_field_codegen());
             ts_interface.push('\n');
         }
         ts_interface.push('}');
@@ -548,6 +1064,7 @@ mod tests {
                     widget: Some("TextField".to_string()),
                     cardinality: Cardinality::Single,
                     linked_from: None,
+                    constraints: vec![],
                 },
                 FieldDefinition {
                     field_name: "content".to_string(),
@@ -555,9 +1072,16 @@ mod tests {
                     widget: Some("TextArea".to_string()),
                     cardinality: Cardinality::Single,
                     linked_from: None,
+                    constraints: vec![],
                 },
             ],
             reference_entry_hash: false,
+            update_policy: Policy::Anyone,
+            delete_policy: Policy::Anyone,
+            immutable_fields: vec![],
+            visibility: EntryVisibility::Public,
+            required_validations: None,
+            entry_def_name: None,
         };
 
         let comment_entry = EntryDefinition {
@@ -569,6 +1093,7 @@ mod tests {
                     widget: Some("TextArea".to_string()),
                     cardinality: Cardinality::Single,
                     linked_from: None,
+                    constraints: vec![],
                 },
                 FieldDefinition {
                     field_name: "post_hash".to_string(),
@@ -579,9 +1104,16 @@ mod tests {
                         entry_type: post_entry.name.to_string(),
                         reference_entry_hash: false,
                     })),
+                    constraints: vec![],
                 },
             ],
             reference_entry_hash: false,
+            update_policy: Policy::Anyone,
+            delete_policy: Policy::Anyone,
+            immutable_fields: vec![],
+            visibility: EntryVisibility::Public,
+            required_validations: None,
+            entry_def_name: None,
         };
 
         let post_ts_interface = &post_entry.ts_type_codegen();
@@ -610,6 +1142,7 @@ mod tests {
                     widget: None,
                     cardinality: Cardinality::Single,
                     linked_from: None,
+                    constraints: vec![],
                 },
                 FieldDefinition {
                     field_name: "field_two".to_string(),
@@ -617,6 +1150,7 @@ mod tests {
                     widget: None,
                     cardinality: Cardinality::Option,
                     linked_from: None,
+                    constraints: vec![],
                 },
                 FieldDefinition {
                     field_name: "field_three".to_string(),
@@ -624,6 +1158,7 @@ mod tests {
                     widget: None,
                     cardinality: Cardinality::Vector,
                     linked_from: None,
+                    constraints: vec![],
                 },
                 FieldDefinition {
                     field_name: "enum_field".to_string(),
@@ -638,9 +1173,16 @@ mod tests {
                     widget: None,
                     cardinality: Cardinality::Single,
                     linked_from: None,
+                    constraints: vec![],
                 },
             ],
             reference_entry_hash: false,
+            update_policy: Policy::Anyone,
+            delete_policy: Policy::Anyone,
+            immutable_fields: vec![],
+            visibility: EntryVisibility::Public,
+            required_validations: None,
+            entry_def_name: None,
         };
 
         let ts_interface = &other_entry.ts_type_codegen();
@@ -656,4 +1198,123 @@ export interface ExampleEntry {
 
         assert_eq!(ts_interface, expected_ts_interface);
     }
+
+    #[test]
+    fn test_field_definition_with_constraints_from_str() {
+        let field = FieldDefinition::from_str("nickname:String:TextField;min_len=3;regex=^[a-z]+$")
+            .unwrap();
+        assert_eq!(field.widget, Some("TextField".to_string()));
+        assert_eq!(
+            field.constraints,
+            vec![
+                FieldConstraint::MinLen { value: 3 },
+                FieldConstraint::Regex {
+                    pattern: "^[a-z]+$".to_string()
+                }
+            ]
+        );
+
+        let field = FieldDefinition::from_str("age:Option<i32>;range=-5..").unwrap();
+        assert_eq!(
+            field.constraints,
+            vec![FieldConstraint::Range {
+                min: Some("-5".to_string()),
+                max: None
+            }]
+        );
+
+        assert!(FieldDefinition::from_str("age:u32;min_len=3").is_err());
+        assert!(FieldDefinition::from_str("age:u32;range=-1..10").is_err());
+        assert!(FieldDefinition::from_str("title:String;unknown").is_err());
+    }
+
+    #[test]
+    fn test_field_definition_with_struct_from_str() {
+        let fields = FieldDefinition::parse_list(&[
+            "address:Option<Address{street:String:TextField,zip:u32}>:Form;".to_string(),
+            "title:String".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].widget, Some("Form".to_string()));
+        assert!(matches!(fields[0].cardinality, Cardinality::Option));
+        assert_eq!(
+            fields[0].field_type.ts_type_definition(),
+            Some("export interface Address {\n  street: string;\n  zip: number;\n}".to_string())
+        );
+
+        assert!(FieldDefinition::from_str("address:Address{}").is_err());
+    }
+
+    #[test]
+    fn test_field_definitions_with_commas_in_regex_from_str() {
+        let fields = FieldDefinition::parse_list(&[
+            "code:String;regex=^[a-z,]{2,5}$,title:String;regex=^(a|b,c)$".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(
+            fields[0].constraints,
+            vec![FieldConstraint::Regex {
+                pattern: "^[a-z,]{2,5}$".to_string()
+            }]
+        );
+        assert_eq!(
+            fields[1].constraints,
+            vec![FieldConstraint::Regex {
+                pattern: "^(a|b,c)$".to_string()
+            }]
+        );
+
+        let fields =
+            FieldDefinition::parse_list(&[r"price:String;regex=^\($,title:String".to_string()])
+                .unwrap();
+        assert_eq!(fields.len(), 2);
+        assert!(FieldDefinition::from_str("address:Address{author:AgentPubKey}").is_err());
+    }
+
+    #[test]
+    fn test_field_definition_with_bytes_from_str() {
+        let field = FieldDefinition::from_str("chunk:Vec<u8>:FileInput").unwrap();
+        assert_eq!(field.field_type, FieldType::SerializedBytes);
+        assert!(matches!(field.cardinality, Cardinality::Single));
+
+        let field = FieldDefinition::from_str("chunks:Vec<SerializedBytes>").unwrap();
+        assert_eq!(field.field_type, FieldType::SerializedBytes);
+        assert!(matches!(field.cardinality, Cardinality::Vector));
+
+        let field = FieldDefinition::from_str("counter:u64;range=..10000000000").unwrap();
+        assert_eq!(field.field_type.rust_type().to_string(), "u64");
+        assert_eq!(field.field_type.ts_type(), "number");
+    }
+
+    #[test]
+    fn test_field_definition_linked_from_dna_from_str() {
+        let field = FieldDefinition::from_str("community_dna:DnaHash::community").unwrap();
+        let linked_from = field.linked_from.unwrap();
+        assert_eq!(
+            linked_from,
+            Referenceable::Dna {
+                name: "community".to_string()
+            }
+        );
+        assert_eq!(linked_from.field_type(), FieldType::DnaHash);
+        assert_eq!(
+            linked_from.link_base(quote!(community_dna)).to_string(),
+            "ExternalHash :: from_raw_36 (community_dna . get_raw_36 () . to_vec ())"
+        );
+    }
+
+    #[test]
+    fn test_field_definition_with_map_from_str() {
+        let field = FieldDefinition::from_str("metadata:Map<String>:TextField").unwrap();
+        assert_eq!(field.field_type, FieldType::String);
+        assert!(matches!(field.cardinality, Cardinality::Map));
+        assert_eq!(
+            field.ts_field_codegen(),
+            "  metadata: Record<string, string>;"
+        );
+
+        assert!(FieldDefinition::from_str("authors:Map<AgentPubKey>::creator").is_err());
+    }
 }
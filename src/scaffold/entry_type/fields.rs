@@ -9,11 +9,13 @@ use crate::{
     file_tree::{dir_content, FileTree},
     reserved_words::check_for_reserved_keywords,
     scaffold::zome::ZomeFileTree,
-    utils::{check_case, input_with_case, input_with_custom_validation},
+    utils::{check_case, check_interactive, input_with_case, input_with_custom_validation},
 };
 
 use super::{
-    definitions::{Cardinality, EntryTypeReference, FieldDefinition, FieldType, Referenceable},
+    definitions::{
+        Cardinality, EntryTypeReference, FieldDefinition, FieldType, Referenceable, StructField,
+    },
     integrity::get_all_entry_types,
 };
 
@@ -22,7 +24,10 @@ pub fn choose_fields(
     zome_file_tree: &ZomeFileTree,
     field_types_templates: &FileTree,
     no_ui: bool,
+    no_interactive: bool,
 ) -> ScaffoldResult<Vec<FieldDefinition>> {
+    check_interactive("the `--fields` option", no_interactive)?;
+
     let mut finished = false;
     let mut fields: Vec<FieldDefinition> = Vec::new();
 
@@ -34,6 +39,7 @@ pub fn choose_fields(
             zome_file_tree,
             field_types_templates,
             no_ui,
+            no_interactive,
         )?;
         println!();
 
@@ -69,6 +75,7 @@ pub fn choose_fields(
             zome_file_tree,
             field_types_templates,
             no_ui,
+            no_interactive,
         );
     }
 
@@ -80,57 +87,16 @@ fn choose_field(
     zome_file_tree: &ZomeFileTree,
     field_types_templates: &FileTree,
     no_ui: bool,
+    no_interactive: bool,
 ) -> ScaffoldResult<FieldDefinition> {
-    let field_types = FieldType::list();
-    let field_type_names: Vec<String> = field_types
-        .clone()
-        .into_iter()
-        .map(|s| s.to_string())
-        .collect();
-
-    let field_name = input_with_custom_validation("Field name:", |input| {
-        if let Err(e) = check_case(&input, "field_name", Case::Snake) {
-            return Err(e.to_string());
-        }
-        if let Err(e) = check_for_reserved_keywords(&input) {
-            return Err(e.to_string());
-        }
-        Ok(())
-    })?;
-
-    let selection = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("Choose field type:")
-        .default(0)
-        .items(&field_type_names[..])
-        .item("Option of...")
-        .item("Vector of...")
-        .interact()?;
-
-    // If user selected Vector of ...
-    let (cardinality, field_type) = if selection == field_type_names.len() {
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Option of which field type?")
-            .default(0)
-            .items(&field_type_names[..])
-            .interact()?;
-
-        (Cardinality::Option, field_types[selection].clone())
-    // If user selected Option of ...
-    } else if selection == field_type_names.len() + 1 {
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Vector of which field type?")
-            .default(0)
-            .items(&field_type_names[..])
-            .interact()?;
-
-        (Cardinality::Vector, field_types[selection].clone())
-    } else {
-        (Cardinality::Single, field_types[selection].clone())
-    };
+    let field_name = choose_field_name(no_interactive)?;
+    let (cardinality, field_type) = choose_field_type(&FieldType::list())?;
 
     if let FieldType::Enum { .. } = field_type {
-        let label =
-            input_with_custom_validation("Enter the name of the enum:", |input: String| {
+        let label = input_with_custom_validation(
+            "Enter the name of the enum:",
+            no_interactive,
+            |input: String| {
                 if !input.is_case(Case::Pascal) {
                     return Err(format!("Input must be {:?} case.", Case::Pascal));
                 }
@@ -140,7 +106,8 @@ fn choose_field(
                     ));
                 }
                 Ok(())
-            })?;
+            },
+        )?;
 
         let mut variants = Vec::new();
         let mut another_variant = true;
@@ -148,6 +115,7 @@ fn choose_field(
         while another_variant {
             let variant = input_with_custom_validation(
                 "Enter the name of the next variant:",
+                no_interactive,
                 |input: String| {
                     if !input.is_case(Case::Pascal) {
                         return Err(format!("Input must be {:?} case.", Case::Pascal));
@@ -176,10 +144,62 @@ fn choose_field(
             widget,
             cardinality,
             None,
+            vec![],
+        );
+    }
+
+    if let FieldType::Struct { .. } = field_type {
+        let label = input_with_custom_validation(
+            "Enter the name of the struct:",
+            no_interactive,
+            |input: String| {
+                if !input.is_case(Case::Pascal) {
+                    return Err(format!("Input must be {:?} case.", Case::Pascal));
+                }
+                if input.to_ascii_lowercase() == entry_type_name {
+                    return Err(format!(
+                        "Struct name: {input} conflicts with entry-type name: {entry_type_name}"
+                    ));
+                }
+                Ok(())
+            },
+        )?;
+
+        println!("\nWhich fields should the struct contain?\n");
+
+        let mut fields: Vec<StructField> = Vec::new();
+        let mut another_field = true;
+
+        while another_field {
+            let field = choose_struct_field(field_types_templates, no_ui, no_interactive)?;
+            if fields.iter().any(|f| f.field_name == field.field_name) {
+                println!("{} is already a field of the struct", field.field_name);
+                continue;
+            }
+            fields.push(field);
+            another_field = Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Add another field to the struct?")
+                .report(false)
+                .interact()?;
+        }
+
+        let widget = (!no_ui)
+            .then(|| choose_widget(&field_type, field_types_templates))
+            .transpose()?
+            .flatten();
+
+        return FieldDefinition::new(
+            field_name,
+            FieldType::Struct { label, fields },
+            widget,
+            cardinality,
+            None,
+            vec![],
         );
     }
 
     let linked_from = match &field_type {
+        _ if cardinality == Cardinality::Map => None,
         FieldType::AgentPubKey => {
             let should_link_from_agent_pubkey = Confirm::with_theme(&ColorfulTheme::default())
                 .with_prompt(
@@ -190,13 +210,32 @@ fn choose_field(
             if should_link_from_agent_pubkey {
                 let role = input_with_case(
                     "Which role does this agent play in the relationship ? (eg. \"creator\", \"invitee\")",
-                    Case::Snake
+                    Case::Snake,
+                    no_interactive,
                 )?;
                 Some(Referenceable::Agent { role })
             } else {
                 None
             }
         }
+        FieldType::DnaHash => {
+            let should_link_from_dna_hash = Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(
+                    "Should a link from the DnaHash provided in this field also be created when entries of this type are created?"
+                )
+                .interact()?;
+
+            if should_link_from_dna_hash {
+                let name = input_with_case(
+                    "What name should be given to the link for this DNA? (eg. \"community\")",
+                    Case::Snake,
+                    no_interactive,
+                )?;
+                Some(Referenceable::Dna { name })
+            } else {
+                None
+            }
+        }
         FieldType::ActionHash | FieldType::EntryHash => {
             let should_link_from_hash_type = Confirm::with_theme(&ColorfulTheme::default())
                     .with_prompt(
@@ -260,7 +299,93 @@ fn choose_field(
         .transpose()?
         .flatten();
 
-    FieldDefinition::new(field_name, field_type, widget, cardinality, linked_from)
+    FieldDefinition::new(
+        field_name,
+        field_type,
+        widget,
+        cardinality,
+        linked_from,
+        vec![],
+    )
+}
+
+fn choose_field_name(no_interactive: bool) -> ScaffoldResult<String> {
+    input_with_custom_validation("Field name:", no_interactive, |input| {
+        if let Err(e) = check_case(&input, "field_name", Case::Snake) {
+            return Err(e.to_string());
+        }
+        if let Err(e) = check_for_reserved_keywords(&input) {
+            return Err(e.to_string());
+        }
+        Ok(())
+    })
+}
+
+fn choose_field_type(field_types: &[FieldType]) -> ScaffoldResult<(Cardinality, FieldType)> {
+    let field_type_names: Vec<String> = field_types.iter().map(|s| s.to_string()).collect();
+
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Choose field type:")
+        .default(0)
+        .items(&field_type_names[..])
+        .item("Option of...")
+        .item("Vector of...")
+        .item("Map of...")
+        .interact()?;
+
+    // If user selected Vector of ...
+    let (cardinality, field_type) = if selection == field_type_names.len() {
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Option of which field type?")
+            .default(0)
+            .items(&field_type_names[..])
+            .interact()?;
+
+        (Cardinality::Option, field_types[selection].clone())
+    // If user selected Option of ...
+    } else if selection == field_type_names.len() + 1 {
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Vector of which field type?")
+            .default(0)
+            .items(&field_type_names[..])
+            .interact()?;
+
+        (Cardinality::Vector, field_types[selection].clone())
+    // If user selected Map of ...
+    } else if selection == field_type_names.len() + 2 {
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Map of which field type?")
+            .default(0)
+            .items(&field_type_names[..])
+            .interact()?;
+
+        (Cardinality::Map, field_types[selection].clone())
+    } else {
+        (Cardinality::Single, field_types[selection].clone())
+    };
+
+    Ok((cardinality, field_type))
+}
+
+fn choose_struct_field(
+    field_types_templates: &FileTree,
+    no_ui: bool,
+    no_interactive: bool,
+) -> ScaffoldResult<StructField> {
+    let field_name = choose_field_name(no_interactive)?;
+    let (cardinality, field_type) = choose_field_type(&FieldType::struct_field_types())?;
+
+    let widget = (!no_ui)
+        .then(|| choose_widget(&field_type, field_types_templates))
+        .transpose()?
+        .flatten();
+
+    Ok(StructField {
+        field_name,
+        field_type,
+        widget,
+        cardinality,
+    })
 }
 
 fn choose_widget(
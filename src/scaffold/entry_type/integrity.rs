@@ -6,18 +6,22 @@ use std::{ffi::OsString, path::PathBuf};
 
 use crate::error::{ScaffoldError, ScaffoldResult};
 use crate::file_tree::insert_file;
+use crate::scaffold::app::cargo::{
+    add_crate_workspace_dependency, add_workspace_external_dependency,
+};
 use crate::scaffold::dna::DnaFileTree;
 use crate::scaffold::zome::coordinator::find_extern_function_in_zomes;
-use crate::scaffold::zome::utils::get_coordinator_zomes_for_integrity;
+use crate::scaffold::zome::utils::{get_coordinator_zomes_for_integrity, remove_module_from_crate};
 use crate::utils::unparse_pretty;
 use crate::{
     file_tree::{find_map_rust_files, map_file, map_rust_files},
     scaffold::zome::ZomeFileTree,
 };
 
-use super::crud::Crud;
+use super::crud::{Crud, Policy};
 use super::definitions::{
-    Cardinality, EntryDefinition, EntryTypeReference, FieldDefinition, Referenceable,
+    Cardinality, EntryDefinition, EntryTypeReference, EntryVisibility, FieldConstraint,
+    FieldDefinition, FieldType, Referenceable,
 };
 
 pub fn add_entry_type_to_integrity_zome(
@@ -30,7 +34,9 @@ pub fn add_entry_type_to_integrity_zome(
     let zome_manifest = zome_file_tree.zome_manifest.clone();
 
     let snake_entry_def_name = entry_def.name.to_case(Case::Snake);
-    let entry_def_file = render_entry_definition_file(entry_def, crud)?;
+    let private_linked_entry_types = get_private_linked_entry_types(&zome_file_tree, entry_def)?;
+    let entry_def_file =
+        render_entry_definition_file(entry_def, crud, &private_linked_entry_types)?;
 
     let entry_types = get_all_entry_types(&zome_file_tree)?;
 
@@ -151,7 +157,9 @@ pub use {snake_entry_def_name}::*;
                             }
                             found = true;
                             let pascal_entry_def_name = format_ident!("{pascal_entry_def_name}");
+                            let entry_type_attribute = render_entry_type_attribute(entry_def);
                             let new_variant = syn::parse_quote! {
+                                #entry_type_attribute
                                 #pascal_entry_def_name(#pascal_entry_def_name)
                             };
                             item_enum.variants.push(new_variant);
@@ -186,9 +194,220 @@ pub use {snake_entry_def_name}::*;
     Ok(zome_file_tree)
 }
 
+/// Renders the `#[entry_type(...)]` attribute for the variant of the entry type in the `EntryTypes` enum,
+/// if any of its entry definition options differ from holochain's defaults
+fn render_entry_type_attribute(entry_def: &EntryDefinition) -> Option<TokenStream> {
+    let mut options: Vec<TokenStream> = Vec::new();
+
+    if let Some(name) = &entry_def.entry_def_name {
+        options.push(quote! { name = #name });
+    }
+    if entry_def.visibility == EntryVisibility::Private {
+        options.push(quote! { visibility = "private" });
+    }
+    if let Some(required_validations) = entry_def.required_validations {
+        let required_validations = proc_macro2::Literal::u8_unsuffixed(required_validations);
+        options.push(quote! { required_validations = #required_validations });
+    }
+
+    (!options.is_empty()).then(|| quote! { #[entry_type(#(#options),*)] })
+}
+
+/// Appends the given fields to the struct of an already existing entry type, validating the entries referenced
+/// by the new linked fields in its validate_create function and the constraints of the new fields in its
+/// validate_create and validate_update functions
+///
+/// Returns the path of the file defining the entry type, relative to the crate's "src/" folder
+pub fn add_fields_to_entry_type_in_integrity_zome(
+    zome_file_tree: ZomeFileTree,
+    entry_def: &EntryDefinition,
+    crud: &Crud,
+) -> ScaffoldResult<(ZomeFileTree, PathBuf)> {
+    let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
+    let zome_manifest = zome_file_tree.zome_manifest.clone();
+
+    let private_linked_entry_types = get_private_linked_entry_types(&zome_file_tree, entry_def)?;
+
+    let (entry_def_path, item_struct) = find_entry_type_struct(&zome_file_tree, &entry_def.name)?
+        .ok_or(ScaffoldError::EntryTypeNotFound(
+        entry_def.name.clone(),
+        zome_file_tree.dna_file_tree.dna_manifest.name(),
+        zome_manifest.name.0.to_string(),
+    ))?;
+
+    for field_def in &entry_def.fields {
+        if item_struct
+            .fields
+            .iter()
+            .any(|f| f.ident.as_ref().is_some_and(|i| *i == field_def.field_name))
+        {
+            return Err(ScaffoldError::FieldAlreadyExists(
+                field_def.field_name.clone(),
+                entry_def.name.clone(),
+            ));
+        }
+    }
+
+    let new_fields =
+        match syn::parse2::<syn::ItemStruct>(render_entry_definition_struct(entry_def)?)?.fields {
+            syn::Fields::Named(fields_named) => fields_named.named,
+            _ => syn::punctuated::Punctuated::new(),
+        };
+
+    let snake_entry_def_name = entry_def.name.to_case(Case::Snake);
+    let validate_create_fn = format_ident!("validate_create_{snake_entry_def_name}");
+    let validate_update_fn = format_ident!("validate_update_{snake_entry_def_name}");
+
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        |file_path, mut file| {
+            if file_path != entry_def_path {
+                return Ok(file);
+            }
+
+            // Define the types of the new fields, unless the file already has them
+            let type_definitions: Vec<syn::Item> = entry_def
+                .fields
+                .iter()
+                .unique_by(|field_def| field_def.field_type.rust_type().to_string())
+                .filter(|field_def| {
+                    let type_name = field_def.field_type.rust_type().to_string();
+                    !file.items.iter().any(|item| match item {
+                        syn::Item::Enum(item_enum) => item_enum.ident == type_name,
+                        syn::Item::Struct(item_struct) => item_struct.ident == type_name,
+                        _ => false,
+                    })
+                })
+                .filter_map(|field_def| field_def.field_type.rust_type_definition())
+                .map(syn::parse2)
+                .collect::<Result<Vec<syn::Item>, syn::Error>>()?;
+
+            if let Some((i, _)) = file.items.iter().find_position(
+                |item| matches!(item, syn::Item::Struct(s) if s.ident == item_struct.ident),
+            ) {
+                for (j, type_definition) in type_definitions.into_iter().enumerate() {
+                    file.items.insert(i + j, type_definition);
+                }
+            }
+
+            for item in &mut file.items {
+                match item {
+                    syn::Item::Struct(s) if s.ident == item_struct.ident => {
+                        if let syn::Fields::Named(fields_named) = &mut s.fields {
+                            fields_named.named.extend(new_fields.clone());
+                        }
+                    }
+                    syn::Item::Fn(item_fn) if item_fn.sig.ident == validate_create_fn => {
+                        add_fields_validation(item_fn, |entry_arg| {
+                            let mut validation = render_linked_fields_validation(
+                                entry_arg,
+                                &entry_def.fields,
+                                &private_linked_entry_types,
+                            );
+                            validation.extend(render_field_constraints_validation(
+                                entry_arg,
+                                &entry_def.fields,
+                            )?);
+                            Ok(validation)
+                        })?;
+                    }
+                    syn::Item::Fn(item_fn)
+                        if crud.update && item_fn.sig.ident == validate_update_fn =>
+                    {
+                        add_fields_validation(item_fn, |entry_arg| {
+                            render_field_constraints_validation(entry_arg, &entry_def.fields)
+                        })?;
+                    }
+                    _ => {}
+                }
+            }
+
+            Ok(file)
+        },
+    )
+    .map_err(|e| match e {
+        ScaffoldError::MalformedFile(path, error) => {
+            ScaffoldError::MalformedFile(crate_src_path.join(path), error)
+        }
+        _ => e,
+    })?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)?;
+
+    Ok((zome_file_tree, entry_def_path))
+}
+
+/// Adds the crates needed to check the constraints of the given fields to the integrity zome, if any
+pub fn add_field_constraints_dependencies_to_integrity_zome(
+    zome_file_tree: ZomeFileTree,
+    fields: &[FieldDefinition],
+) -> ScaffoldResult<ZomeFileTree> {
+    if !fields.iter().any(|f| {
+        f.constraints
+            .iter()
+            .any(|c| matches!(c, FieldConstraint::Regex { .. }))
+    }) {
+        return Ok(zome_file_tree);
+    }
+
+    let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
+    let zome_manifest = zome_file_tree.zome_manifest.clone();
+    let zome_crate_path = zome_file_tree.zome_crate_path.clone();
+
+    let file_tree =
+        add_workspace_external_dependency(zome_file_tree.dna_file_tree.file_tree(), "regex", "1")?;
+    let file_tree = add_crate_workspace_dependency(file_tree, &zome_crate_path, "regex")?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)
+}
+
+/// Adds the validation rendered for the new entry argument at the end of an existing validate_create or validate_update function
+fn add_fields_validation(
+    validate_fn: &mut syn::ItemFn,
+    render_validation: impl Fn(&syn::Ident) -> ScaffoldResult<Vec<TokenStream>>,
+) -> ScaffoldResult<()> {
+    let Some(syn::FnArg::Typed(pat_type)) = validate_fn.sig.inputs.iter_mut().nth(1) else {
+        return Ok(());
+    };
+    let syn::Pat::Ident(pat_ident) = &mut *pat_type.pat else {
+        return Ok(());
+    };
+
+    let entry_arg_name = pat_ident.ident.to_string();
+    let entry_arg = format_ident!("{}", entry_arg_name.trim_start_matches('_'));
+
+    let validation = render_validation(&entry_arg)?;
+    if validation.is_empty() {
+        return Ok(());
+    }
+
+    // The entry was unused until now
+    pat_ident.ident = entry_arg.clone();
+
+    let validation_block: syn::Block = syn::parse2(quote! { { #(#validation)* } })?;
+
+    let stmts = &mut validate_fn.block.stmts;
+    let insert_index = stmts.len().saturating_sub(1);
+    for (i, stmt) in validation_block.stmts.into_iter().enumerate() {
+        stmts.insert(insert_index + i, stmt);
+    }
+
+    Ok(())
+}
+
 pub fn render_entry_definition_file(
     entry_def: &EntryDefinition,
     crud: &Crud,
+    private_linked_entry_types: &[String],
 ) -> ScaffoldResult<syn::File> {
     let entry_def_token_stream = render_entry_definition_struct(entry_def)?;
     let name_pascal: syn::Expr = syn::parse_str(entry_def.name.to_case(Case::Pascal).as_str())?;
@@ -201,16 +420,68 @@ pub fn render_entry_definition_file(
         .filter_map(|field_def| field_def.field_type.rust_type_definition())
         .collect();
 
-    let validate_update_fn =
-        format_ident!("validate_update_{}", entry_def.name.to_case(Case::Snake));
-    let new_entry_arg = format_ident!("_{}", entry_def.name.to_case(Case::Snake));
-    let original_entry_arg = format_ident!("_original_{}", entry_def.name.to_case(Case::Snake));
+    let has_constraints = entry_def.fields.iter().any(|f| !f.constraints.is_empty());
+    let snake_entry_def_name = entry_def.name.to_case(Case::Snake);
+    // Unused arguments are prefixed with an underscore
+    let arg = |name: String, used: bool| match used {
+        true => format_ident!("{name}"),
+        false => format_ident!("_{name}"),
+    };
+
+    let update_author_only = crud.update && entry_def.update_policy == Policy::Author;
+    let has_immutable_fields = crud.update && !entry_def.immutable_fields.is_empty();
+    let update_has_constraints = crud.update && has_constraints;
+
+    let validate_update_fn = format_ident!("validate_update_{snake_entry_def_name}");
+    let update_action_arg = arg(String::from("action"), update_author_only);
+    let new_entry_arg = arg(
+        snake_entry_def_name.clone(),
+        has_immutable_fields || update_has_constraints,
+    );
+    let update_original_action_arg = arg(String::from("original_action"), update_author_only);
+    let original_entry_arg = arg(
+        format!("original_{snake_entry_def_name}"),
+        has_immutable_fields,
+    );
     let updated_invalid_reason = format!("{plural_name_title} cannot be updated");
 
     let validate_update_result = if crud.update {
-        quote! {
-            /// TODO: add the appropriate validation rules
-            Ok(ValidateCallbackResult::Valid)
+        let mut update_validation: Vec<TokenStream> = vec![];
+        if update_author_only {
+            let invalid_reason = format!("{plural_name_title} can only be updated by their author");
+            update_validation.push(quote! {
+                if action.author != *original_action.author() {
+                    return Ok(ValidateCallbackResult::Invalid(String::from(#invalid_reason)));
+                }
+            });
+        }
+        for immutable_field in &entry_def.immutable_fields {
+            let field_name = format_ident!("{immutable_field}");
+            let invalid_reason = format!(
+                "The {} field cannot be updated",
+                immutable_field.to_case(Case::Lower)
+            );
+            update_validation.push(quote! {
+                if #new_entry_arg.#field_name != #original_entry_arg.#field_name {
+                    return Ok(ValidateCallbackResult::Invalid(String::from(#invalid_reason)));
+                }
+            });
+        }
+        update_validation.extend(render_field_constraints_validation(
+            &new_entry_arg,
+            &entry_def.fields,
+        )?);
+
+        match update_validation.is_empty() {
+            true => quote! {
+                /// TODO: add the appropriate validation rules
+                Ok(ValidateCallbackResult::Valid)
+            },
+            false => quote! {
+                #(#update_validation)*
+
+                Ok(ValidateCallbackResult::Valid)
+            },
         }
     } else {
         quote! {
@@ -220,21 +491,33 @@ pub fn render_entry_definition_file(
 
     let validate_update = quote! {
         pub fn #validate_update_fn(
-            _action: Update,
+            #update_action_arg: Update,
             #new_entry_arg: #name_pascal,
-            _original_action: EntryCreationAction,
+            #update_original_action_arg: EntryCreationAction,
             #original_entry_arg: #name_pascal
         ) -> ExternResult<ValidateCallbackResult> {
             #validate_update_result
         }
     };
 
-    let validate_delete_fn =
-        format_ident!("validate_delete_{}", entry_def.name.to_case(Case::Snake));
-    let deleted_post_arg = format_ident!("_original_{}", entry_def.name.to_case(Case::Snake));
+    let delete_author_only = crud.delete && entry_def.delete_policy == Policy::Author;
+
+    let validate_delete_fn = format_ident!("validate_delete_{snake_entry_def_name}");
+    let delete_action_arg = arg(String::from("action"), delete_author_only);
+    let delete_original_action_arg = arg(String::from("original_action"), delete_author_only);
+    let deleted_post_arg = format_ident!("_original_{snake_entry_def_name}");
     let deleted_invalid_reason = format!("{plural_name_title} cannot be deleted");
 
-    let validate_delete_result = if crud.delete {
+    let validate_delete_result = if delete_author_only {
+        let invalid_reason = format!("{plural_name_title} can only be deleted by their author");
+        quote! {
+            if action.author != *original_action.author() {
+                return Ok(ValidateCallbackResult::Invalid(String::from(#invalid_reason)));
+            }
+
+            Ok(ValidateCallbackResult::Valid)
+        }
+    } else if crud.delete {
         quote! {
             /// TODO: add the appropriate validation rules
             Ok(ValidateCallbackResult::Valid)
@@ -247,8 +530,8 @@ pub fn render_entry_definition_file(
 
     let validate_delete = quote! {
         pub fn #validate_delete_fn(
-            _action: Delete,
-            _original_action: EntryCreationAction,
+            #delete_action_arg: Delete,
+            #delete_original_action_arg: EntryCreationAction,
             #deleted_post_arg: #name_pascal
         ) -> ExternResult<ValidateCallbackResult> {
             #validate_delete_result
@@ -258,8 +541,252 @@ pub fn render_entry_definition_file(
     let validate_create_fn =
         format_ident!("validate_create_{}", entry_def.name.to_case(Case::Snake));
 
-    let deps: Vec<(FieldDefinition, EntryTypeReference)> = entry_def
+    let has_linked_entry_types = entry_def
         .fields
+        .iter()
+        .any(|f| matches!(f.linked_from, Some(Referenceable::EntryType(_))));
+
+    let create_new_entry_arg = match has_linked_entry_types || has_constraints {
+        false => format_ident!("_{}", entry_def.name.to_case(Case::Snake)),
+        true => format_ident!("{}", entry_def.name.to_case(Case::Snake)),
+    };
+    let deps_validation = render_linked_fields_validation(
+        &create_new_entry_arg,
+        &entry_def.fields,
+        private_linked_entry_types,
+    );
+    let constraints_validation =
+        render_field_constraints_validation(&create_new_entry_arg, &entry_def.fields)?;
+    let validate_create_result = match has_constraints {
+        true => quote! {
+            #(#constraints_validation)*
+
+            Ok(ValidateCallbackResult::Valid)
+        },
+        false => quote! {
+            /// TODO: add the appropriate validation rules
+            Ok(ValidateCallbackResult::Valid)
+        },
+    };
+
+    let token_stream = syn::parse_quote! {
+        use hdi::prelude::*;
+
+        #(#type_definitions)*
+
+        #[derive(Clone, PartialEq)]
+        #[hdk_entry_helper]
+        #entry_def_token_stream
+
+        pub fn #validate_create_fn(
+            _action: EntryCreationAction,
+            #create_new_entry_arg: #name_pascal
+        ) -> ExternResult<ValidateCallbackResult> {
+            #(#deps_validation)*
+
+            #validate_create_result
+        }
+
+        #validate_update
+
+        #validate_delete
+    };
+
+    Ok(token_stream)
+}
+
+/// Renders the checks of the constraints of the given fields, for the validate_create and validate_update functions of an entry type
+fn render_field_constraints_validation(
+    entry_arg: &syn::Ident,
+    fields: &[FieldDefinition],
+) -> ScaffoldResult<Vec<TokenStream>> {
+    fields
+        .iter()
+        .filter(|field_def| !field_def.constraints.is_empty())
+        .map(|field_def| {
+            let field_name = format_ident!("{}", field_def.field_name);
+            let lowercase_field_name = field_def.field_name.to_case(Case::Lower);
+            let invalid = |reason: String| {
+                let reason = format!("The {lowercase_field_name} field {reason}");
+                quote! {
+                    return Ok(ValidateCallbackResult::Invalid(String::from(#reason)));
+                }
+            };
+
+            // Checks on the number of items of vectors
+            let mut vector_checks: Vec<TokenStream> = vec![];
+            // Checks on each value, that `value` refers to and `deref_value` dereferences
+            let mut value_checks: Vec<TokenStream> = vec![];
+
+            let (value, deref_value) = match field_def.cardinality {
+                Cardinality::Single => (
+                    quote!(#entry_arg.#field_name),
+                    quote!(#entry_arg.#field_name),
+                ),
+                Cardinality::Option | Cardinality::Vector | Cardinality::Map => {
+                    (quote!(#field_name), quote!(*#field_name))
+                }
+            };
+            let is_vector = matches!(
+                field_def.cardinality,
+                Cardinality::Vector | Cardinality::Map
+            );
+
+            for constraint in &field_def.constraints {
+                match constraint {
+                    FieldConstraint::MinLen { value: min_len } => {
+                        let literal = proc_macro2::Literal::usize_unsuffixed(*min_len);
+                        if is_vector {
+                            let invalid = invalid(format!("must have at least {min_len} items"));
+                            vector_checks.push(quote! {
+                                if #entry_arg.#field_name.len() < #literal {
+                                    #invalid
+                                }
+                            });
+                        } else {
+                            let invalid =
+                                invalid(format!("must be at least {min_len} characters long"));
+                            value_checks.push(quote! {
+                                if #value.chars().count() < #literal {
+                                    #invalid
+                                }
+                            });
+                        }
+                    }
+                    FieldConstraint::MaxLen { value: max_len } => {
+                        let literal = proc_macro2::Literal::usize_unsuffixed(*max_len);
+                        if is_vector {
+                            let invalid = invalid(format!("must have at most {max_len} items"));
+                            vector_checks.push(quote! {
+                                if #entry_arg.#field_name.len() > #literal {
+                                    #invalid
+                                }
+                            });
+                        } else {
+                            let invalid =
+                                invalid(format!("must be at most {max_len} characters long"));
+                            value_checks.push(quote! {
+                                if #value.chars().count() > #literal {
+                                    #invalid
+                                }
+                            });
+                        }
+                    }
+                    FieldConstraint::NonEmpty => {
+                        let invalid = invalid(String::from("cannot be empty"));
+                        if is_vector {
+                            vector_checks.push(quote! {
+                                if #entry_arg.#field_name.is_empty() {
+                                    #invalid
+                                }
+                            });
+                        } else {
+                            value_checks.push(quote! {
+                                if #value.trim().is_empty() {
+                                    #invalid
+                                }
+                            });
+                        }
+                    }
+                    FieldConstraint::Regex { pattern } => {
+                        let invalid = invalid(format!("must match the pattern {pattern}"));
+                        // Compiled once per wasm instance rather than on every validation, and in its own block so
+                        // that the statics of several regex constraints don't clash
+                        value_checks.push(quote! {
+                            {
+                                static REGEX: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+                                let regex = REGEX.get_or_init(|| {
+                                    regex::Regex::new(#pattern).expect("Invalid pattern for the regex constraint")
+                                });
+                                if !regex.is_match(&#value) {
+                                    #invalid
+                                }
+                            }
+                        });
+                    }
+                    FieldConstraint::Range { min, max } => {
+                        if let Some(min) = min {
+                            let literal = field_def.field_type.literal(min)?;
+                            let invalid = invalid(format!("must be at least {min}"));
+                            value_checks.push(quote! {
+                                if #deref_value < #literal {
+                                    #invalid
+                                }
+                            });
+                        }
+                        if let Some(max) = max {
+                            let literal = field_def.field_type.literal(max)?;
+                            let invalid = invalid(format!("must be at most {max}"));
+                            value_checks.push(quote! {
+                                if #deref_value > #literal {
+                                    #invalid
+                                }
+                            });
+                        }
+                    }
+                    FieldConstraint::OneOf { values } => {
+                        let invalid = invalid(format!("must be one of: {}", values.join(", ")));
+                        match field_def.field_type {
+                            FieldType::String => value_checks.push(quote! {
+                                if ![#(#values),*].contains(&#value.as_str()) {
+                                    #invalid
+                                }
+                            }),
+                            _ => {
+                                let literals = values
+                                    .iter()
+                                    .map(|v| field_def.field_type.literal(v))
+                                    .collect::<ScaffoldResult<Vec<TokenStream>>>()?;
+                                value_checks.push(quote! {
+                                    if ![#(#literals),*].contains(&#deref_value) {
+                                        #invalid
+                                    }
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+
+            let value_checks = match (value_checks.is_empty(), &field_def.cardinality) {
+                (true, _) => quote! {},
+                (false, Cardinality::Single) => quote! { #(#value_checks)* },
+                (false, Cardinality::Option) => quote! {
+                    if let Some(#field_name) = &#entry_arg.#field_name {
+                        #(#value_checks)*
+                    }
+                },
+                (false, Cardinality::Vector) => quote! {
+                    for #field_name in &#entry_arg.#field_name {
+                        #(#value_checks)*
+                    }
+                },
+                (false, Cardinality::Map) => quote! {
+                    for #field_name in #entry_arg.#field_name.values() {
+                        #(#value_checks)*
+                    }
+                },
+            };
+
+            Ok(quote! {
+                #(#vector_checks)*
+                #value_checks
+            })
+        })
+        .collect()
+}
+
+/// Renders the validation of the entries that the linked fields reference, for the validate_create function of an entry type
+///
+/// The entries of the private entry types can't be fetched by the validating agents, so for those
+/// only the existence of the action is checked when referenced by `ActionHash`, and nothing when
+/// referenced by `EntryHash`
+fn render_linked_fields_validation(
+    create_new_entry_arg: &syn::Ident,
+    fields: &[FieldDefinition],
+    private_entry_types: &[String],
+) -> Vec<TokenStream> {
+    let deps: Vec<(FieldDefinition, EntryTypeReference)> = fields
         .iter()
         .filter_map(|f| match &f.linked_from {
             Some(Referenceable::EntryType(entry_type_reference)) => {
@@ -269,25 +796,34 @@ pub fn render_entry_definition_file(
         })
         .collect();
 
-    let create_new_entry_arg = match deps.len() {
-        0 => format_ident!("_{}", entry_def.name.to_case(Case::Snake)),
-        _ => format_ident!("{}", entry_def.name.to_case(Case::Snake)),
-    };
-    let deps_validation: Vec<TokenStream> = deps
-        .into_iter()
-        .map(|(field_def, reference)| {
+    deps.into_iter()
+        .filter_map(|(field_def, reference)| {
             let field_name = format_ident!("{}",field_def.field_name);
             let dependant_entry_type_snake = format_ident!("_{}", reference.entry_type.to_case(Case::Snake));
             let dependant_entry_type_pascal = format_ident!("{}", reference.entry_type.to_case(Case::Pascal));
-            match (field_def.cardinality, reference.reference_entry_hash) {
-                (Cardinality::Single, false) => quote! {
+            let private = private_entry_types.contains(&reference.entry_type.to_case(Case::Pascal));
+            let validation = match (field_def.cardinality, reference.reference_entry_hash, private) {
+                (Cardinality::Single, false, true) => quote! {
+                    must_get_valid_record(#create_new_entry_arg.#field_name.clone())?;
+                },
+                (Cardinality::Option, false, true) => quote! {
+                    if let Some(action_hash) = #create_new_entry_arg.#field_name.clone() {
+                        must_get_valid_record(action_hash)?;
+                    }
+                },
+                (Cardinality::Vector, false, true) => quote! {
+                    for action_hash in #create_new_entry_arg.#field_name.clone() {
+                        must_get_valid_record(action_hash)?;
+                    }
+                },
+                (Cardinality::Single, false, false) => quote! {
                     let record = must_get_valid_record(#create_new_entry_arg.#field_name.clone())?;
 
                     let #dependant_entry_type_snake: crate::#dependant_entry_type_pascal = record.entry().to_app_option()
                         .map_err(|e| wasm_error!(e))?
                         .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Dependant action must be accompanied by an entry"))))?;
                 },
-                (Cardinality::Option, false) => quote! {
+                (Cardinality::Option, false, false) => quote! {
                     if let Some(action_hash) = #create_new_entry_arg.#field_name.clone() {
                         let record = must_get_valid_record(action_hash)?;
 
@@ -296,7 +832,7 @@ pub fn render_entry_definition_file(
                             .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Dependant action must be accompanied by an entry"))))?;
                     }
                 },
-                (Cardinality::Vector, false) => quote! {
+                (Cardinality::Vector, false, false) => quote! {
                     for action_hash in #create_new_entry_arg.#field_name.clone() {
                         let record = must_get_valid_record(action_hash)?;
                         let #dependant_entry_type_snake: crate::#dependant_entry_type_pascal = record.entry().to_app_option()
@@ -304,51 +840,53 @@ pub fn render_entry_definition_file(
                             .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Dependant action must be accompanied by an entry"))))?;
                     }
                 },
-                (Cardinality::Single,true) => quote! {
+                (_, true, true) => return None,
+                (Cardinality::Single, true, false) => quote! {
                     let entry = must_get_entry(#create_new_entry_arg.#field_name.clone())?;
                     let #dependant_entry_type_snake = crate::#dependant_entry_type_pascal::try_from(entry)?;
                 },
-                (Cardinality::Option, true) => quote! {
+                (Cardinality::Option, true, false) => quote! {
                     if let Some(entry_hash) = #create_new_entry_arg.#field_name.clone() {
                         let entry = must_get_entry(entry_hash)?;
                         let #dependant_entry_type_snake = crate::#dependant_entry_type_pascal::try_from(entry)?;
                     }
                 },
-                (Cardinality::Vector, true) => quote! {
+                (Cardinality::Vector, true, false) => quote! {
                     for entry_hash in #create_new_entry_arg.#field_name.clone() {
                         let entry = must_get_entry(entry_hash)?;
                         let #dependant_entry_type_snake = crate::#dependant_entry_type_pascal::try_from(entry)?;
                     }
                 },
-            }
+                (Cardinality::Map, _, _) => unreachable!("map fields can't be linked from"),
+            };
+            Some(validation)
         })
-        .collect();
-
-    let token_stream = syn::parse_quote! {
-        use hdi::prelude::*;
-
-        #(#type_definitions)*
-
-        #[derive(Clone, PartialEq)]
-        #[hdk_entry_helper]
-        #entry_def_token_stream
-
-        pub fn #validate_create_fn(
-            _action: EntryCreationAction,
-            #create_new_entry_arg: #name_pascal
-        ) -> ExternResult<ValidateCallbackResult> {
-            #(#deps_validation)*
+        .collect()
+}
 
-            /// TODO: add the appropriate validation rules
-            Ok(ValidateCallbackResult::Valid)
+/// Returns the entry types referenced by the linked fields of the entry definition that are private
+fn get_private_linked_entry_types(
+    zome_file_tree: &ZomeFileTree,
+    entry_def: &EntryDefinition,
+) -> ScaffoldResult<Vec<String>> {
+    let mut private_entry_types = vec![];
+
+    for field_def in &entry_def.fields {
+        if let Some(Referenceable::EntryType(reference)) = &field_def.linked_from {
+            let entry_type = reference.entry_type.to_case(Case::Pascal);
+            let visibility = if entry_type == entry_def.name.to_case(Case::Pascal) {
+                entry_def.visibility
+            } else {
+                get_entry_type_visibility(zome_file_tree, &entry_type)?
+            };
+            if visibility == EntryVisibility::Private && !private_entry_types.contains(&entry_type)
+            {
+                private_entry_types.push(entry_type);
+            }
         }
+    }
 
-        #validate_update
-
-        #validate_delete
-    };
-
-    Ok(token_stream)
+    Ok(private_entry_types)
 }
 
 pub fn render_entry_definition_struct(entry_def: &EntryDefinition) -> ScaffoldResult<TokenStream> {
@@ -457,6 +995,274 @@ pub fn get_all_entry_types(
     }
 }
 
+/// Returns the visibility of the given entry type, read from the `#[entry_type]` attribute of its variant in the `EntryTypes` enum
+pub fn get_entry_type_visibility(
+    zome_file_tree: &ZomeFileTree,
+    entry_type: &str,
+) -> ScaffoldResult<EntryVisibility> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let crate_src_path_iter: Vec<OsString> =
+        crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    let pascal_entry_type = entry_type.to_case(Case::Pascal);
+
+    let variants = find_map_rust_files(
+        zome_file_tree
+            .dna_file_tree
+            .file_tree_ref()
+            .path(&mut crate_src_path_iter.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        &|_file_path, rust_file| {
+            rust_file.items.iter().find_map(|i| match i {
+                syn::Item::Enum(item_enum)
+                    if item_enum.attrs.iter().any(|a| {
+                        a.path()
+                            .segments
+                            .iter()
+                            .any(|s| s.ident == "hdk_entry_types")
+                    }) =>
+                {
+                    item_enum
+                        .variants
+                        .iter()
+                        .find(|v| v.ident == pascal_entry_type)
+                        .cloned()
+                }
+                _ => None,
+            })
+        },
+    );
+
+    let is_private = variants
+        .values()
+        .flat_map(|v| &v.attrs)
+        .filter(|a| a.path().is_ident("entry_type"))
+        .any(|a| {
+            a.parse_args_with(
+                syn::punctuated::Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated,
+            )
+            .map(|options| {
+                options.iter().any(|option| {
+                    option.path.is_ident("visibility")
+                        && matches!(
+                            &option.value,
+                            syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) if s.value() == "private"
+                        )
+                })
+            })
+            .unwrap_or(false)
+        });
+
+    if is_private {
+        Ok(EntryVisibility::Private)
+    } else {
+        Ok(EntryVisibility::Public)
+    }
+}
+
+/// Returns the update policy, the delete policy and the immutable fields of the given entry type, read from the
+/// validation scaffolded in its `validate_update_*` and `validate_delete_*` functions
+pub fn get_entry_type_policies(
+    zome_file_tree: &ZomeFileTree,
+    entry_type: &str,
+) -> ScaffoldResult<(Policy, Policy, Vec<String>)> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let crate_src_path_iter: Vec<OsString> =
+        crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    let mut crate_src_path_iter = crate_src_path_iter.iter();
+    let crate_src = zome_file_tree
+        .dna_file_tree
+        .file_tree_ref()
+        .path(&mut crate_src_path_iter)
+        .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?;
+    let snake_entry_type = entry_type.to_case(Case::Snake);
+
+    // The conditions of the if statements in the body of the given function
+    let conditions = |fn_name: syn::Ident| -> Vec<syn::Expr> {
+        find_map_rust_files(crate_src, &|_file_path, rust_file| {
+            rust_file.items.iter().find_map(|i| match i {
+                syn::Item::Fn(item_fn) if item_fn.sig.ident == fn_name => Some(item_fn.clone()),
+                _ => None,
+            })
+        })
+        .into_values()
+        .flat_map(|item_fn| item_fn.block.stmts)
+        .filter_map(|stmt| match stmt {
+            syn::Stmt::Expr(syn::Expr::If(expr_if), _) => Some(*expr_if.cond),
+            _ => None,
+        })
+        .collect()
+    };
+    let author_condition: syn::Expr =
+        syn::parse_quote! { action.author != *original_action.author() };
+    let policy = |conditions: &[syn::Expr]| match conditions.contains(&author_condition) {
+        true => Policy::Author,
+        false => Policy::Anyone,
+    };
+
+    let update_conditions = conditions(format_ident!("validate_update_{snake_entry_type}"));
+    let original_entry_arg = format!("original_{snake_entry_type}");
+    let immutable_fields = update_conditions
+        .iter()
+        .filter_map(|condition| match condition {
+            syn::Expr::Binary(syn::ExprBinary {
+                left,
+                op: syn::BinOp::Ne(_),
+                right,
+                ..
+            }) => match (&**left, &**right) {
+                (syn::Expr::Field(new_field), syn::Expr::Field(original_field)) => {
+                    match (&new_field.member, &*original_field.base) {
+                        (syn::Member::Named(field_name), syn::Expr::Path(original))
+                            if new_field.member == original_field.member
+                                && original.path.is_ident(&original_entry_arg) =>
+                        {
+                            Some(field_name.to_string())
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    let delete_conditions = conditions(format_ident!("validate_delete_{snake_entry_type}"));
+
+    Ok((
+        policy(&update_conditions),
+        policy(&delete_conditions),
+        immutable_fields,
+    ))
+}
+
+/// Returns the path (relative to the crate's "src/" folder) and the definition of the struct for the given entry type
+pub fn find_entry_type_struct(
+    zome_file_tree: &ZomeFileTree,
+    entry_type: &str,
+) -> ScaffoldResult<Option<(PathBuf, syn::ItemStruct)>> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let crate_src_path_iter: Vec<OsString> =
+        crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    let pascal_entry_type = entry_type.to_case(Case::Pascal);
+
+    let entry_structs = find_map_rust_files(
+        zome_file_tree
+            .dna_file_tree
+            .file_tree_ref()
+            .path(&mut crate_src_path_iter.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        &|_file_path, rust_file| {
+            rust_file.items.iter().find_map(|i| match i {
+                syn::Item::Struct(item_struct) if item_struct.ident == pascal_entry_type => {
+                    Some(item_struct.clone())
+                }
+                _ => None,
+            })
+        },
+    );
+
+    Ok(entry_structs.into_iter().next())
+}
+
+/// Removes the given entry type from the integrity zome: its definition file, its variant in the
+/// `#[hdk_entry_types]` enum and its arms in the validate function
+///
+/// Also returns whether there are no entry types left in the zome
+pub fn remove_entry_type_from_integrity_zome(
+    zome_file_tree: ZomeFileTree,
+    entry_type: &str,
+) -> ScaffoldResult<(ZomeFileTree, bool)> {
+    let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
+    let zome_manifest = zome_file_tree.zome_manifest.clone();
+
+    let pascal_entry_type = entry_type.to_case(Case::Pascal);
+    let snake_entry_type = entry_type.to_case(Case::Snake);
+
+    let entry_types = get_all_entry_types(&zome_file_tree)?.unwrap_or_default();
+    if !entry_types
+        .iter()
+        .any(|e| e.entry_type == pascal_entry_type)
+    {
+        return Err(ScaffoldError::EntryTypeNotFound(
+            pascal_entry_type,
+            zome_file_tree.dna_file_tree.dna_manifest.name(),
+            zome_manifest.name.0.to_string(),
+        ));
+    }
+    let no_entry_types_left = entry_types.len() == 1;
+
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    remove_module_from_crate(&mut file_tree, &crate_src_path, &snake_entry_type)?;
+
+    let empty_arm_body: syn::Expr = syn::parse_quote! {
+        Ok(ValidateCallbackResult::Invalid(
+            "There are no entry types in this integrity zome".to_string(),
+        ))
+    };
+
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        |_file_path, mut file| {
+            file.items.retain(|item| {
+                !(no_entry_types_left && is_enum_with_attribute(item, "hdk_entry_types"))
+            });
+
+            for item in &mut file.items {
+                if is_enum_with_attribute(item, "hdk_entry_types") {
+                    if let syn::Item::Enum(item_enum) = item {
+                        item_enum.variants = item_enum
+                            .variants
+                            .clone()
+                            .into_iter()
+                            .filter(|v| v.ident != pascal_entry_type)
+                            .collect();
+                    }
+                }
+
+                remove_arms_from_validation(
+                    item,
+                    &|pat| match pat {
+                        syn::Pat::TupleStruct(pat_tuple_struct) => {
+                            pat_tuple_struct.path.segments.len() == 2
+                                && pat_tuple_struct.path.segments[0].ident == "EntryTypes"
+                                && pat_tuple_struct.path.segments[1].ident == pascal_entry_type
+                        }
+                        _ => false,
+                    },
+                    &empty_arm_body,
+                );
+
+                if no_entry_types_left {
+                    if let Some(first_arg) =
+                        find_flattened_type_args(item).and_then(|args| args.first_mut())
+                    {
+                        *first_arg = syn::GenericArgument::Type(syn::parse_quote! {()});
+                    }
+                }
+            }
+
+            Ok(file)
+        },
+    )
+    .map_err(|e| match e {
+        ScaffoldError::MalformedFile(path, error) => {
+            ScaffoldError::MalformedFile(crate_src_path.join(path), error)
+        }
+        _ => e,
+    })?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)?;
+
+    Ok((zome_file_tree, no_entry_types_left))
+}
+
 fn add_entry_type_to_validation_arms(
     item: &mut syn::Item,
     entry_def: &EntryDefinition,
@@ -863,6 +1669,75 @@ pub fn find_ending_match_expr(e: &mut syn::Expr) -> Option<&mut syn::ExprMatch>
     }
 }
 
+/// Removes the arms with the given patterns from the nested matches of the validate function,
+/// resetting the arms whose matches are left empty to the given body
+pub fn remove_arms_from_validation(
+    item: &mut syn::Item,
+    is_removed_arm: &dyn Fn(&syn::Pat) -> bool,
+    empty_arm_body: &syn::Expr,
+) {
+    if let syn::Item::Fn(item_fn) = item {
+        if item_fn.sig.ident != "validate" {
+            return;
+        }
+        if let Some(match_expr) = find_ending_match_expr_in_block(&mut item_fn.block) {
+            remove_arms_from_match(match_expr, is_removed_arm, empty_arm_body);
+        }
+    }
+}
+
+fn remove_arms_from_match(
+    match_expr: &mut syn::ExprMatch,
+    is_removed_arm: &dyn Fn(&syn::Pat) -> bool,
+    empty_arm_body: &syn::Expr,
+) {
+    for arm in &mut match_expr.arms {
+        if let Some(inner_match_expr) = find_ending_match_expr(&mut arm.body) {
+            let arms_count = inner_match_expr.arms.len();
+            inner_match_expr
+                .arms
+                .retain(|inner_arm| !is_removed_arm(&inner_arm.pat));
+
+            if arms_count > 0 && inner_match_expr.arms.is_empty() {
+                *arm.body = empty_arm_body.clone();
+            } else {
+                remove_arms_from_match(inner_match_expr, is_removed_arm, empty_arm_body);
+            }
+        }
+    }
+}
+
+/// Returns the type arguments of the `op.flattened::<ET, LT>()` call, if the given item is the validate function
+pub fn find_flattened_type_args(
+    item: &mut syn::Item,
+) -> Option<&mut syn::punctuated::Punctuated<syn::GenericArgument, syn::token::Comma>> {
+    if let syn::Item::Fn(item_fn) = item {
+        if item_fn.sig.ident != "validate" {
+            return None;
+        }
+        if let Some(match_expr) = find_ending_match_expr_in_block(&mut item_fn.block) {
+            if let syn::Expr::Try(try_expr) = &mut *match_expr.expr {
+                if let syn::Expr::MethodCall(call) = &mut *try_expr.expr {
+                    if call.method == "flattened" {
+                        return call.turbofish.as_mut().map(|turbofish| &mut turbofish.args);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+pub fn is_enum_with_attribute(item: &syn::Item, attribute: &str) -> bool {
+    if let syn::Item::Enum(item_enum) = item {
+        return item_enum
+            .attrs
+            .iter()
+            .any(|a| a.path().segments.iter().any(|s| s.ident == attribute));
+    }
+    false
+}
+
 fn is_entry(pat: &syn::Pat, entry: &str) -> bool {
     if let syn::Pat::Struct(pat_struct) = pat {
         if let Some(ps) = pat_struct.path.segments.last() {
@@ -871,3 +1746,73 @@ fn is_entry(pat: &syn::Pat, entry: &str) -> bool {
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn post_hash_field(reference_entry_hash: bool) -> FieldDefinition {
+        FieldDefinition {
+            field_name: "post_hash".to_string(),
+            field_type: if reference_entry_hash {
+                FieldType::EntryHash
+            } else {
+                FieldType::ActionHash
+            },
+            widget: None,
+            cardinality: Cardinality::Single,
+            linked_from: Some(Referenceable::EntryType(EntryTypeReference {
+                entry_type: "Post".to_string(),
+                reference_entry_hash,
+            })),
+            constraints: vec![],
+        }
+    }
+
+    #[test]
+    fn only_checks_the_action_of_the_linked_private_entries() {
+        let entry_arg = format_ident!("comment");
+
+        let validation: Vec<String> =
+            render_linked_fields_validation(&entry_arg, &[post_hash_field(false)], &[])
+                .iter()
+                .map(|v| v.to_string())
+                .collect();
+        assert_eq!(validation.len(), 1);
+        assert!(validation[0].contains("to_app_option"));
+
+        let validation: Vec<String> = render_linked_fields_validation(
+            &entry_arg,
+            &[post_hash_field(false)],
+            &["Post".to_string()],
+        )
+        .iter()
+        .map(|v| v.to_string())
+        .collect();
+        assert_eq!(validation.len(), 1);
+        assert!(validation[0].contains("must_get_valid_record (comment . post_hash . clone ())"));
+        assert!(!validation[0].contains("to_app_option"));
+
+        let validation = render_linked_fields_validation(
+            &entry_arg,
+            &[post_hash_field(true)],
+            &["Post".to_string()],
+        );
+        assert!(validation.is_empty());
+    }
+
+    #[test]
+    fn compiles_the_regex_constraints_once() {
+        let field = FieldDefinition::from_str("code:String;regex=^[a-z]+$;regex=^.{2,5}$").unwrap();
+
+        let validation = render_field_constraints_validation(&format_ident!("post"), &[field])
+            .unwrap()
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<String>();
+        assert_eq!(validation.matches("static REGEX").count(), 2);
+        assert_eq!(validation.matches("REGEX . get_or_init").count(), 2);
+    }
+}
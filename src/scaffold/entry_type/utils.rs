@@ -7,16 +7,21 @@ use crate::{
     error::{ScaffoldError, ScaffoldResult},
     reserved_words::check_for_reserved_keywords,
     scaffold::zome::ZomeFileTree,
-    utils::input_with_case,
+    utils::{check_interactive, input_with_case},
 };
 
-pub fn choose_reference_entry_hash(prompt: &str, recommended: bool) -> ScaffoldResult<bool> {
+pub fn choose_reference_entry_hash(
+    prompt: &str,
+    recommended: bool,
+    no_interactive: bool,
+) -> ScaffoldResult<bool> {
     let options = if recommended {
         [("EntryHash", true), ("ActionHash", false)]
     } else {
         [("ActionHash", false), ("EntryHash", true)]
     };
 
+    check_interactive(&format!("answer to \"{prompt}\""), no_interactive)?;
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .default(0)
@@ -33,6 +38,7 @@ pub fn get_or_choose_referenceable(
     zome_file_tree: &ZomeFileTree,
     entry_type: Option<&Referenceable>,
     all_entries: &[EntryTypeReference],
+    no_interactive: bool,
 ) -> ScaffoldResult<Referenceable> {
     match &entry_type {
         Some(Referenceable::Agent { role }) => {
@@ -54,7 +60,7 @@ pub fn get_or_choose_referenceable(
 
             Ok(Referenceable::EntryType(app_entry_reference.clone()))
         }
-        _ => choose_referenceable(all_entries, prompt),
+        _ => choose_referenceable(all_entries, prompt, no_interactive),
     }
 }
 
@@ -63,6 +69,7 @@ pub fn get_or_choose_optional_reference_type(
     zome_file_tree: &ZomeFileTree,
     entry_type: Option<&Referenceable>,
     all_entries: &[EntryTypeReference],
+    no_interactive: bool,
 ) -> ScaffoldResult<Option<Referenceable>> {
     match entry_type {
         Some(Referenceable::Agent { .. }) => Ok(entry_type.cloned()),
@@ -81,26 +88,30 @@ pub fn get_or_choose_optional_reference_type(
 
             Ok(entry_type.cloned())
         }
-        _ => choose_optional_referenceable(all_entries, prompt),
+        _ => choose_optional_referenceable(all_entries, prompt, no_interactive),
     }
 }
 
 pub fn choose_referenceable(
     all_entries: &[EntryTypeReference],
     prompt: &str,
+    no_interactive: bool,
 ) -> ScaffoldResult<Referenceable> {
-    let maybe_reference_type = inner_choose_referenceable(all_entries, prompt, None)?;
+    let maybe_reference_type =
+        inner_choose_referenceable(all_entries, prompt, None, no_interactive)?;
     Ok(maybe_reference_type.context("Reference type should not be None")?)
 }
 
 pub fn choose_optional_referenceable(
     all_entries: &[EntryTypeReference],
     prompt: &str,
+    no_interactive: bool,
 ) -> ScaffoldResult<Option<Referenceable>> {
     inner_choose_referenceable(
         all_entries,
         prompt,
         Some(vec!["[None] (Use this link to attach meta-data only)"]),
+        no_interactive,
     )
 }
 
@@ -108,6 +119,7 @@ fn inner_choose_referenceable(
     all_entries: &[EntryTypeReference],
     prompt: &str,
     extra_options: Option<Vec<&str>>,
+    no_interactive: bool,
 ) -> ScaffoldResult<Option<Referenceable>> {
     let mut all_options: Vec<String> = all_entries
         .iter()
@@ -121,6 +133,7 @@ fn inner_choose_referenceable(
         all_options.extend(options.into_iter().map(String::from).collect::<Vec<_>>())
     }
 
+    check_interactive(&format!("answer to \"{prompt}\""), no_interactive)?;
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .default(0)
@@ -131,7 +144,7 @@ fn inner_choose_referenceable(
         "Agent" => {
             let role = input_with_case(
             "Which role does this agent play in the relationship ? (eg. \"creator\", \"invitee\")",
-            Case::Snake,
+            Case::Snake, no_interactive,
         )?;
             check_for_reserved_keywords(&role)?;
             Ok(Some(Referenceable::Agent { role }))
@@ -140,6 +153,7 @@ fn inner_choose_referenceable(
             let name = input_with_case(
                 "What name should be given to the link for this hash?",
                 Case::Snake,
+                no_interactive,
             )?;
             Ok(Some(Referenceable::ExternalHash { name }))
         }
@@ -149,6 +163,7 @@ fn inner_choose_referenceable(
             reference_entry_hash: choose_reference_entry_hash(
                 "Reference this entry type with its entry hash or its action hash?",
                 all_entries[selection].reference_entry_hash,
+                no_interactive,
             )?,
         }))),
     }
@@ -157,9 +172,11 @@ fn inner_choose_referenceable(
 pub fn choose_entry_type_reference(
     all_entries: &[EntryTypeReference],
     prompt: &str,
+    no_interactive: bool,
 ) -> ScaffoldResult<EntryTypeReference> {
     let all_options: Vec<String> = all_entries.iter().cloned().map(|r| r.entry_type).collect();
 
+    check_interactive(&format!("answer to \"{prompt}\""), no_interactive)?;
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .default(0)
@@ -1,4 +1,5 @@
 use crate::error::{ScaffoldError, ScaffoldResult};
+use crate::utils::check_interactive;
 use dialoguer::{theme::ColorfulTheme, Select};
 use serde::Serialize;
 use std::str::FromStr;
@@ -11,8 +12,9 @@ pub enum ExampleType {
 }
 
 impl ExampleType {
-    pub fn choose() -> ScaffoldResult<Self> {
+    pub fn choose(no_interactive: bool) -> ScaffoldResult<Self> {
         let examples = [ExampleType::Forum, ExampleType::HelloWorld];
+        check_interactive("the `<example>` argument", no_interactive)?;
         let selection = Select::with_theme(&ColorfulTheme::default())
             .with_prompt("Choose example:")
             .items(&examples)
@@ -22,8 +24,9 @@ impl ExampleType {
         Ok(examples[selection].clone())
     }
 
-    pub fn choose_non_vanilla() -> ScaffoldResult<Self> {
+    pub fn choose_non_vanilla(no_interactive: bool) -> ScaffoldResult<Self> {
         let examples = [ExampleType::Forum];
+        check_interactive("the `<example>` argument", no_interactive)?;
         let selection = Select::with_theme(&ColorfulTheme::default())
             .with_prompt("Choose example:")
             .items(&examples)
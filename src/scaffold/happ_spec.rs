@@ -0,0 +1,694 @@
+use std::{collections::BTreeSet, path::Path, str::FromStr};
+
+use colored::Colorize;
+use convert_case::{Case, Casing};
+use holochain::prelude::DnaManifest;
+use serde::Deserialize;
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::FileTree,
+    reserved_words::check_for_reserved_keywords,
+    scaffold::{
+        app::AppFileTree,
+        collection::{scaffold_collection, CollectionType},
+        dna::{find_dna_manifests, scaffold_dna, DnaFileTree},
+        entry_type::{
+            crud::{Crud, Policy},
+            definitions::{
+                EntryTypeReference, EntryVisibility, FieldDefinition, FieldType, Referenceable,
+            },
+            scaffold_entry_type, EntryTypeOptions,
+        },
+        link_type::{scaffold_link_type, EntryTypeLocation, LinkValidation},
+        zome::{
+            integrity_zome_name, scaffold_coordinator_zome_in_path,
+            scaffold_integrity_zome_with_path, ZomeFileTree,
+        },
+    },
+    templates::ScaffoldedTemplate,
+    utils::check_case,
+};
+
+/// Declarative description of the DNAs, zomes, entry types, link types and collections of a hApp
+///
+/// Fields, link referenceables and collection entry types use the same string syntax as their
+/// respective `hc-scaffold` command-line arguments, eg. `title:String:TextField`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HappSpec {
+    /// Name of the app the DNAs get added to, only needed if there are multiple apps
+    #[serde(default)]
+    pub app: Option<String>,
+    pub dnas: Vec<DnaSpec>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DnaSpec {
+    pub name: String,
+    #[serde(default)]
+    pub zomes: Vec<ZomeSpec>,
+}
+
+/// A coordinator zome and its '{name}_integrity' integrity zome
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ZomeSpec {
+    pub name: String,
+    #[serde(default)]
+    pub entry_types: Vec<EntryTypeSpec>,
+    #[serde(default)]
+    pub link_types: Vec<LinkTypeSpec>,
+    #[serde(default)]
+    pub collections: Vec<CollectionSpec>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EntryTypeSpec {
+    pub name: String,
+    #[serde(default)]
+    pub fields: Vec<String>,
+    #[serde(default = "default_crud")]
+    pub crud: String,
+    #[serde(default)]
+    pub reference_entry_hash: bool,
+    #[serde(default = "default_true")]
+    pub link_from_original_to_each_update: bool,
+    #[serde(default)]
+    pub update_policy: Policy,
+    #[serde(default)]
+    pub delete_policy: Policy,
+    #[serde(default)]
+    pub immutable_fields: Vec<String>,
+    #[serde(default)]
+    pub visibility: EntryVisibility,
+    #[serde(default)]
+    pub required_validations: Option<u8>,
+    #[serde(default)]
+    pub entry_def_name: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LinkTypeSpec {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub delete: bool,
+    #[serde(default)]
+    pub bidirectional: bool,
+    #[serde(default)]
+    pub tag_type: Option<String>,
+    #[serde(default)]
+    pub delete_policy: Policy,
+    #[serde(default)]
+    pub base_is_author: bool,
+    #[serde(default)]
+    pub unique: bool,
+    /// Zome and DNA of the `from` entry type, if it's not defined in this zome, they must come earlier in the spec
+    #[serde(default)]
+    pub from_zome: Option<String>,
+    #[serde(default)]
+    pub from_dna: Option<String>,
+    /// Zome and DNA of the `to` entry type, if it's not defined in this zome, they must come earlier in the spec
+    #[serde(default)]
+    pub to_zome: Option<String>,
+    #[serde(default)]
+    pub to_dna: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CollectionSpec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub collection_type: String,
+    #[serde(default)]
+    pub entry_type: Option<String>,
+    #[serde(default)]
+    pub entry_types: Vec<String>,
+    #[serde(default)]
+    pub key_field: Option<String>,
+    #[serde(default)]
+    pub paginated: bool,
+    #[serde(default)]
+    pub filter_field: Option<String>,
+    #[serde(default)]
+    pub filter_value: Option<String>,
+}
+
+fn default_crud() -> String {
+    String::from("crud")
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl HappSpec {
+    /// Parses the spec as TOML or YAML depending on the extension of the spec file
+    pub fn from_file_content(path: &Path, content: &str) -> ScaffoldResult<HappSpec> {
+        let spec: HappSpec = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(content)?,
+            Some("yaml") | Some("yml") => serde_yml::from_str(content)?,
+            _ => {
+                return Err(ScaffoldError::InvalidPath(
+                    path.to_path_buf(),
+                    String::from("hApp spec files must have a .yaml, .yml or .toml extension"),
+                ))
+            }
+        };
+
+        spec.check()?;
+
+        Ok(spec)
+    }
+
+    /// Validates every name and argument in the spec, so that nothing gets scaffolded if any of them is invalid
+    fn check(&self) -> ScaffoldResult<()> {
+        for dna in &self.dnas {
+            check_case(&dna.name, "dna name", Case::Snake)?;
+            check_for_reserved_keywords(&dna.name)?;
+
+            for zome in &dna.zomes {
+                check_case(&zome.name, "zome name", Case::Snake)?;
+                check_for_reserved_keywords(&zome.name)?;
+
+                for entry_type in &zome.entry_types {
+                    check_case(&entry_type.name, "entry type name", Case::Snake)?;
+                    entry_type.crud()?;
+                    entry_type.fields()?;
+                }
+                zome.entry_types_in_dependency_order()?;
+
+                for link_type in &zome.link_types {
+                    link_type.from_referenceable()?;
+                    link_type.to_referenceable()?;
+                }
+
+                for collection in &zome.collections {
+                    check_case(&collection.name, "collection name", Case::Snake)?;
+                    collection.collection_type()?;
+                    collection.entry_types()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ZomeSpec {
+    /// Sorts the entry types so that the entry types referenced by the fields of another one are scaffolded first
+    pub fn entry_types_in_dependency_order(&self) -> ScaffoldResult<Vec<EntryTypeSpec>> {
+        let entry_type_names: BTreeSet<String> = self
+            .entry_types
+            .iter()
+            .map(|e| e.name.to_case(Case::Pascal))
+            .collect();
+
+        let mut scaffolded: BTreeSet<String> = BTreeSet::new();
+        let mut pending = self.entry_types.clone();
+        let mut sorted = Vec::new();
+
+        while !pending.is_empty() {
+            let (ready, not_ready): (Vec<EntryTypeSpec>, Vec<EntryTypeSpec>) =
+                pending.into_iter().partition(|entry_type| {
+                    entry_type
+                        .dependencies()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|d| entry_type_names.contains(d))
+                        .all(|d| scaffolded.contains(&d))
+                });
+
+            if ready.is_empty() {
+                return Err(ScaffoldError::InvalidArguments(format!(
+                    "circular references between the entry types {} of zome {}",
+                    not_ready
+                        .iter()
+                        .map(|e| e.name.clone())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    self.name
+                )));
+            }
+
+            for entry_type in ready {
+                scaffolded.insert(entry_type.name.to_case(Case::Pascal));
+                sorted.push(entry_type);
+            }
+            pending = not_ready;
+        }
+
+        Ok(sorted)
+    }
+}
+
+impl EntryTypeSpec {
+    pub fn fields(&self) -> ScaffoldResult<Vec<FieldDefinition>> {
+        self.fields
+            .iter()
+            .map(|f| FieldDefinition::from_str(f))
+            .collect()
+    }
+
+    pub fn crud(&self) -> ScaffoldResult<Crud> {
+        Crud::from_str(&self.crud)
+    }
+
+    /// Names of the entry types this entry type links from
+    fn dependencies(&self) -> ScaffoldResult<Vec<String>> {
+        Ok(self
+            .fields()?
+            .into_iter()
+            .filter_map(|f| match f.linked_from {
+                Some(Referenceable::EntryType(r)) => Some(r.entry_type.to_case(Case::Pascal)),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+impl LinkTypeSpec {
+    pub fn from_referenceable(&self) -> ScaffoldResult<Referenceable> {
+        Referenceable::from_str(&self.from)
+    }
+
+    pub fn to_referenceable(&self) -> ScaffoldResult<Referenceable> {
+        Referenceable::from_str(&self.to)
+    }
+
+    pub fn tag_type(&self) -> ScaffoldResult<Option<FieldType>> {
+        self.tag_type
+            .as_deref()
+            .map(FieldType::parse_struct)
+            .transpose()
+    }
+}
+
+impl CollectionSpec {
+    pub fn collection_type(&self) -> ScaffoldResult<CollectionType> {
+        CollectionType::from_str(&self.collection_type)
+    }
+
+    /// Entry types of the collection, given either as `entry_type` or as a list in `entry_types`
+    pub fn entry_types(&self) -> ScaffoldResult<Vec<EntryTypeReference>> {
+        match (&self.entry_type, self.entry_types.is_empty()) {
+            (Some(entry_type), true) => Ok(vec![EntryTypeReference::from_str(entry_type)?]),
+            (None, false) => self
+                .entry_types
+                .iter()
+                .map(|entry_type| EntryTypeReference::from_str(entry_type))
+                .collect(),
+            _ => Err(ScaffoldError::InvalidArguments(format!(
+                "collection {} must have either an entry_type or a list of entry_types",
+                self.name
+            ))),
+        }
+    }
+}
+
+/// Scaffolds everything described in the spec, skipping the DNAs and zomes that already exist
+///
+/// DNAs and zomes are scaffolded first, then entry types, link types and collections are added to each zome,
+/// in that order. Nothing is written to disk: the whole hApp is built in the returned file tree.
+pub fn apply_happ_spec(
+    mut file_tree: FileTree,
+    template_file_tree: &FileTree,
+    spec: &HappSpec,
+    no_ui: bool,
+    no_spec: bool,
+    no_interactive: bool,
+) -> ScaffoldResult<FileTree> {
+    for dna in &spec.dnas {
+        let dna_exists = find_dna_manifests(&file_tree)?
+            .into_iter()
+            .any(|(_, m)| m.name().eq(&dna.name));
+
+        if !dna_exists {
+            let app_file_tree =
+                AppFileTree::get_or_choose(file_tree, spec.app.as_deref(), no_interactive)?;
+            let ScaffoldedTemplate { file_tree: f, .. } =
+                scaffold_dna(app_file_tree, template_file_tree, &dna.name, no_interactive)?;
+            file_tree = f;
+            println!("DNA {} scaffolded", dna.name.italic());
+        }
+
+        for zome in &dna.zomes {
+            file_tree = scaffold_zome_pair_if_missing(
+                file_tree,
+                template_file_tree,
+                &dna.name,
+                &zome.name,
+                no_interactive,
+            )?;
+        }
+    }
+
+    for dna in &spec.dnas {
+        for zome in &dna.zomes {
+            let integrity_zome = integrity_zome_name(&zome.name);
+
+            for entry_type in zome.entry_types_in_dependency_order()? {
+                let dna_file_tree =
+                    DnaFileTree::get_or_choose(file_tree, Some(&dna.name), no_interactive)?;
+                let zome_file_tree = ZomeFileTree::get_or_choose_integrity(
+                    dna_file_tree,
+                    Some(&integrity_zome),
+                    no_interactive,
+                )?;
+
+                let ScaffoldedTemplate { file_tree: f, .. } = scaffold_entry_type(
+                    zome_file_tree,
+                    template_file_tree,
+                    &entry_type.name,
+                    EntryTypeOptions {
+                        crud: Some(entry_type.crud()?),
+                        reference_entry_hash: Some(entry_type.reference_entry_hash),
+                        link_from_original_to_each_update: Some(
+                            entry_type.link_from_original_to_each_update,
+                        ),
+                        update_policy: entry_type.update_policy,
+                        delete_policy: entry_type.delete_policy,
+                        immutable_fields: entry_type.immutable_fields.clone(),
+                        visibility: entry_type.visibility,
+                        required_validations: entry_type.required_validations,
+                        entry_def_name: entry_type.entry_def_name.clone(),
+                        fields: Some(entry_type.fields()?),
+                    },
+                    no_ui,
+                    no_spec,
+                    no_interactive,
+                )?;
+                file_tree = f;
+                println!("Entry type {} scaffolded", entry_type.name.italic());
+            }
+
+            for link_type in &zome.link_types {
+                let dna_file_tree =
+                    DnaFileTree::get_or_choose(file_tree, Some(&dna.name), no_interactive)?;
+                let zome_file_tree = ZomeFileTree::get_or_choose_integrity(
+                    dna_file_tree,
+                    Some(&integrity_zome),
+                    no_interactive,
+                )?;
+
+                let ScaffoldedTemplate { file_tree: f, .. } = scaffold_link_type(
+                    zome_file_tree,
+                    template_file_tree,
+                    Some(&link_type.from_referenceable()?),
+                    Some(&link_type.to_referenceable()?),
+                    Some(link_type.delete),
+                    Some(link_type.bidirectional),
+                    link_type.tag_type()?.as_ref(),
+                    LinkValidation {
+                        delete_policy: link_type.delete_policy,
+                        base_is_author: link_type.base_is_author,
+                        unique: link_type.unique,
+                        ..Default::default()
+                    },
+                    &EntryTypeLocation {
+                        dna: link_type.from_dna.clone(),
+                        zome: link_type.from_zome.as_deref().map(integrity_zome_name),
+                    },
+                    &EntryTypeLocation {
+                        dna: link_type.to_dna.clone(),
+                        zome: link_type.to_zome.as_deref().map(integrity_zome_name),
+                    },
+                    no_ui,
+                    no_spec,
+                    no_interactive,
+                )?;
+                file_tree = f;
+                println!(
+                    "Link type from {} to {} scaffolded",
+                    link_type.from.italic(),
+                    link_type.to.italic()
+                );
+            }
+
+            for collection in &zome.collections {
+                let dna_file_tree =
+                    DnaFileTree::get_or_choose(file_tree, Some(&dna.name), no_interactive)?;
+                let zome_file_tree = ZomeFileTree::get_or_choose_integrity(
+                    dna_file_tree,
+                    Some(&integrity_zome),
+                    no_interactive,
+                )?;
+
+                let ScaffoldedTemplate { file_tree: f, .. } = scaffold_collection(
+                    zome_file_tree,
+                    template_file_tree,
+                    &collection.name,
+                    Some(collection.collection_type()?),
+                    collection.entry_types()?,
+                    collection.key_field.clone(),
+                    collection.paginated,
+                    collection.filter_field.clone(),
+                    collection.filter_value.clone(),
+                    no_ui,
+                    no_spec,
+                    no_interactive,
+                )?;
+                file_tree = f;
+                println!("Collection {} scaffolded", collection.name.italic());
+            }
+        }
+    }
+
+    Ok(file_tree)
+}
+
+/// Scaffolds the coordinator zome and its integrity zome next to the other zomes of the DNA, if they don't exist yet
+fn scaffold_zome_pair_if_missing(
+    file_tree: FileTree,
+    template_file_tree: &FileTree,
+    dna_name: &str,
+    zome_name: &str,
+    no_interactive: bool,
+) -> ScaffoldResult<FileTree> {
+    let dna_file_tree = DnaFileTree::get_or_choose(file_tree, Some(dna_name), no_interactive)?;
+    let dna_manifest_path = dna_file_tree.dna_manifest_path.clone();
+
+    // The DNA manifest lives in "{dna folder}/workdir/dna.yaml"
+    let zomes_path = dna_manifest_path
+        .parent()
+        .and_then(|workdir| workdir.parent())
+        .map(|dna_path| dna_path.join("zomes"))
+        .ok_or(ScaffoldError::InvalidPath(
+            dna_manifest_path.clone(),
+            String::from("DNA manifest is not inside a workdir folder"),
+        ))?;
+
+    let integrity_zome = integrity_zome_name(zome_name);
+
+    let (integrity_exists, coordinator_exists) = match &dna_file_tree.dna_manifest {
+        DnaManifest::V1(v1) => (
+            v1.integrity
+                .zomes
+                .iter()
+                .any(|z| z.name.0.eq(&integrity_zome)),
+            v1.coordinator.zomes.iter().any(|z| z.name.0.eq(zome_name)),
+        ),
+    };
+
+    let dna_file_tree = if integrity_exists {
+        dna_file_tree
+    } else {
+        let ScaffoldedTemplate { file_tree, .. } = scaffold_integrity_zome_with_path(
+            dna_file_tree,
+            template_file_tree,
+            &integrity_zome,
+            &zomes_path.join("integrity"),
+        )?;
+        println!("Integrity zome {} scaffolded", integrity_zome.italic());
+        DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?
+    };
+
+    if coordinator_exists {
+        return Ok(dna_file_tree.file_tree());
+    }
+
+    let ScaffoldedTemplate { file_tree, .. } = scaffold_coordinator_zome_in_path(
+        dna_file_tree,
+        template_file_tree,
+        zome_name,
+        Some(&vec![integrity_zome]),
+        &zomes_path.join("coordinator"),
+    )?;
+    println!("Coordinator zome {} scaffolded", zome_name.italic());
+
+    Ok(file_tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        file_tree::{file_content, file_exists},
+        scaffold::web_app::{
+            package_manager::PackageManager, scaffold_web_app, template_type::TemplateType,
+        },
+    };
+
+    #[test]
+    fn test_parse_yaml_spec_in_dependency_order() {
+        let yaml = r#"
+dnas:
+  - name: forum
+    zomes:
+      - name: posts
+        entry_types:
+          - name: comment
+            crud: crd
+            visibility: private
+            fields: ["comment:String:TextArea", "post_hash:ActionHash::post"]
+          - name: post
+            fields: ["title:String:TextField"]
+        collections:
+          - name: all_posts
+            type: global
+            entry_type: post
+            paginated: true
+          - name: posts_by_title
+            type: by-path
+            entry_type: post
+            key_field: title
+          - name: latest_posts
+            type: time-indexed
+            entry_type: post
+          - name: feed
+            type: global
+            entry_types: [post, comment]
+"#;
+        let spec = HappSpec::from_file_content(Path::new("happ-spec.yaml"), yaml).unwrap();
+        let zome = &spec.dnas[0].zomes[0];
+
+        let names: Vec<String> = zome
+            .entry_types_in_dependency_order()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(names, vec!["post", "comment"]);
+        assert!(!zome.entry_types[0].crud().unwrap().update);
+        assert!(zome.entry_types[1].link_from_original_to_each_update);
+        assert_eq!(zome.entry_types[0].visibility, EntryVisibility::Private);
+        assert_eq!(zome.entry_types[1].visibility, EntryVisibility::Public);
+        assert!(zome.collections[0].paginated);
+        assert!(!zome.collections[1].paginated);
+        assert!(matches!(
+            zome.collections[1].collection_type(),
+            Ok(CollectionType::ByPath { .. })
+        ));
+        assert_eq!(zome.collections[1].key_field.as_deref(), Some("title"));
+        assert!(matches!(
+            zome.collections[2].collection_type(),
+            Ok(CollectionType::TimeIndexed)
+        ));
+        let feed_entry_types: Vec<String> = zome.collections[3]
+            .entry_types()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.entry_type)
+            .collect();
+        assert_eq!(feed_entry_types, vec!["Post", "Comment"]);
+    }
+
+    #[test]
+    fn test_parse_toml_spec() {
+        let toml = r#"
+[[dnas]]
+name = "forum"
+
+[[dnas.zomes]]
+name = "posts"
+
+[[dnas.zomes.link_types]]
+from = "agent"
+to = "post"
+delete = true
+delete_policy = "author"
+base_is_author = true
+"#;
+        let spec = HappSpec::from_file_content(Path::new("happ-spec.toml"), toml).unwrap();
+        let link_type = &spec.dnas[0].zomes[0].link_types[0];
+
+        assert_eq!(
+            link_type.from_referenceable().unwrap(),
+            Referenceable::Agent {
+                role: String::from("agent")
+            }
+        );
+        assert!(link_type.delete);
+        assert!(!link_type.bidirectional);
+        assert_eq!(link_type.delete_policy, Policy::Author);
+        assert!(link_type.base_is_author);
+        assert!(!link_type.unique);
+    }
+
+    #[test]
+    fn test_circular_entry_type_references_are_rejected() {
+        let yaml = r#"
+dnas:
+  - name: forum
+    zomes:
+      - name: posts
+        entry_types:
+          - name: post
+            fields: ["comment_hash:ActionHash::comment"]
+          - name: comment
+            fields: ["post_hash:ActionHash::post"]
+"#;
+        assert!(HappSpec::from_file_content(Path::new("happ-spec.yml"), yaml).is_err());
+    }
+
+    #[test]
+    fn test_apply_happ_spec_in_memory() {
+        let template_file_tree = TemplateType::Vanilla.file_tree().unwrap();
+        let ScaffoldedTemplate { file_tree, .. } = scaffold_web_app(
+            "forum",
+            None,
+            PackageManager::Npm,
+            true,
+            &template_file_tree,
+            false,
+        )
+        .unwrap();
+        let yaml = r#"
+dnas:
+  - name: forum
+    zomes:
+      - name: posts
+        entry_types:
+          - name: post
+            fields: ["title:String:TextField"]
+        link_types:
+          - from: post
+            to: post
+        collections:
+          - name: all_posts
+            type: global
+            entry_type: post
+"#;
+        let spec = HappSpec::from_file_content(Path::new("happ-spec.yml"), yaml).unwrap();
+
+        // The zome crates scaffolded for the spec are located in memory, before anything is written to disk
+        let file_tree =
+            apply_happ_spec(file_tree, &template_file_tree, &spec, true, true, true).unwrap();
+
+        let integrity_lib_rs = file_content(
+            &file_tree,
+            Path::new("dnas/forum/zomes/integrity/posts/src/lib.rs"),
+        )
+        .unwrap();
+        assert!(integrity_lib_rs.contains("pub mod post_to_posts;"));
+        assert!(integrity_lib_rs.contains("AllPosts"));
+        assert!(file_exists(
+            &file_tree,
+            Path::new("dnas/forum/zomes/coordinator/posts/src/all_posts.rs")
+        ));
+    }
+}
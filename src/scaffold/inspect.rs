@@ -0,0 +1,351 @@
+use std::path::{Path, PathBuf};
+
+use holochain_types::prelude::{DnaManifest, ZomeManifest};
+use serde::Serialize;
+
+use crate::{
+    error::ScaffoldResult,
+    file_tree::FileTree,
+    scaffold::{
+        app::find_app_manifests,
+        dna::{find_dna_manifests, DnaFileTree},
+        entry_type::integrity::{find_entry_type_struct, get_all_entry_types},
+        link_type::integrity::get_all_link_types,
+        zome::{coordinator::find_all_extern_functions, ZomeFileTree},
+    },
+};
+
+/// Read-only inventory of the apps, DNAs and zomes of an existing hApp
+#[derive(Serialize, Debug)]
+pub struct HappInspection {
+    pub apps: Vec<AppInspection>,
+    pub dnas: Vec<DnaInspection>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AppInspection {
+    pub name: String,
+    pub manifest_path: PathBuf,
+    /// Names of the roles of the app, one per DNA
+    pub roles: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DnaInspection {
+    pub name: String,
+    pub manifest_path: PathBuf,
+    pub integrity_zomes: Vec<IntegrityZomeInspection>,
+    pub coordinator_zomes: Vec<CoordinatorZomeInspection>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct IntegrityZomeInspection {
+    pub name: String,
+    pub crate_path: PathBuf,
+    pub entry_types: Vec<EntryTypeInspection>,
+    pub link_types: Vec<String>,
+    pub extern_functions: Vec<ExternFunctionInspection>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CoordinatorZomeInspection {
+    pub name: String,
+    pub crate_path: PathBuf,
+    /// Names of the integrity zomes this coordinator zome depends on
+    pub dependencies: Vec<String>,
+    pub extern_functions: Vec<ExternFunctionInspection>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EntryTypeInspection {
+    pub name: String,
+    pub reference_entry_hash: bool,
+    pub fields: Vec<FieldInspection>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FieldInspection {
+    pub name: String,
+    pub rust_type: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExternFunctionInspection {
+    pub name: String,
+    pub input: Option<String>,
+    pub output: Option<String>,
+}
+
+/// Walks the given project to list its apps, DNAs, zomes, entry types, link types and extern functions
+pub fn inspect_happ(file_tree: &FileTree) -> ScaffoldResult<HappInspection> {
+    let apps = find_app_manifests(file_tree)?
+        .into_iter()
+        .map(|(manifest_path, app_manifest)| AppInspection {
+            name: app_manifest.app_name().to_string(),
+            manifest_path,
+            roles: app_manifest
+                .app_roles()
+                .into_iter()
+                .map(|role| role.name)
+                .collect(),
+        })
+        .collect();
+
+    let dnas = find_dna_manifests(file_tree)?
+        .into_keys()
+        .map(|dna_manifest_path| inspect_dna(file_tree, &dna_manifest_path))
+        .collect::<ScaffoldResult<Vec<DnaInspection>>>()?;
+
+    Ok(HappInspection { apps, dnas })
+}
+
+fn inspect_dna(file_tree: &FileTree, dna_manifest_path: &Path) -> ScaffoldResult<DnaInspection> {
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree.clone(), dna_manifest_path)?;
+
+    let (integrity_zomes, coordinator_zomes) = match dna_file_tree.dna_manifest.clone() {
+        DnaManifest::V1(v1) => (v1.integrity.zomes, v1.coordinator.zomes),
+    };
+
+    let integrity_zomes = integrity_zomes
+        .into_iter()
+        .map(|zome_manifest| inspect_integrity_zome(&dna_file_tree, zome_manifest))
+        .collect::<ScaffoldResult<Vec<IntegrityZomeInspection>>>()?;
+
+    let coordinator_zomes = coordinator_zomes
+        .into_iter()
+        .map(|zome_manifest| inspect_coordinator_zome(&dna_file_tree, zome_manifest))
+        .collect::<ScaffoldResult<Vec<CoordinatorZomeInspection>>>()?;
+
+    Ok(DnaInspection {
+        name: dna_file_tree.dna_manifest.name(),
+        manifest_path: dna_manifest_path.to_path_buf(),
+        integrity_zomes,
+        coordinator_zomes,
+    })
+}
+
+fn inspect_integrity_zome(
+    dna_file_tree: &DnaFileTree,
+    zome_manifest: ZomeManifest,
+) -> ScaffoldResult<IntegrityZomeInspection> {
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(
+        DnaFileTree::from_dna_manifest_path(
+            dna_file_tree.file_tree_ref().clone(),
+            &dna_file_tree.dna_manifest_path,
+        )?,
+        zome_manifest,
+    )?;
+
+    let entry_types = get_all_entry_types(&zome_file_tree)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entry_type_reference| {
+            let fields =
+                match find_entry_type_struct(&zome_file_tree, &entry_type_reference.entry_type)? {
+                    Some((_, item_struct)) => item_struct
+                        .fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, field)| FieldInspection {
+                            name: field
+                                .ident
+                                .as_ref()
+                                .map(|ident| ident.to_string())
+                                .unwrap_or_else(|| i.to_string()),
+                            rust_type: type_to_string(&field.ty),
+                        })
+                        .collect(),
+                    None => vec![],
+                };
+            Ok(EntryTypeInspection {
+                name: entry_type_reference.entry_type,
+                reference_entry_hash: entry_type_reference.reference_entry_hash,
+                fields,
+            })
+        })
+        .collect::<ScaffoldResult<Vec<EntryTypeInspection>>>()?;
+
+    Ok(IntegrityZomeInspection {
+        name: zome_file_tree.zome_manifest.name.0.to_string(),
+        crate_path: zome_file_tree.zome_crate_path.clone(),
+        entry_types,
+        link_types: get_all_link_types(&zome_file_tree)?.unwrap_or_default(),
+        extern_functions: inspect_extern_functions(&zome_file_tree)?,
+    })
+}
+
+fn inspect_coordinator_zome(
+    dna_file_tree: &DnaFileTree,
+    zome_manifest: ZomeManifest,
+) -> ScaffoldResult<CoordinatorZomeInspection> {
+    let dependencies = zome_manifest
+        .dependencies
+        .iter()
+        .flatten()
+        .map(|dependency| dependency.name.0.to_string())
+        .collect();
+
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(
+        DnaFileTree::from_dna_manifest_path(
+            dna_file_tree.file_tree_ref().clone(),
+            &dna_file_tree.dna_manifest_path,
+        )?,
+        zome_manifest,
+    )?;
+
+    Ok(CoordinatorZomeInspection {
+        name: zome_file_tree.zome_manifest.name.0.to_string(),
+        crate_path: zome_file_tree.zome_crate_path.clone(),
+        dependencies,
+        extern_functions: inspect_extern_functions(&zome_file_tree)?,
+    })
+}
+
+fn inspect_extern_functions(
+    zome_file_tree: &ZomeFileTree,
+) -> ScaffoldResult<Vec<ExternFunctionInspection>> {
+    let mut extern_functions: Vec<ExternFunctionInspection> =
+        find_all_extern_functions(zome_file_tree)?
+            .into_iter()
+            .map(|item_fn| ExternFunctionInspection {
+                name: item_fn.sig.ident.to_string(),
+                input: item_fn.sig.inputs.first().and_then(|arg| match arg {
+                    syn::FnArg::Typed(pat_type) => Some(type_to_string(&pat_type.ty)),
+                    syn::FnArg::Receiver(_) => None,
+                }),
+                output: match &item_fn.sig.output {
+                    syn::ReturnType::Type(_, ty) => Some(type_to_string(ty)),
+                    syn::ReturnType::Default => None,
+                },
+            })
+            .collect();
+
+    extern_functions.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(extern_functions)
+}
+
+/// Renders a type as it would be written by hand, eg. `Option<ActionHash>` instead of `Option < ActionHash >`
+fn type_to_string(ty: &syn::Type) -> String {
+    let type_alias: syn::File = syn::parse_quote! { type T = #ty; };
+    let formatted = prettyplease::unparse(&type_alias);
+
+    formatted
+        .trim()
+        .trim_start_matches("type T = ")
+        .trim_end_matches(';')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        scaffold::{
+            happ_spec::{apply_happ_spec, HappSpec},
+            web_app::{
+                package_manager::PackageManager, scaffold_web_app, template_type::TemplateType,
+            },
+        },
+        templates::ScaffoldedTemplate,
+    };
+
+    #[test]
+    fn test_type_to_string() {
+        let types = [
+            "Option<ActionHash>",
+            "BTreeMap<String, Vec<u8>>",
+            "ExternResult<Vec<(String, u32)>>",
+            "[u8; 32]",
+            "&str",
+        ];
+        for ty in types {
+            assert_eq!(type_to_string(&syn::parse_str(ty).unwrap()), ty);
+        }
+    }
+
+    #[test]
+    fn test_inspect_happ() {
+        let template_file_tree = TemplateType::Vanilla.file_tree().unwrap();
+        let ScaffoldedTemplate { file_tree, .. } = scaffold_web_app(
+            "forum",
+            None,
+            PackageManager::Npm,
+            true,
+            &template_file_tree,
+            false,
+        )
+        .unwrap();
+        let spec = HappSpec::from_file_content(
+            Path::new("happ-spec.yml"),
+            r#"
+dnas:
+  - name: forum
+    zomes:
+      - name: posts
+        entry_types:
+          - name: post
+            fields: ["title:String:TextField", "tags:Vec<String>"]
+          - name: comment
+            fields: ["post_hash:ActionHash::Post"]
+"#,
+        )
+        .unwrap();
+        let file_tree =
+            apply_happ_spec(file_tree, &template_file_tree, &spec, true, true, true).unwrap();
+
+        let inspection = inspect_happ(&file_tree).unwrap();
+
+        assert_eq!(inspection.apps.len(), 1);
+        assert_eq!(inspection.apps[0].name, "forum");
+        assert_eq!(inspection.apps[0].roles, vec![String::from("forum")]);
+
+        assert_eq!(inspection.dnas.len(), 1);
+        let dna = &inspection.dnas[0];
+        assert_eq!(dna.name, "forum");
+
+        assert_eq!(dna.integrity_zomes.len(), 1);
+        let integrity_zome = &dna.integrity_zomes[0];
+        assert_eq!(integrity_zome.name, "posts_integrity");
+        let entry_types: Vec<(&str, Vec<(&str, &str)>)> = integrity_zome
+            .entry_types
+            .iter()
+            .map(|entry_type| {
+                (
+                    entry_type.name.as_str(),
+                    entry_type
+                        .fields
+                        .iter()
+                        .map(|f| (f.name.as_str(), f.rust_type.as_str()))
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            entry_types,
+            vec![
+                ("Post", vec![("title", "String"), ("tags", "Vec<String>")]),
+                ("Comment", vec![("post_hash", "ActionHash")]),
+            ]
+        );
+        assert!(integrity_zome
+            .extern_functions
+            .iter()
+            .any(|f| f.name == "validate"));
+
+        assert_eq!(dna.coordinator_zomes.len(), 1);
+        let coordinator_zome = &dna.coordinator_zomes[0];
+        assert_eq!(coordinator_zome.name, "posts");
+        assert_eq!(
+            coordinator_zome.dependencies,
+            vec![String::from("posts_integrity")]
+        );
+        let create_post = coordinator_zome
+            .extern_functions
+            .iter()
+            .find(|f| f.name == "create_post")
+            .unwrap();
+        assert_eq!(create_post.input.as_deref(), Some("Post"));
+        assert_eq!(create_post.output.as_deref(), Some("ExternResult<Record>"));
+    }
+}
@@ -1,13 +1,15 @@
-use std::path::PathBuf;
+use std::{ffi::OsString, path::PathBuf};
 
+use colored::Colorize;
 use convert_case::{Case, Casing};
 use dialoguer::{theme::ColorfulTheme, Confirm, Select};
+use quote::format_ident;
 
 use crate::{
     error::{ScaffoldError, ScaffoldResult},
-    file_tree::{insert_file, map_file, FileTree},
+    file_tree::{find_map_rust_files, insert_file, map_file, FileTree},
     templates::{link_type::scaffold_link_type_templates, ScaffoldedTemplate},
-    utils::input_with_case,
+    utils::{check_interactive, input_with_case, unparse_pretty},
 };
 
 use self::{
@@ -15,19 +17,70 @@ use self::{
 };
 
 use super::{
-    app::AppFileTree,
+    app::{
+        cargo::add_crate_workspace_dependency,
+        utils::{get_or_choose_app_manifest_path_for_dna_manifest, role_for_dna},
+        AppFileTree,
+    },
     dna::DnaFileTree,
     entry_type::{
-        definitions::{Cardinality, Referenceable},
+        crud::Policy,
+        definitions::{Cardinality, EntryTypeReference, FieldType, Referenceable},
         integrity::get_all_entry_types,
         utils::{get_or_choose_optional_reference_type, get_or_choose_referenceable},
     },
-    zome::{utils::get_coordinator_zomes_for_integrity, ZomeFileTree},
+    zome::{
+        coordinator::find_extern_function_in_zomes, module_path,
+        utils::get_coordinator_zomes_for_integrity, ZomeFileTree,
+    },
 };
 
 pub mod coordinator;
 pub mod integrity;
 
+/// Rules checked in the validation of the links of a link type
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LinkValidation {
+    /// Who can delete the links: anyone, or only the agent that created them
+    pub delete_policy: Policy,
+    /// Whether the base of the links must be the public key of the agent that creates them
+    pub base_is_author: bool,
+    /// Whether each agent can have at most one link from a given base to a given target
+    pub unique: bool,
+    /// Entry types that the targets of the links can be, when they are tagged with the name of their entry type
+    pub tagged_target_entry_types: Vec<EntryTypeReference>,
+}
+
+/// Integrity zome, and DNA, in which the entry type at one end of a link type is defined,
+/// when it's not the integrity zome of the link type
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EntryTypeLocation {
+    /// Name of the DNA, installed by a role of the app of the link type
+    pub dna: Option<String>,
+    /// Name of the integrity zome, chosen among the integrity zomes of `dna` if `None`
+    pub zome: Option<String>,
+}
+
+impl EntryTypeLocation {
+    fn is_local(&self) -> bool {
+        self.dna.is_none() && self.zome.is_none()
+    }
+}
+
+/// An entry type at one end of a link type that is defined outside of the integrity zome of the link type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForeignEntryType {
+    /// Defined in another integrity zome of the same DNA, whose crate the integrity zome of the link type depends on
+    Zome { zome: String },
+    /// Defined in another DNA of the app, installed by the cells of `role`, whose records are fetched
+    /// with `get_fn` in `coordinator_zome`
+    Dna {
+        role: String,
+        coordinator_zome: String,
+        get_fn: String,
+    },
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn scaffold_link_type(
     zome_file_tree: ZomeFileTree,
@@ -36,58 +89,154 @@ pub fn scaffold_link_type(
     to_referenceable: Option<&Referenceable>,
     delete: Option<bool>,
     bidirectional: Option<bool>,
+    tag_type: Option<&FieldType>,
+    validation: LinkValidation,
+    from_location: &EntryTypeLocation,
+    to_location: &EntryTypeLocation,
     no_ui: bool,
     no_spec: bool,
+    no_interactive: bool,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
+    let tag_type_label = match tag_type {
+        Some(FieldType::Struct { label, .. }) => Some(label.as_str()),
+        Some(_) => {
+            return Err(ScaffoldError::InvalidArguments(String::from(
+                "The tag type of a link type must be a struct",
+            )))
+        }
+        None => None,
+    };
+
     let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
     let zome_manifest = zome_file_tree.zome_manifest.clone();
-    let all_entry_types = get_all_entry_types(&zome_file_tree)?.unwrap_or_default();
 
+    let from_zome_file_tree =
+        located_integrity_zome(&zome_file_tree, from_location, no_interactive)?;
+    let from_entry_types = get_all_entry_types(&from_zome_file_tree)?.unwrap_or_default();
+
+    if from_referenceable.is_none() {
+        check_interactive("the `<from-referenceable>` argument", no_interactive)?;
+    }
     let from_referenceable = get_or_choose_referenceable(
         "Link from which entry type?",
-        &zome_file_tree,
+        &from_zome_file_tree,
         from_referenceable,
-        &all_entry_types,
+        &from_entry_types,
+        no_interactive,
+    )?;
+    let from_foreign_entry_type = foreign_entry_type(
+        &zome_file_tree,
+        &from_zome_file_tree,
+        Some(&from_referenceable),
+        &from_entry_types,
+        no_interactive,
     )?;
 
+    if validation.base_is_author && !matches!(from_referenceable, Referenceable::Agent { .. }) {
+        return Err(ScaffoldError::InvalidArguments(String::from(
+            "only link types from an agent role can require their base to be the author of the links",
+        )));
+    }
+
+    let to_zome_file_tree = located_integrity_zome(&zome_file_tree, to_location, no_interactive)?;
+    let to_entry_types = get_all_entry_types(&to_zome_file_tree)?.unwrap_or_default();
+
+    if to_referenceable.is_none() {
+        check_interactive("the `<to-referenceable>` argument", no_interactive)?;
+    }
     let to_referenceable = get_or_choose_optional_reference_type(
         "Link to which entry type?",
-        &zome_file_tree,
+        &to_zome_file_tree,
         to_referenceable,
-        &all_entry_types,
+        &to_entry_types,
+        no_interactive,
+    )?;
+    let to_foreign_entry_type = foreign_entry_type(
+        &zome_file_tree,
+        &to_zome_file_tree,
+        to_referenceable.as_ref(),
+        &to_entry_types,
+        no_interactive,
     )?;
 
     let link_type = match &to_referenceable {
         Some(to_referenceable) => link_type_name(&from_referenceable, to_referenceable),
-        None => input_with_case("Enter link type name:", Case::Pascal)?,
+        None => input_with_case("Enter link type name:", Case::Pascal, no_interactive)?,
     };
 
     let bidirectional = match (&to_referenceable, bidirectional) {
         (None, _) => false,
         (_, Some(b)) => b,
-        _ => Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Should the link be bidirectional?")
-            .interact()?,
+        _ => {
+            check_interactive("the `--bidirectional` option", no_interactive)?;
+            Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Should the link be bidirectional?")
+                .interact()?
+        }
     };
 
     let delete = match delete {
         Some(d) => d,
-        None => Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Can the link be deleted?")
-            .interact()?,
+        None => {
+            check_interactive("the `--delete` option", no_interactive)?;
+            Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Can the link be deleted?")
+                .interact()?
+        }
     };
 
+    if !delete && validation.delete_policy != Policy::Anyone {
+        return Err(ScaffoldError::InvalidArguments(String::from(
+            "the delete policy only applies to link types that can be deleted",
+        )));
+    }
+
     // 1. Create an LINK_TYPE_NAME.rs in "src/", with the link type validation
     let crate_src_path = zome_file_tree.zome_crate_path.join("src");
 
     let link_type_file_name = PathBuf::from(format!("{}.rs", link_type.to_case(Case::Snake)));
 
+    // The tag struct is only defined here if the integrity zome doesn't already have it, and then it's
+    // exported from the root of the crate like the rest of the link type module
+    let existing_tag_type_path = match tag_type_label {
+        Some(label) => find_tag_type_path(&zome_file_tree, label)?,
+        None => None,
+    };
+    let tag_type_path: Option<syn::Path> = tag_type_label.map(|label| {
+        existing_tag_type_path.clone().unwrap_or_else(|| {
+            let ident = format_ident!("{label}");
+            syn::parse_quote! { #ident }
+        })
+    });
+    let tag_struct = match tag_type.and_then(|t| t.rust_type_definition()) {
+        Some(definition) if existing_tag_type_path.is_none() => {
+            let mut item_struct: syn::ItemStruct = syn::parse2(definition)?;
+            item_struct.attrs = vec![syn::parse_quote! {
+                #[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+            }];
+            Some(item_struct)
+        }
+        _ => None,
+    };
+
     let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
 
+    // The entry types of other integrity zomes of the DNA are deserialized in the validation with their own types
+    for foreign_entry_type in [&from_foreign_entry_type, &to_foreign_entry_type] {
+        if let Some(ForeignEntryType::Zome { zome }) = foreign_entry_type {
+            file_tree =
+                add_crate_workspace_dependency(file_tree, &zome_file_tree.zome_crate_path, zome)?;
+        }
+    }
+
     insert_file(
         &mut file_tree,
         &crate_src_path.join(&link_type_file_name),
-        "use hdi::prelude::*;",
+        &unparse_pretty(&syn::parse_quote! {
+            use hdi::prelude::*;
+
+            #tag_struct
+        }),
     )?;
 
     // 2. Add this file as a module in the entry point for the crate
@@ -115,6 +264,10 @@ pub use {}::*;
         &Some(from_referenceable.clone()),
         &to_referenceable,
         delete,
+        tag_type_path.as_ref(),
+        &validation,
+        &from_foreign_entry_type,
+        &to_foreign_entry_type,
         &link_type_file_name,
     )?;
 
@@ -127,6 +280,14 @@ pub use {}::*;
                 &to_referenceable,
                 &Some(from_referenceable.clone()),
                 delete,
+                tag_type_path.as_ref(),
+                // The base of the inverse links is the target of the original ones
+                &LinkValidation {
+                    base_is_author: false,
+                    ..validation.clone()
+                },
+                &to_foreign_entry_type,
+                &from_foreign_entry_type,
                 &link_type_file_name,
             )?;
             Some(inverse_link_type)
@@ -155,6 +316,10 @@ pub use {}::*;
                 .iter()
                 .map(|z| z.name.to_string())
                 .collect();
+            check_interactive(
+                "a choice of coordinator zome for the link type functions, as the integrity zome has more than one",
+                no_interactive,
+            )?;
             let selection = Select::with_theme(&ColorfulTheme::default())
                 .with_prompt(
                     "Which coordinator zome should the link type functions be scaffolded in?",
@@ -180,12 +345,29 @@ pub use {}::*;
         &to_referenceable,
         delete,
         bidirectional,
+        tag_type_path.as_ref(),
+        &from_foreign_entry_type,
+        &to_foreign_entry_type,
     )?;
 
-    let app_file_tree = AppFileTree::get_or_choose(zome_file_tree.dna_file_tree.file_tree(), None)?;
+    let app_file_tree = AppFileTree::get_or_choose(
+        zome_file_tree.dna_file_tree.file_tree(),
+        None,
+        no_interactive,
+    )?;
 
     let app_name = app_file_tree.app_manifest.app_name().to_string();
 
+    let has_foreign_entry_type =
+        from_foreign_entry_type.is_some() || to_foreign_entry_type.is_some();
+    if has_foreign_entry_type && !(no_ui && no_spec) {
+        let warning_text = r#"
+WARNING: No UI components or tests are scaffolded for link types to or from the entry types of other integrity zomes
+or DNAs, since those entry types have their UI components and tests in the folders of their own zomes."#
+            .yellow();
+        println!("{warning_text}");
+    }
+
     scaffold_link_type_templates(
         app_file_tree.file_tree(),
         template_file_tree,
@@ -197,11 +379,156 @@ pub use {}::*;
         &to_referenceable,
         delete,
         inverse_link_type.as_deref(),
-        no_ui,
-        no_spec,
+        tag_type,
+        // The UI components and the tests for the entry types of other zomes or DNAs live elsewhere
+        no_ui || has_foreign_entry_type,
+        no_spec || has_foreign_entry_type,
     )
 }
 
+/// The path from the root of the integrity zome crate of the struct with the given name, if the zome defines it
+fn find_tag_type_path(
+    zome_file_tree: &ZomeFileTree,
+    tag_type: &str,
+) -> ScaffoldResult<Option<syn::Path>> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+    let files = find_map_rust_files(
+        zome_file_tree
+            .dna_file_tree
+            .file_tree_ref()
+            .path(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        &|_path, file| {
+            file.items.iter().find_map(|i| match i {
+                syn::Item::Struct(item_struct) if item_struct.ident == tag_type => Some(()),
+                _ => None,
+            })
+        },
+    );
+
+    Ok(files.into_keys().next().map(|file_path| {
+        let module = module_path(&file_path);
+        let ident = format_ident!("{tag_type}");
+        syn::parse_quote! { #(#module::)*#ident }
+    }))
+}
+
+/// The integrity zome where the entry types for one end of a link type are looked up
+fn located_integrity_zome(
+    zome_file_tree: &ZomeFileTree,
+    location: &EntryTypeLocation,
+    no_interactive: bool,
+) -> ScaffoldResult<ZomeFileTree> {
+    if location.is_local() {
+        return ZomeFileTree::from_zome_manifest(
+            zome_file_tree.dna_file_tree.clone(),
+            zome_file_tree.zome_manifest.clone(),
+        );
+    }
+
+    let dna_file_tree = match &location.dna {
+        Some(dna) => DnaFileTree::get_or_choose(
+            zome_file_tree.dna_file_tree.file_tree_ref().clone(),
+            Some(dna),
+            no_interactive,
+        )?,
+        None => zome_file_tree.dna_file_tree.clone(),
+    };
+
+    ZomeFileTree::get_or_choose_integrity(dna_file_tree, location.zome.as_deref(), no_interactive)
+}
+
+/// Where the referenced entry type is defined, if it's not in the integrity zome of the link type
+fn foreign_entry_type(
+    zome_file_tree: &ZomeFileTree,
+    located_zome_file_tree: &ZomeFileTree,
+    referenceable: Option<&Referenceable>,
+    entry_types: &[EntryTypeReference],
+    no_interactive: bool,
+) -> ScaffoldResult<Option<ForeignEntryType>> {
+    let same_dna = located_zome_file_tree.dna_file_tree.dna_manifest_path
+        == zome_file_tree.dna_file_tree.dna_manifest_path;
+    let zome_name = located_zome_file_tree.zome_manifest.name.to_string();
+
+    if same_dna && zome_name == zome_file_tree.zome_manifest.name.to_string() {
+        return Ok(None);
+    }
+
+    let Some(Referenceable::EntryType(entry_type)) = referenceable else {
+        return Err(ScaffoldError::InvalidArguments(String::from(
+            "only entry types can be defined in another zome or DNA than the link type",
+        )));
+    };
+
+    if same_dna {
+        if !entry_types
+            .iter()
+            .any(|e| e.entry_type == entry_type.entry_type)
+        {
+            return Err(ScaffoldError::EntryTypeNotFound(
+                entry_type.entry_type.clone(),
+                located_zome_file_tree.dna_file_tree.dna_manifest.name(),
+                zome_name,
+            ));
+        }
+        return Ok(Some(ForeignEntryType::Zome { zome: zome_name }));
+    }
+
+    let dna_name = located_zome_file_tree.dna_file_tree.dna_manifest.name();
+    let file_tree = zome_file_tree.dna_file_tree.file_tree_ref();
+    let app_manifest_path = get_or_choose_app_manifest_path_for_dna_manifest(
+        file_tree,
+        &zome_file_tree.dna_file_tree.dna_manifest_path,
+        no_interactive,
+    )?;
+    let role = role_for_dna(
+        file_tree,
+        &app_manifest_path,
+        &located_zome_file_tree.dna_file_tree.dna_manifest_path,
+    )?
+    .ok_or_else(|| {
+        ScaffoldError::InvalidArguments(format!(
+            "the {dna_name} DNA is not installed by any role of the app at {}",
+            app_manifest_path.display()
+        ))
+    })?;
+
+    let coordinator_zomes = get_coordinator_zomes_for_integrity(
+        &located_zome_file_tree.dna_file_tree.dna_manifest,
+        &zome_name,
+    );
+
+    let snake_entry_type = entry_type.entry_type.to_case(Case::Snake);
+    let get_fns = match entry_type.reference_entry_hash {
+        true => vec![format!("get_{snake_entry_type}")],
+        false => vec![
+            format!("get_original_{snake_entry_type}"),
+            format!("get_{snake_entry_type}"),
+        ],
+    };
+
+    for get_fn in get_fns {
+        if let Some((coordinator_zome, _)) = find_extern_function_in_zomes(
+            &located_zome_file_tree.dna_file_tree,
+            &coordinator_zomes,
+            &get_fn,
+        )? {
+            return Ok(Some(ForeignEntryType::Dna {
+                role,
+                coordinator_zome: coordinator_zome.name.to_string(),
+                get_fn,
+            }));
+        }
+    }
+
+    Err(ScaffoldError::InvalidArguments(format!(
+        "no coordinator zome of the {dna_name} DNA has a function to get the {} entries",
+        entry_type.entry_type
+    )))
+}
+
 pub fn link_type_name(
     from_referenceable: &Referenceable,
     to_referenceable: &Referenceable,
@@ -222,3 +549,85 @@ pub fn link_type_name(
         .to_case(Case::Pascal),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, str::FromStr};
+
+    use build_fs_tree::{dir, file};
+    use holochain_types::prelude::ZomeManifest;
+    use mr_bundle::Location;
+
+    use super::*;
+    use crate::{file_tree::file_content, scaffold::web_app::template_type::TemplateType};
+
+    #[test]
+    fn test_tag_type_templates() {
+        let tag_type = FieldType::parse_struct("Weight{value:u32,note:String}").unwrap();
+        let zome_manifest = ZomeManifest {
+            name: "posts".into(),
+            hash: None,
+            location: Location::Bundled(PathBuf::from("posts.wasm")),
+            dependencies: None,
+            dylib: None,
+        };
+        let app_file_tree: FileTree = dir! {
+            "ui" => dir! {
+                "src" => dir! {
+                    "forum" => dir! {
+                        "posts" => dir! {
+                            "types.ts" => file!("import { ActionHash } from \"@holochain/client\";\n\nexport type PostsSignal = {\n  type: \"LinkDeleted\";\n};\n")
+                        }
+                    }
+                }
+            }
+        };
+
+        let scaffolded = scaffold_link_type_templates(
+            app_file_tree,
+            &TemplateType::Svelte.file_tree().unwrap(),
+            "forum",
+            "forum",
+            &zome_manifest,
+            "PostToComments",
+            &Referenceable::from_str("post").unwrap(),
+            &Some(Referenceable::from_str("comment").unwrap()),
+            false,
+            None,
+            Some(&tag_type),
+            false,
+            false,
+        )
+        .unwrap();
+
+        let types_ts = file_content(
+            &scaffolded.file_tree,
+            Path::new("ui/src/forum/posts/types.ts"),
+        )
+        .unwrap();
+        assert!(
+            types_ts.contains("export interface Weight {\n  value: number;\n  note: string;\n}")
+        );
+
+        let component = file_content(
+            &scaffolded.file_tree,
+            Path::new("ui/src/forum/posts/CommentsForPost.svelte"),
+        )
+        .unwrap();
+        assert!(component.contains("import type { PostsSignal, Weight } from \"./types\";"));
+        assert!(component.contains("Array<[Link, Weight]>"));
+
+        let test = file_content(
+            &scaffolded.file_tree,
+            Path::new("tests/src/forum/posts/post-to-comments.test.ts"),
+        )
+        .unwrap();
+        assert!(test.contains(
+            "const tag: Weight = { value: 10, note: \"Lorem ipsum dolor sit amet, consectetur adipiscing elit.\" };"
+        ));
+        assert!(test.contains(
+            "import { createComment } from \"./common.js\";\n\nexport interface Weight {\n  value: number;\n  note: string;\n}\n\ntest("
+        ));
+        assert!(test.contains("let linksOutput: Array<[Link, Weight]>"));
+    }
+}
@@ -1,5 +1,5 @@
 use convert_case::{Case, Casing};
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
 
 use crate::{
@@ -8,13 +8,18 @@ use crate::{
     scaffold::{
         dna::DnaFileTree,
         entry_type::definitions::{Cardinality, EntryTypeReference, Referenceable},
-        zome::ZomeFileTree,
+        zome::{
+            coordinator::{find_extern_function_in_zome, remove_signals_from_coordinator},
+            utils::remove_module_from_crate,
+            ZomeFileTree,
+        },
     },
     utils::unparse_pretty,
 };
 
-use super::link_type_name;
+use super::{link_type_name, ForeignEntryType};
 
+#[allow(clippy::too_many_arguments)]
 pub fn add_link_type_functions_to_coordinator(
     coordinator_zome_file_tree: ZomeFileTree,
     integrity_zome_name: &str,
@@ -23,7 +28,43 @@ pub fn add_link_type_functions_to_coordinator(
     to_referenceable: &Option<Referenceable>,
     delete: bool,
     bidirectional: bool,
+    tag_type: Option<&syn::Path>,
+    from_foreign_entry_type: &Option<ForeignEntryType>,
+    to_foreign_entry_type: &Option<ForeignEntryType>,
 ) -> ScaffoldResult<ZomeFileTree> {
+    // Entry types of other DNAs are fetched through a call to their own coordinator zome
+    let mut bridge_getter_names: Vec<String> = Vec::new();
+    let mut bridge_getters: Vec<TokenStream> = Vec::new();
+    let foreign_ends = [
+        (Some(from_referenceable), from_foreign_entry_type),
+        (to_referenceable.as_ref(), to_foreign_entry_type),
+    ];
+    for (referenceable, foreign_entry_type) in foreign_ends {
+        if let (
+            Some(Referenceable::EntryType(entry_type)),
+            Some(ForeignEntryType::Dna {
+                role,
+                coordinator_zome,
+                get_fn,
+            }),
+        ) = (referenceable, foreign_entry_type)
+        {
+            let function_name = bridge_getter_name(entry_type, role);
+            if !bridge_getter_names.contains(&function_name)
+                && find_extern_function_in_zome(&coordinator_zome_file_tree, &function_name)?
+                    .is_none()
+            {
+                bridge_getter_names.push(function_name);
+                bridge_getters.push(bridge_getter_handler(
+                    entry_type,
+                    role,
+                    coordinator_zome,
+                    get_fn,
+                ));
+            }
+        }
+    }
+
     let dna_manifest_path = coordinator_zome_file_tree
         .dna_file_tree
         .dna_manifest_path
@@ -47,11 +88,21 @@ pub fn add_link_type_functions_to_coordinator(
             r,
             delete,
             bidirectional,
+            tag_type,
+        ),
+        None => metadata_handlers(
+            integrity_zome_name,
+            link_type_name,
+            from_referenceable,
+            tag_type,
         ),
-        None => metadata_handlers(integrity_zome_name, link_type_name, from_referenceable),
     };
 
-    let file = unparse_pretty(&syn::parse_quote! { #link_type_handlers_file });
+    let file = unparse_pretty(&syn::parse_quote! {
+        #link_type_handlers_file
+
+        #(#bridge_getters)*
+    });
 
     // insert handlers file
     insert_file(&mut file_tree, &new_file_path, &file)?;
@@ -67,15 +118,107 @@ pub fn add_link_type_functions_to_coordinator(
     Ok(zome_file_tree)
 }
 
+/// Removes the files with the functions for the given link types from the coordinator zome,
+/// along with the link signals if there are no link types left in its integrity zome
+pub fn remove_link_type_functions_from_coordinator(
+    coordinator_zome_file_tree: ZomeFileTree,
+    link_types: &[String],
+    no_link_types_left: bool,
+) -> ScaffoldResult<ZomeFileTree> {
+    let dna_manifest_path = coordinator_zome_file_tree
+        .dna_file_tree
+        .dna_manifest_path
+        .clone();
+    let zome_manifest = coordinator_zome_file_tree.zome_manifest.clone();
+    let crate_src_path = coordinator_zome_file_tree.zome_crate_path.join("src");
+
+    let mut file_tree = coordinator_zome_file_tree.dna_file_tree.file_tree();
+
+    for link_type in link_types {
+        remove_module_from_crate(
+            &mut file_tree,
+            &crate_src_path,
+            &link_type.to_case(Case::Snake),
+        )?;
+    }
+
+    if no_link_types_left {
+        remove_signals_from_coordinator(
+            &mut file_tree,
+            &crate_src_path,
+            &["LinkCreated", "LinkDeleted"],
+            &["CreateLink", "DeleteLink"],
+            &[],
+        )?;
+    }
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)?;
+
+    Ok(zome_file_tree)
+}
+
+fn bridge_getter_name(entry_type: &EntryTypeReference, role: &str) -> String {
+    format!(
+        "get_{}_from_{}",
+        entry_type.entry_type.to_case(Case::Snake),
+        role.to_case(Case::Snake)
+    )
+}
+
+/// The handler getting a record of an entry type of another DNA, by calling its getter in the cell of that role
+fn bridge_getter_handler(
+    entry_type: &EntryTypeReference,
+    role: &str,
+    coordinator_zome: &str,
+    get_fn: &str,
+) -> TokenStream {
+    let function_name = format_ident!("{}", bridge_getter_name(entry_type, role));
+    let hash_param = format_ident!("{}", entry_type.field_name(&Cardinality::Single));
+    let hash_type = format_ident!("{}", entry_type.field_type().to_string());
+    let error_message = format!(
+        "Failed to get the {} from the {role} role",
+        entry_type.entry_type.to_case(Case::Pascal)
+    );
+
+    quote! {
+        #[hdk_extern]
+        pub fn #function_name(#hash_param: #hash_type) -> ExternResult<Option<Record>> {
+            let response = call(
+                CallTargetCell::OtherRole(String::from(#role)),
+                ZomeName::from(#coordinator_zome),
+                FunctionName::from(#get_fn),
+                None,
+                #hash_param,
+            )?;
+            match response {
+                ZomeCallResponse::Ok(result) => result.decode().map_err(|e| wasm_error!(e)),
+                _ => Err(wasm_error!(WasmErrorInner::Guest(format!("{}: {:?}", #error_message, response)))),
+            }
+        }
+    }
+}
+
 fn normal_handlers(
     integrity_zome_name: &str,
     from_referenceable: &Referenceable,
     to_referenceable: &Referenceable,
     delete: bool,
     bidirectional: bool,
+    tag_type: Option<&syn::Path>,
 ) -> TokenStream {
     let inverse_get_handler = bidirectional
-        .then(|| get_links_handler(to_referenceable, from_referenceable, delete))
+        .then(|| {
+            let get_links_handler =
+                get_links_handler(to_referenceable, from_referenceable, delete, tag_type);
+            let count_and_has_links_handlers =
+                count_and_has_links_handlers(to_referenceable, from_referenceable);
+            quote! {
+                #get_links_handler
+
+                #count_and_has_links_handlers
+            }
+        })
         .unwrap_or_default();
 
     let delete_link_handler = delete
@@ -83,8 +226,16 @@ fn normal_handlers(
         .unwrap_or_default();
 
     let integrity_zome_name = format_ident!("{integrity_zome_name}");
-    let add_links_handler = add_link_handler(from_referenceable, to_referenceable, bidirectional);
-    let get_links_handler = get_links_handler(from_referenceable, to_referenceable, delete);
+    let add_links_handler = add_link_handler(
+        from_referenceable,
+        to_referenceable,
+        bidirectional,
+        tag_type,
+    );
+    let get_links_handler =
+        get_links_handler(from_referenceable, to_referenceable, delete, tag_type);
+    let count_and_has_links_handlers =
+        count_and_has_links_handlers(from_referenceable, to_referenceable);
 
     quote! {
         use hdk::prelude::*;
@@ -94,6 +245,8 @@ fn normal_handlers(
 
         #get_links_handler
 
+        #count_and_has_links_handlers
+
         #inverse_get_handler
 
         #delete_link_handler
@@ -104,6 +257,7 @@ fn metadata_handlers(
     integrity_zome_name: &str,
     link_type_name: &str,
     from_referenceable: &Referenceable,
+    tag_type: Option<&syn::Path>,
 ) -> TokenStream {
     let integrity_zome_name = format_ident!("{}", integrity_zome_name);
     let snake_from_arg = format_ident!(
@@ -137,6 +291,45 @@ fn metadata_handlers(
     let get_link_type_function_name =
         format_ident!("get_{plural_snake_link_type_name}_for_{snake_from}");
 
+    if let Some(tag_type) = tag_type {
+        return quote! {
+            use hdk::prelude::*;
+            use #integrity_zome_name::*;
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct #create_link_input_struct_name {
+                pub #snake_from_arg: #from_field_type,
+                pub #snake_link_type_name: #tag_type,
+            }
+
+            #[hdk_extern]
+            pub fn #create_link_function_name(input: #create_link_input_struct_name) -> ExternResult<()> {
+                let tag = SerializedBytes::try_from(input.#snake_link_type_name).map_err(|e| wasm_error!(e))?;
+                create_link(
+                    input.#snake_from_arg.clone(),
+                    input.#snake_from_arg,
+                    LinkTypes::#pascal_link_type_name,
+                    LinkTag::new(tag.bytes().clone()),
+                )?;
+                Ok(())
+            }
+
+            #[hdk_extern]
+            pub fn #get_link_type_function_name(#snake_from_arg: #from_field_type) -> ExternResult<Vec<#tag_type>> {
+                let links = get_links(
+                    GetLinksInputBuilder::try_new(#snake_from_arg, LinkTypes::#pascal_link_type_name)?.build(),
+                )?;
+                links
+                    .into_iter()
+                    .map(|link|
+                        #tag_type::try_from(SerializedBytes::from(UnsafeBytes::from(link.tag.into_inner())))
+                            .map_err(|e| wasm_error!(e))
+                    )
+                    .collect()
+            }
+        };
+    }
+
     quote! {
           use hdk::prelude::*;
           use #integrity_zome_name::*;
@@ -179,6 +372,7 @@ pub fn add_link_handler(
     from_referenceable: &Referenceable,
     to_referenceable: &Referenceable,
     bidirectional: bool,
+    tag_type: Option<&syn::Path>,
 ) -> TokenStream {
     let from_field_type = format_ident!("{}", from_referenceable.field_type().to_string());
     let to_field_type = format_ident!("{}", to_referenceable.field_type().to_string());
@@ -214,6 +408,17 @@ pub fn add_link_handler(
     let inverse_link_type_name =
         format_ident!("{}", link_type_name(to_referenceable, from_referenceable));
 
+    let (tag_field, serialize_tag, tag) = match tag_type {
+        Some(tag_type) => (
+            quote! { pub tag: #tag_type, },
+            quote! {
+                let tag = SerializedBytes::try_from(input.tag).map_err(|e| wasm_error!(e))?;
+            },
+            quote! { LinkTag::new(tag.bytes().clone()) },
+        ),
+        None => (quote! {}, quote! {}, quote! { () }),
+    };
+
     let bidirectional_create = bidirectional
         .then(|| {
             quote! {
@@ -221,7 +426,7 @@ pub fn add_link_handler(
                     input.#target_field_name,
                     input.#base_field_name,
                     LinkTypes::#inverse_link_type_name,
-                    (),
+                    #tag,
                 )?;
             }
         })
@@ -232,15 +437,17 @@ pub fn add_link_handler(
         pub struct #add_link_input_struct_name {
             pub #base_field_name: #from_field_type,
             pub #target_field_name: #to_field_type,
+            #tag_field
         }
 
         #[hdk_extern]
         pub fn #add_link_function_name(input: #add_link_input_struct_name) -> ExternResult<()> {
+            #serialize_tag
             create_link(
                 input.#base_field_name.clone(),
                 input.#target_field_name.clone(),
                 LinkTypes::#normal_link_type_name,
-                (),
+                #tag,
             )?;
             #bidirectional_create
             Ok(())
@@ -252,15 +459,119 @@ pub fn get_links_handler(
     from_referenceable: &Referenceable,
     to_referenceable: &Referenceable,
     delete: bool,
+    tag_type: Option<&syn::Path>,
 ) -> TokenStream {
     match to_referenceable {
         Referenceable::Agent { .. } => {
-            get_links_to_agent_handler(from_referenceable, to_referenceable, delete)
+            get_links_to_agent_handler(from_referenceable, to_referenceable, delete, tag_type)
+        }
+        Referenceable::ExternalHash { .. } => get_links_to_any_linkable_hash_handler(
+            from_referenceable,
+            to_referenceable,
+            delete,
+            tag_type,
+        ),
+        Referenceable::EntryType(e) => {
+            get_links_to_entry_handler(from_referenceable, e, delete, tag_type)
+        }
+        Referenceable::Dna { .. } => unreachable!("DnaHashes can't be the target of links"),
+    }
+}
+
+/// The handler returning the links of the given link type, along with their decoded tags if they are typed
+fn get_links_fn(
+    function_name: &Ident,
+    from_referenceable: &Referenceable,
+    pascal_link_type_name: &Ident,
+    tag_type: Option<&syn::Path>,
+) -> TokenStream {
+    let from_field_type = format_ident!("{}", from_referenceable.field_type().to_string());
+    let from_arg_name = format_ident!("{}", from_referenceable.field_name(&Cardinality::Single));
+    let base = from_referenceable.link_base(quote!(#from_arg_name));
+
+    match tag_type {
+        Some(tag_type) => {
+            quote! {
+                #[hdk_extern]
+                pub fn #function_name(#from_arg_name: #from_field_type) -> ExternResult<Vec<(Link, #tag_type)>> {
+                    let links = get_links(
+                        GetLinksInputBuilder::try_new(#base, LinkTypes::#pascal_link_type_name)?.build(),
+                    )?;
+                    links
+                        .into_iter()
+                        .map(|link| {
+                            let tag = #tag_type::try_from(SerializedBytes::from(UnsafeBytes::from(link.tag.clone().into_inner())))
+                                .map_err(|e| wasm_error!(e))?;
+                            Ok((link, tag))
+                        })
+                        .collect()
+                }
+            }
         }
-        Referenceable::ExternalHash { .. } => {
-            get_links_to_any_linkable_hash_handler(from_referenceable, to_referenceable, delete)
+        None => quote! {
+            #[hdk_extern]
+            pub fn #function_name(#from_arg_name: #from_field_type) -> ExternResult<Vec<Link>> {
+                get_links(
+                    GetLinksInputBuilder::try_new(#base, LinkTypes::#pascal_link_type_name)?.build(),
+                )
+            }
+        },
+    }
+}
+
+/// Handlers returning the number of links from a base, and whether there is a link from a base to a given target,
+/// so that callers don't need to fetch the whole set of links
+fn count_and_has_links_handlers(
+    from_referenceable: &Referenceable,
+    to_referenceable: &Referenceable,
+) -> TokenStream {
+    let from_field_type = format_ident!("{}", from_referenceable.field_type().to_string());
+    let to_field_type = format_ident!("{}", to_referenceable.field_type().to_string());
+    let from_arg_name = format_ident!("{}", from_referenceable.field_name(&Cardinality::Single));
+    let base_field_name = format_ident!(
+        "base_{}",
+        from_referenceable.field_name(&Cardinality::Single)
+    );
+    let target_field_name = format_ident!(
+        "target_{}",
+        to_referenceable.field_name(&Cardinality::Single)
+    );
+
+    let link_type_name = link_type_name(from_referenceable, to_referenceable);
+    let count_links_function_name = format_ident!("count_{}", link_type_name.to_case(Case::Snake));
+    let has_link_function_name = format_ident!("has_{}", link_type_name.to_case(Case::Snake));
+    let has_link_input_struct_name = format_ident!("Has{link_type_name}Input");
+    let pascal_link_type_name = format_ident!("{link_type_name}");
+
+    let target_hash_type_code = hash_type_code_from_referenceable(to_referenceable);
+
+    quote! {
+        #[hdk_extern]
+        pub fn #count_links_function_name(#from_arg_name: #from_field_type) -> ExternResult<usize> {
+            count_links(LinkQuery::new(
+                #from_arg_name,
+                LinkTypes::#pascal_link_type_name.try_into_filter()?,
+            ))
+        }
+
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct #has_link_input_struct_name {
+            pub #base_field_name: #from_field_type,
+            pub #target_field_name: #to_field_type,
+        }
+
+        #[hdk_extern]
+        pub fn #has_link_function_name(input: #has_link_input_struct_name) -> ExternResult<bool> {
+            let links = get_links(
+                GetLinksInputBuilder::try_new(input.#base_field_name.clone(), LinkTypes::#pascal_link_type_name)?.build(),
+            )?;
+            for link in links {
+                if #target_hash_type_code == input.#target_field_name.clone().into_hash().into() {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
         }
-        Referenceable::EntryType(e) => get_links_to_entry_handler(from_referenceable, e, delete),
     }
 }
 
@@ -268,6 +579,7 @@ fn get_links_to_agent_handler(
     from_referenceable: &Referenceable,
     to_referenceable: &Referenceable,
     delete: bool,
+    tag_type: Option<&syn::Path>,
 ) -> TokenStream {
     let from_field_type = format_ident!("{}", from_referenceable.field_type().to_string());
     let from_arg_name = format_ident!("{}", from_referenceable.field_name(&Cardinality::Single));
@@ -317,13 +629,15 @@ fn get_links_to_agent_handler(
     let get_entry_for_entry_function_name =
         format_ident!("get_{plural_snake_to_entry_type}_for_{singular_snake_from_entry_type}");
 
+    let get_links_handler = get_links_fn(
+        &get_entry_for_entry_function_name,
+        from_referenceable,
+        &pascal_link_type_name,
+        tag_type,
+    );
+
     quote::quote! {
-        #[hdk_extern]
-        pub fn #get_entry_for_entry_function_name(#from_arg_name: #from_field_type) -> ExternResult<Vec<Link>> {
-            get_links(
-                GetLinksInputBuilder::try_new(#from_arg_name, LinkTypes::#pascal_link_type_name)?.build(),
-            )
-        }
+        #get_links_handler
 
         #get_deleted_links_handler
     }
@@ -333,9 +647,11 @@ fn get_links_to_entry_handler(
     from_referenceable: &Referenceable,
     to_entry_type: &EntryTypeReference,
     delete: bool,
+    tag_type: Option<&syn::Path>,
 ) -> TokenStream {
     let from_field_type = format_ident!("{}", from_referenceable.field_type().to_string());
     let from_arg_name = format_ident!("{}", from_referenceable.field_name(&Cardinality::Single));
+    let base = from_referenceable.link_base(quote!(#from_arg_name));
 
     let pascal_link_type_name = format_ident!(
         "{}",
@@ -369,7 +685,7 @@ fn get_links_to_entry_handler(
                     #from_arg_name: #from_field_type,
                 ) -> ExternResult<Vec<(SignedActionHashed, Vec<SignedActionHashed>)>> {
                     let details = get_link_details(
-                        #from_arg_name,
+                        #base,
                         LinkTypes::#pascal_link_type_name,
                         None,
                         GetOptions::default(),
@@ -387,13 +703,15 @@ fn get_links_to_entry_handler(
     let get_entry_for_entry_function_name =
         format_ident!("get_{plural_snake_to_entry_type}_for_{singular_snake_from_entry_type}");
 
+    let get_links_handler = get_links_fn(
+        &get_entry_for_entry_function_name,
+        from_referenceable,
+        &pascal_link_type_name,
+        tag_type,
+    );
+
     quote::quote! {
-        #[hdk_extern]
-        pub fn #get_entry_for_entry_function_name(#from_arg_name: #from_field_type) -> ExternResult<Vec<Link>> {
-            get_links(
-                GetLinksInputBuilder::try_new(#from_arg_name, LinkTypes::#pascal_link_type_name)?.build(),
-            )
-        }
+        #get_links_handler
 
         #get_deleted_links_handler
     }
@@ -403,6 +721,7 @@ fn get_links_to_any_linkable_hash_handler(
     from_referenceable: &Referenceable,
     to_referenceable: &Referenceable,
     deletable: bool,
+    tag_type: Option<&syn::Path>,
 ) -> TokenStream {
     let from_field_type = format_ident!("{}", from_referenceable.field_type().to_string());
     let from_arg_name = format_ident!("{}", from_referenceable.field_name(&Cardinality::Single));
@@ -452,13 +771,15 @@ fn get_links_to_any_linkable_hash_handler(
     let get_entry_for_entry_function_name =
         format_ident!("get_{plural_snake_to_entry_type}_for_{singular_snake_from_entry_type}");
 
+    let get_links_handler = get_links_fn(
+        &get_entry_for_entry_function_name,
+        from_referenceable,
+        &pascal_link_type_name,
+        tag_type,
+    );
+
     quote::quote! {
-        #[hdk_extern]
-        pub fn #get_entry_for_entry_function_name(#from_arg_name: #from_field_type) -> ExternResult<Vec<Link>> {
-            get_links(
-                GetLinksInputBuilder::try_new(#from_arg_name, LinkTypes::#pascal_link_type_name)?.build(),
-            )
-        }
+        #get_links_handler
 
         #get_deleted_links_handler
     }
@@ -560,6 +881,7 @@ fn hash_type_code_from_referenceable(referenceable: &Referenceable) -> TokenStre
         Referenceable::ExternalHash { .. } => quote! {
             link.target.clone().into_hash()
         },
+        Referenceable::Dna { .. } => unreachable!("DnaHashes can't be the target of links"),
         Referenceable::EntryType(_) => {
             let field_type = referenceable.field_type().to_string();
             let into_hash_method_name = format_ident!("into_{}", field_type.to_case(Case::Snake));
@@ -10,23 +10,34 @@ use quote::{format_ident, quote};
 
 use crate::{
     error::{ScaffoldError, ScaffoldResult},
-    file_tree::{find_map_rust_files, map_rust_files, FileTree},
+    file_tree::{file_content, find_map_rust_files, map_rust_files, FileTree},
     scaffold::{
         dna::DnaFileTree,
         entry_type::{
-            definitions::Referenceable,
-            integrity::{find_ending_match_expr, find_ending_match_expr_in_block},
+            crud::Policy,
+            definitions::{EntryTypeReference, Referenceable},
+            integrity::{
+                find_ending_match_expr, find_ending_match_expr_in_block, find_flattened_type_args,
+                is_enum_with_attribute, remove_arms_from_validation,
+            },
         },
         zome::{utils::get_coordinator_zomes_for_integrity, ZomeFileTree},
     },
 };
 
+use super::{ForeignEntryType, LinkValidation};
+
+#[allow(clippy::too_many_arguments)]
 pub fn add_link_type_to_integrity_zome(
     zome_file_tree: ZomeFileTree,
     link_type_name: &str,
     from_referenceable: &Option<Referenceable>,
     to_referenceable: &Option<Referenceable>,
     delete: bool,
+    tag_type: Option<&syn::Path>,
+    validation: &LinkValidation,
+    from_foreign_entry_type: &Option<ForeignEntryType>,
+    to_foreign_entry_type: &Option<ForeignEntryType>,
     file_to_add_validation_to: &Path,
 ) -> ScaffoldResult<ZomeFileTree> {
     let crate_src_path = zome_file_tree.zome_crate_path.join("src");
@@ -190,73 +201,160 @@ pub fn add_link_type_to_integrity_zome(
                     link_type_name.to_case(Case::Snake)
                 );
 
-                let validate_delete_fn = format_ident!(
-                    "validate_delete_link_{}",
-                    link_type_name.to_case(Case::Snake)
-                );
-
-                let deleted_invalid_reason = format!(
-                    "{} links cannot be deleted",
-                    link_type_name.to_case(Case::Pascal)
-                );
-
-                let validate_delete_result: TokenStream = if delete {
-                    quote! {
-                        // TODO: add the appropriate validation rules
-                        Ok(ValidateCallbackResult::Valid)
-                    }
-                } else {
-                    quote! {
-                        Ok(ValidateCallbackResult::Invalid(#deleted_invalid_reason.to_string()))
-                    }
-                };
-
                 let base_address_ident = match from_referenceable {
                     Some(Referenceable::EntryType(_)) => format_ident!("base_address"),
                     _ => format_ident!("_base_address"),
                 };
 
-                let validate_create_from = from_referenceable
-                    .as_ref()
-                    .map(|r| validate_referenceable(r, &base_address_ident));
+                let validate_create_from = from_referenceable.as_ref().map(|r| {
+                    validate_referenceable(
+                        r,
+                        from_foreign_entry_type.as_ref(),
+                        &base_address_ident,
+                        link_type_name,
+                    )
+                });
+
+                let tagged_targets = !validation.tagged_target_entry_types.is_empty();
 
                 let target_address_ident = match to_referenceable {
                     Some(Referenceable::EntryType(_)) => format_ident!("target_address"),
+                    _ if tagged_targets => format_ident!("target_address"),
                     _ => format_ident!("_target_address"),
                 };
 
-                let validate_create_to = to_referenceable
-                    .as_ref()
-                    .map(|r| validate_referenceable(r, &target_address_ident));
+                let tag_ident = match tag_type.is_some() || tagged_targets {
+                    true => format_ident!("tag"),
+                    false => format_ident!("_tag"),
+                };
+
+                let validate_create_to = match to_referenceable {
+                    Some(r) => Some(validate_referenceable(
+                        r,
+                        to_foreign_entry_type.as_ref(),
+                        &target_address_ident,
+                        link_type_name,
+                    )),
+                    None if tagged_targets => Some(validate_tagged_target(
+                        &validation.tagged_target_entry_types,
+                        &target_address_ident,
+                        &tag_ident,
+                        link_type_name,
+                    )),
+                    None => None,
+                };
+
+                let validate_create_tag = tag_type.map(|tag_type| {
+                    let invalid_tag_reason = format!(
+                        "The tag of a {} link must be a valid {}",
+                        link_type_name.to_case(Case::Pascal),
+                        tag_type.segments.last().map(|s| s.ident.to_string()).unwrap_or_default()
+                    );
+                    quote! {
+                        if crate::#tag_type::try_from(SerializedBytes::from(UnsafeBytes::from(#tag_ident.into_inner()))).is_err() {
+                            return Ok(ValidateCallbackResult::Invalid(#invalid_tag_reason.to_string()));
+                        }
+                    }
+                });
+
+                let validate_create_author = validation.base_is_author.then(|| {
+                    let invalid_reason = format!(
+                        "The base of a {} link must be the agent that creates it",
+                        link_type_name.to_case(Case::Pascal)
+                    );
+                    quote! {
+                        if action.base_address.clone().into_agent_pub_key() != Some(action.author.clone()) {
+                            return Ok(ValidateCallbackResult::Invalid(#invalid_reason.to_string()));
+                        }
+                    }
+                });
+
+                let validate_create_unique = validation.unique.then(|| {
+                    let invalid_reason = format!(
+                        "There already is a {} link from this base to this target",
+                        link_type_name.to_case(Case::Pascal)
+                    );
+                    quote! {
+                        let activity = must_get_agent_activity(
+                            action.author.clone(),
+                            ChainFilter::new(action.prev_action.clone()),
+                        )?;
+                        let mut existing_links: Vec<ActionHash> = vec![];
+                        let mut deleted_links: Vec<ActionHash> = vec![];
+                        for registered_activity in activity {
+                            match registered_activity.action.hashed.content {
+                                Action::CreateLink(create_link)
+                                    if create_link.base_address == action.base_address
+                                        && create_link.target_address == action.target_address
+                                        && create_link.zome_index == action.zome_index
+                                        && create_link.link_type == action.link_type =>
+                                {
+                                    existing_links.push(registered_activity.action.hashed.hash);
+                                }
+                                Action::DeleteLink(delete_link) => {
+                                    deleted_links.push(delete_link.link_add_address);
+                                }
+                                _ => {}
+                            }
+                        }
+                        if existing_links.iter().any(|hash| !deleted_links.contains(hash)) {
+                            return Ok(ValidateCallbackResult::Invalid(#invalid_reason.to_string()));
+                        }
+                    }
+                });
+
+                let create_action_ident = match validation.base_is_author || validation.unique {
+                    true => format_ident!("action"),
+                    false => format_ident!("_action"),
+                };
+
+                let unique_doc = validation.unique.then(|| {
+                    quote! {
+                        /// Each agent can only create one link from a given base to a given target until it's deleted.
+                        /// The links of other agents can't be fetched deterministically in validation, so the existing
+                        /// links are only looked up in the source chain of the author: the same link from another agent is valid
+                    }
+                });
+
+                let has_validation_rules = validate_create_from.is_some()
+                    || validate_create_to.is_some()
+                    || validate_create_tag.is_some()
+                    || validate_create_author.is_some()
+                    || validate_create_unique.is_some();
+
+                let create_result = match has_validation_rules {
+                    true => quote! { Ok(ValidateCallbackResult::Valid) },
+                    false => quote! {
+                        /// TODO: add the appropriate validation rules
+                        Ok(ValidateCallbackResult::Valid)
+                    },
+                };
 
                 let create_token_stream = quote! {
+                    #unique_doc
                     pub fn #validate_create_fn(
-                        _action: CreateLink,
+                        #create_action_ident: CreateLink,
                         #base_address_ident: AnyLinkableHash,
                         #target_address_ident: AnyLinkableHash,
-                        _tag: LinkTag,
+                        #tag_ident: LinkTag,
                     ) -> ExternResult<ValidateCallbackResult> {
                         #validate_create_from
 
                         #validate_create_to
 
-                        // TODO: add the appropriate validation rules
-                        Ok(ValidateCallbackResult::Valid)
-                  }
-                };
+                        #validate_create_tag
 
-                let delete_token_stream = quote! {
-                    pub fn #validate_delete_fn(
-                        _action: DeleteLink,
-                        _original_action: CreateLink,
-                        _base: AnyLinkableHash,
-                        _target: AnyLinkableHash,
-                        _tag: LinkTag
-                    ) -> ExternResult<ValidateCallbackResult> {
-                        #validate_delete_result
+                        #validate_create_author
+
+                        #validate_create_unique
+
+                        #create_result
                   }
                 };
 
+                let delete_token_stream =
+                    validate_delete_link_handler(link_type_name, delete, validation.delete_policy);
+
                 let item: syn::Item = syn::parse_str(create_token_stream.to_string().as_str())?;
                 file.items.push(item);
                 let item: syn::Item = syn::parse_str(delete_token_stream.to_string().as_str())?;
@@ -293,38 +391,348 @@ pub fn add_link_type_to_integrity_zome(
     Ok(zome_file_tree)
 }
 
+/// Returns the variants of the `#[hdk_link_types]` enum of the given integrity zome, if it has one
+pub fn get_all_link_types(zome_file_tree: &ZomeFileTree) -> ScaffoldResult<Option<Vec<String>>> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let crate_src_path_iter: Vec<OsString> =
+        crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    let link_types_instances = find_map_rust_files(
+        zome_file_tree
+            .dna_file_tree
+            .file_tree_ref()
+            .path(&mut crate_src_path_iter.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        &|_file_path, rust_file| {
+            rust_file.items.iter().find_map(|i| {
+                if let syn::Item::Enum(item_enum) = i {
+                    if item_enum.attrs.iter().any(|a| {
+                        a.path()
+                            .segments
+                            .iter()
+                            .any(|s| s.ident == "hdk_link_types")
+                    }) {
+                        return Some(item_enum.clone());
+                    }
+                }
+                None
+            })
+        },
+    );
+
+    Ok(link_types_instances.values().next().map(|link_types_enum| {
+        link_types_enum
+            .variants
+            .iter()
+            .map(|v| v.ident.to_string())
+            .collect()
+    }))
+}
+
+/// Removes the given link types from the integrity zome: their variants in the `#[hdk_link_types]` enum,
+/// their arms in the validate function and their validation functions
+///
+/// Also returns whether there are no link types left in the zome
+pub fn remove_link_types_from_integrity_zome(
+    zome_file_tree: ZomeFileTree,
+    link_types: &[String],
+) -> ScaffoldResult<(ZomeFileTree, bool)> {
+    let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
+    let zome_manifest = zome_file_tree.zome_manifest.clone();
+
+    let existing_link_types = get_all_link_types(&zome_file_tree)?.unwrap_or_default();
+    let pascal_link_types: Vec<String> =
+        link_types.iter().map(|l| l.to_case(Case::Pascal)).collect();
+    if let Some(missing_link_type) = pascal_link_types
+        .iter()
+        .find(|l| !existing_link_types.contains(l))
+    {
+        return Err(ScaffoldError::LinkTypeNotFound(
+            missing_link_type.clone(),
+            zome_file_tree.dna_file_tree.dna_manifest.name(),
+            zome_manifest.name.0.to_string(),
+        ));
+    }
+    let no_link_types_left = existing_link_types
+        .iter()
+        .all(|l| pascal_link_types.contains(l));
+
+    let validation_fns: Vec<String> = pascal_link_types
+        .iter()
+        .flat_map(|l| {
+            let snake_link_type = l.to_case(Case::Snake);
+            [
+                format!("validate_create_link_{snake_link_type}"),
+                format!("validate_delete_link_{snake_link_type}"),
+            ]
+        })
+        .collect();
+
+    let empty_arm_body: syn::Expr = syn::parse_quote! {
+        Ok(ValidateCallbackResult::Invalid(
+            "There are no link types in this integrity zome".to_string(),
+        ))
+    };
+
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        |_file_path, mut file| {
+            file.items.retain(|item| match item {
+                syn::Item::Fn(item_fn) => !validation_fns
+                    .iter()
+                    .any(|validation_fn| item_fn.sig.ident == validation_fn),
+                _ => !(no_link_types_left && is_enum_with_attribute(item, "hdk_link_types")),
+            });
+
+            for item in &mut file.items {
+                if is_enum_with_attribute(item, "hdk_link_types") {
+                    if let syn::Item::Enum(item_enum) = item {
+                        item_enum.variants = item_enum
+                            .variants
+                            .clone()
+                            .into_iter()
+                            .filter(|v| !pascal_link_types.iter().any(|l| v.ident == l))
+                            .collect();
+                    }
+                }
+
+                remove_arms_from_validation(
+                    item,
+                    &|pat| match pat {
+                        syn::Pat::Path(pat_path) => {
+                            pat_path.path.segments.len() == 2
+                                && pat_path.path.segments[0].ident == "LinkTypes"
+                                && pascal_link_types
+                                    .iter()
+                                    .any(|l| pat_path.path.segments[1].ident == l)
+                        }
+                        _ => false,
+                    },
+                    &empty_arm_body,
+                );
+
+                if no_link_types_left {
+                    if let Some(last_arg) =
+                        find_flattened_type_args(item).and_then(|args| args.last_mut())
+                    {
+                        *last_arg = syn::GenericArgument::Type(syn::parse_quote! {()});
+                    }
+                }
+            }
+
+            Ok(file)
+        },
+    )
+    .map_err(|e| match e {
+        ScaffoldError::MalformedFile(path, error) => {
+            ScaffoldError::MalformedFile(crate_src_path.join(path), error)
+        }
+        _ => e,
+    })?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)?;
+
+    Ok((zome_file_tree, no_link_types_left))
+}
+
+/// Returns the link types whose validation functions are defined in the given file,
+/// relative to the "src/" folder of the integrity zome
+pub fn find_link_types_validated_in_file(
+    zome_file_tree: &ZomeFileTree,
+    file_path: &Path,
+) -> ScaffoldResult<Vec<String>> {
+    let file_path = zome_file_tree.zome_crate_path.join("src").join(file_path);
+    let file: syn::File = syn::parse_str(&file_content(
+        zome_file_tree.dna_file_tree.file_tree_ref(),
+        &file_path,
+    )?)
+    .map_err(|e| ScaffoldError::MalformedFile(file_path.clone(), e.to_string()))?;
+
+    Ok(file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Fn(item_fn) => item_fn
+                .sig
+                .ident
+                .to_string()
+                .strip_prefix("validate_create_link_")
+                .map(|l| l.to_case(Case::Pascal)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// The validation of the deletes of the links of a link type
+fn validate_delete_link_handler(
+    link_type_name: &str,
+    delete: bool,
+    delete_policy: Policy,
+) -> TokenStream {
+    let validate_delete_fn = format_ident!(
+        "validate_delete_link_{}",
+        link_type_name.to_case(Case::Snake)
+    );
+
+    let delete_author_only = delete && delete_policy == Policy::Author;
+
+    let validate_delete_result: TokenStream = if delete_author_only {
+        let invalid_reason = format!(
+            "{} links can only be deleted by their creator",
+            link_type_name.to_case(Case::Pascal)
+        );
+        quote! {
+            if action.author != original_action.author {
+                return Ok(ValidateCallbackResult::Invalid(#invalid_reason.to_string()));
+            }
+
+            Ok(ValidateCallbackResult::Valid)
+        }
+    } else if delete {
+        quote! {
+            // TODO: add the appropriate validation rules
+            Ok(ValidateCallbackResult::Valid)
+        }
+    } else {
+        let deleted_invalid_reason = format!(
+            "{} links cannot be deleted",
+            link_type_name.to_case(Case::Pascal)
+        );
+        quote! {
+            Ok(ValidateCallbackResult::Invalid(#deleted_invalid_reason.to_string()))
+        }
+    };
+
+    let (delete_action_ident, original_action_ident) = match delete_author_only {
+        true => (format_ident!("action"), format_ident!("original_action")),
+        false => (format_ident!("_action"), format_ident!("_original_action")),
+    };
+
+    quote! {
+        pub fn #validate_delete_fn(
+            #delete_action_ident: DeleteLink,
+            #original_action_ident: CreateLink,
+            _base: AnyLinkableHash,
+            _target: AnyLinkableHash,
+            _tag: LinkTag
+        ) -> ExternResult<ValidateCallbackResult> {
+            #validate_delete_result
+      }
+    }
+}
+
 fn validate_referenceable(
     referenceable: &Referenceable,
+    foreign_entry_type: Option<&ForeignEntryType>,
     address_ident: &syn::Ident,
+    link_type_name: &str,
 ) -> TokenStream {
-    match referenceable {
-        Referenceable::EntryType(entry_type) => {
-            let entry_type_snake = format_ident!("_{}", entry_type.entry_type.to_case(Case::Snake));
-            let entry_type_pascal =
-                format_ident!("{}", entry_type.entry_type.to_case(Case::Pascal));
-
-            if entry_type.reference_entry_hash {
-                quote! {
-                    /// Check the entry type for the given entry hash
-                    let entry_hash = #address_ident.into_entry_hash().ok_or(wasm_error!(WasmErrorInner::Guest("No entry hash associated with link".to_string())))?;
-                    let entry = must_get_entry(entry_hash)?.content;
-
-                    let #entry_type_snake = crate::#entry_type_pascal::try_from(entry)?;
-                }
-            } else {
-                quote! {
-                    /// Check the entry type for the given action hash
-                    let action_hash = #address_ident.into_action_hash().ok_or(wasm_error!(
-                        WasmErrorInner::Guest("No action hash associated with link".to_string())
-                    ))?;
-                    let record = must_get_valid_record(action_hash)?;
-
-                    let #entry_type_snake: crate::#entry_type_pascal = record.entry().to_app_option()
-                      .map_err(|e| wasm_error!(e))?.ok_or(wasm_error!(WasmErrorInner::Guest("Linked action must reference an entry".to_string())))?;
-                }
+    let Referenceable::EntryType(entry_type) = referenceable else {
+        return quote! {};
+    };
+    let entry_type_snake = format_ident!("_{}", entry_type.entry_type.to_case(Case::Snake));
+    let entry_type_pascal = format_ident!("{}", entry_type.entry_type.to_case(Case::Pascal));
+
+    let entry_type_path = match foreign_entry_type {
+        // The records of other DNAs can't be fetched in validation, only the type of their hash can be checked
+        Some(ForeignEntryType::Dna { role, .. }) => {
+            let invalid_reason = format!(
+                "A {} link must reference a {} of the {role} role by its {}",
+                link_type_name.to_case(Case::Pascal),
+                entry_type.entry_type.to_case(Case::Pascal),
+                entry_type.field_type().to_string().to_case(Case::Lower)
+            );
+            return match entry_type.reference_entry_hash {
+                true => quote! {
+                    /// Check that the given address is an entry hash
+                    if #address_ident.into_entry_hash().is_none() {
+                        return Ok(ValidateCallbackResult::Invalid(#invalid_reason.to_string()));
+                    }
+                },
+                false => quote! {
+                    /// Check that the given address is an action hash
+                    if #address_ident.into_action_hash().is_none() {
+                        return Ok(ValidateCallbackResult::Invalid(#invalid_reason.to_string()));
+                    }
+                },
+            };
+        }
+        Some(ForeignEntryType::Zome { zome }) => {
+            let integrity_crate = format_ident!("{zome}");
+            quote! { #integrity_crate::#entry_type_pascal }
+        }
+        None => quote! { crate::#entry_type_pascal },
+    };
+
+    if entry_type.reference_entry_hash {
+        quote! {
+            /// Check the entry type for the given entry hash
+            let entry_hash = #address_ident.into_entry_hash().ok_or(wasm_error!(WasmErrorInner::Guest("No entry hash associated with link".to_string())))?;
+            let entry = must_get_entry(entry_hash)?.content;
+
+            let #entry_type_snake = #entry_type_path::try_from(entry)?;
+        }
+    } else {
+        quote! {
+            /// Check the entry type for the given action hash
+            let action_hash = #address_ident.into_action_hash().ok_or(wasm_error!(
+                WasmErrorInner::Guest("No action hash associated with link".to_string())
+            ))?;
+            let record = must_get_valid_record(action_hash)?;
+
+            let #entry_type_snake: #entry_type_path = record.entry().to_app_option()
+              .map_err(|e| wasm_error!(e))?.ok_or(wasm_error!(WasmErrorInner::Guest("Linked action must reference an entry".to_string())))?;
+        }
+    }
+}
+
+/// Checks that the target of a link tagged with the name of its entry type is a valid entry of that type,
+/// and that the tag is one of the given entry types
+fn validate_tagged_target(
+    entry_types: &[EntryTypeReference],
+    address_ident: &syn::Ident,
+    tag_ident: &syn::Ident,
+    link_type_name: &str,
+) -> TokenStream {
+    let pascal_entry_types: Vec<String> = entry_types
+        .iter()
+        .map(|e| e.entry_type.to_case(Case::Pascal))
+        .collect();
+    let invalid_tag_reason = format!(
+        "The tag of a {} link must be the entry type of its target: {}",
+        link_type_name.to_case(Case::Pascal),
+        pascal_entry_types.join(", ")
+    );
+    let arms = pascal_entry_types.iter().map(|entry_type| {
+        let tag = syn::LitByteStr::new(entry_type.as_bytes(), proc_macro2::Span::call_site());
+        let entry_type_snake = format_ident!("_{}", entry_type.to_case(Case::Snake));
+        let entry_type_pascal = format_ident!("{entry_type}");
+        quote! {
+            #tag => {
+                let #entry_type_snake: crate::#entry_type_pascal = record.entry().to_app_option()
+                  .map_err(|e| wasm_error!(e))?.ok_or(wasm_error!(WasmErrorInner::Guest("Linked action must reference an entry".to_string())))?;
+            }
+        }
+    });
+
+    quote! {
+        /// Check the entry type of the target against the entry type its tag names
+        let action_hash = #address_ident.into_action_hash().ok_or(wasm_error!(
+            WasmErrorInner::Guest("No action hash associated with link".to_string())
+        ))?;
+        let record = must_get_valid_record(action_hash)?;
+        match #tag_ident.into_inner().as_slice() {
+            #(#arms)*
+            _ => {
+                return Ok(ValidateCallbackResult::Invalid(#invalid_tag_reason.to_string()));
             }
         }
-        _ => quote! {},
     }
 }
 
@@ -662,3 +1070,149 @@ fn add_link_type_to_validation_arms(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use quote::ToTokens;
+
+    use super::*;
+    use crate::scaffold::zome::coordinator::calls_function;
+
+    fn comment(reference_entry_hash: bool) -> Referenceable {
+        Referenceable::EntryType(EntryTypeReference {
+            entry_type: "Comment".to_string(),
+            reference_entry_hash,
+        })
+    }
+
+    #[test]
+    fn deserializes_the_entry_types_of_other_zomes_into_their_type() {
+        let validation = validate_referenceable(
+            &comment(false),
+            Some(&ForeignEntryType::Zome {
+                zome: "comments_integrity".to_string(),
+            }),
+            &format_ident!("target_address"),
+            "PostToComments",
+        )
+        .to_string();
+
+        assert!(validation.contains("must_get_valid_record (action_hash)"));
+        assert!(validation.contains(
+            "let _comment : comments_integrity :: Comment = record . entry () . to_app_option ()"
+        ));
+        assert!(!validation.contains("dna_info"));
+
+        let validation = validate_referenceable(
+            &comment(true),
+            Some(&ForeignEntryType::Zome {
+                zome: "comments_integrity".to_string(),
+            }),
+            &format_ident!("target_address"),
+            "PostToComments",
+        )
+        .to_string();
+
+        assert!(validation
+            .contains("let _comment = comments_integrity :: Comment :: try_from (entry) ?"));
+    }
+
+    #[test]
+    fn checks_the_hash_type_of_the_entry_types_of_other_dnas() {
+        let foreign_entry_type = ForeignEntryType::Dna {
+            role: "forum".to_string(),
+            coordinator_zome: "comments".to_string(),
+            get_fn: "get_original_comment".to_string(),
+        };
+
+        let validation = validate_referenceable(
+            &comment(false),
+            Some(&foreign_entry_type),
+            &format_ident!("target_address"),
+            "PostToComments",
+        )
+        .to_string();
+        assert!(validation.contains("if target_address . into_action_hash () . is_none ()"));
+        assert!(validation.contains(
+            "\"A PostToComments link must reference a Comment of the forum role by its action hash\""
+        ));
+        assert!(!validation.contains("must_get"));
+
+        let validation = validate_referenceable(
+            &comment(true),
+            Some(&foreign_entry_type),
+            &format_ident!("target_address"),
+            "PostToComments",
+        )
+        .to_string();
+        assert!(validation.contains("if target_address . into_entry_hash () . is_none ()"));
+    }
+
+    #[test]
+    fn checks_the_tagged_entry_type_of_the_targets() {
+        let entry_types: Vec<EntryTypeReference> = ["post", "comment"]
+            .into_iter()
+            .map(|entry_type| EntryTypeReference {
+                entry_type: entry_type.to_string(),
+                reference_entry_hash: false,
+            })
+            .collect();
+        let validation = validate_tagged_target(
+            &entry_types,
+            &format_ident!("target_address"),
+            &format_ident!("tag"),
+            "Feed",
+        );
+        let block: syn::Block = syn::parse2(quote! { { #validation } }).unwrap();
+
+        let record_index = block
+            .stmts
+            .iter()
+            .position(|stmt| calls_function(stmt, "must_get_valid_record"))
+            .unwrap();
+        let Some(syn::Stmt::Expr(syn::Expr::Match(tag_match), _)) =
+            block.stmts.get(record_index + 1)
+        else {
+            panic!("The target must be checked against its tag after it's fetched");
+        };
+        assert_eq!(
+            tag_match.expr.to_token_stream().to_string(),
+            "tag . into_inner () . as_slice ()"
+        );
+
+        let arms: Vec<String> = tag_match
+            .arms
+            .iter()
+            .map(|arm| arm.pat.to_token_stream().to_string())
+            .collect();
+        assert_eq!(arms, vec!["b\"Post\"", "b\"Comment\"", "_"]);
+
+        let post_arm = tag_match.arms[0].body.to_token_stream().to_string();
+        assert!(
+            post_arm.contains("let _post : crate :: Post = record . entry () . to_app_option ()")
+        );
+        let invalid_arm = tag_match.arms[2].body.to_token_stream().to_string();
+        assert!(invalid_arm.contains(
+            "\"The tag of a Feed link must be the entry type of its target: Post, Comment\""
+        ));
+    }
+
+    #[test]
+    fn only_the_creator_can_delete_the_links_with_the_author_policy() {
+        let author_only =
+            validate_delete_link_handler("PostToComments", true, Policy::Author).to_string();
+        assert!(author_only.contains("if action . author != original_action . author"));
+        assert!(
+            author_only.contains("\"PostToComments links can only be deleted by their creator\"")
+        );
+
+        let anyone =
+            validate_delete_link_handler("PostToComments", true, Policy::Anyone).to_string();
+        assert!(anyone.contains("_original_action : CreateLink"));
+        assert!(anyone.contains("Ok (ValidateCallbackResult :: Valid)"));
+
+        let undeletable =
+            validate_delete_link_handler("PostToComments", false, Policy::Anyone).to_string();
+        assert!(undeletable.contains("\"PostToComments links cannot be deleted\""));
+    }
+}
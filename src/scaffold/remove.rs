@@ -0,0 +1,793 @@
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+use convert_case::{Case, Casing};
+use dialoguer::{theme::ColorfulTheme, Confirm};
+use holochain_types::prelude::ZomeManifest;
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::visit::{self, Visit};
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::{file_exists, find_map_files, find_map_rust_files, remove_file, FileTree},
+    utils::check_interactive,
+};
+
+use super::{
+    collection::{
+        coordinator::{
+            find_by_path_key_field, find_collection_entry_types, find_collection_filter,
+            remove_collection_from_coordinators, TIME_INDEX_MODULE,
+        },
+        keys_link_type_name, time_index_link_type_name, CollectionType,
+    },
+    dna::DnaFileTree,
+    entry_type::{
+        coordinator::remove_crud_functions_from_coordinator,
+        integrity::{get_all_entry_types, remove_entry_type_from_integrity_zome},
+    },
+    link_type::{
+        coordinator::remove_link_type_functions_from_coordinator,
+        integrity::{
+            find_link_types_validated_in_file, get_all_link_types,
+            remove_link_types_from_integrity_zome,
+        },
+    },
+    zome::{
+        coordinator::find_extern_function_in_zomes,
+        utils::{get_coordinator_zomes_for_integrity, remove_module_from_crate},
+        ZomeFileTree,
+    },
+};
+
+/// Removes everything that `entry-type` scaffolded for the given entry type: its definition and validation
+/// in the integrity zome, the link types validated along with it, its CRUD functions and its signals
+pub fn remove_entry_type(
+    integrity_zome_file_tree: ZomeFileTree,
+    entry_type: &str,
+    delete_ui_and_tests: Option<bool>,
+    no_interactive: bool,
+) -> ScaffoldResult<FileTree> {
+    let pascal_entry_type = entry_type.to_case(Case::Pascal);
+    let entry_type_file = PathBuf::from(format!("{}.rs", entry_type.to_case(Case::Snake)));
+
+    // Link types validated in the file of the entry type (linked fields, updates, collections) are removed with it
+    let link_types = if file_exists(
+        integrity_zome_file_tree.dna_file_tree.file_tree_ref(),
+        &integrity_zome_file_tree
+            .zome_crate_path
+            .join("src")
+            .join(&entry_type_file),
+    ) {
+        find_link_types_validated_in_file(&integrity_zome_file_tree, &entry_type_file)?
+    } else {
+        vec![]
+    };
+
+    let (integrity_zome_file_tree, no_entry_types_left) =
+        remove_entry_type_from_integrity_zome(integrity_zome_file_tree, entry_type)?;
+
+    let integrity_zome_file_tree = if link_types.is_empty() {
+        integrity_zome_file_tree
+    } else {
+        remove_link_types(integrity_zome_file_tree, &link_types)?
+    };
+
+    let integrity_zome_manifest = integrity_zome_file_tree.zome_manifest.clone();
+    let mut dna_file_tree = integrity_zome_file_tree.dna_file_tree;
+
+    for coordinator_zome in coordinator_zomes(&dna_file_tree, &integrity_zome_manifest) {
+        let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, coordinator_zome)?;
+        dna_file_tree = remove_crud_functions_from_coordinator(
+            zome_file_tree,
+            entry_type,
+            no_entry_types_left,
+        )?
+        .dna_file_tree;
+    }
+
+    let mut identifiers = vec![pascal_entry_type];
+    identifiers.extend(link_types.iter().cloned());
+    check_not_referenced(&dna_file_tree, &integrity_zome_manifest, &identifiers)?;
+
+    let mut file_names = generated_file_names_for_entry_type(entry_type);
+    for link_type in &link_types {
+        file_names.extend(generated_file_names_for_link_type(link_type));
+    }
+
+    remove_generated_files(
+        dna_file_tree,
+        &integrity_zome_manifest,
+        &file_names,
+        delete_ui_and_tests,
+        no_interactive,
+    )
+}
+
+/// Removes everything that `link-type` scaffolded for the given link type: its variant, validation
+/// and coordinator functions, along with the inverse link type if it was scaffolded as bidirectional
+pub fn remove_link_type(
+    integrity_zome_file_tree: ZomeFileTree,
+    link_type: &str,
+    delete_ui_and_tests: Option<bool>,
+    no_interactive: bool,
+) -> ScaffoldResult<FileTree> {
+    let pascal_link_type = link_type.to_case(Case::Pascal);
+    let link_type_file = PathBuf::from(format!("{}.rs", link_type.to_case(Case::Snake)));
+
+    let mut link_types = vec![pascal_link_type];
+    if file_exists(
+        integrity_zome_file_tree.dna_file_tree.file_tree_ref(),
+        &integrity_zome_file_tree
+            .zome_crate_path
+            .join("src")
+            .join(&link_type_file),
+    ) {
+        for validated_link_type in
+            find_link_types_validated_in_file(&integrity_zome_file_tree, &link_type_file)?
+        {
+            if !link_types.contains(&validated_link_type) {
+                link_types.push(validated_link_type);
+            }
+        }
+    }
+
+    let integrity_zome_file_tree = remove_link_types(integrity_zome_file_tree, &link_types)?;
+
+    let integrity_zome_manifest = integrity_zome_file_tree.zome_manifest.clone();
+    let dna_file_tree = integrity_zome_file_tree.dna_file_tree;
+
+    check_not_referenced(&dna_file_tree, &integrity_zome_manifest, &link_types)?;
+
+    let file_names: Vec<String> = link_types
+        .iter()
+        .flat_map(|l| generated_file_names_for_link_type(l))
+        .collect();
+
+    remove_generated_files(
+        dna_file_tree,
+        &integrity_zome_manifest,
+        &file_names,
+        delete_ui_and_tests,
+        no_interactive,
+    )
+}
+
+/// Removes everything that `collection` scaffolded for the given collection: its link type, its getter
+/// function and the links created and deleted for it in the CRUD functions of the collected entry type
+pub fn remove_collection(
+    integrity_zome_file_tree: ZomeFileTree,
+    collection_name: &str,
+    delete_ui_and_tests: Option<bool>,
+    no_interactive: bool,
+) -> ScaffoldResult<FileTree> {
+    let snake_collection_name = collection_name.to_case(Case::Snake);
+    let link_type_name = collection_name.to_case(Case::Pascal);
+
+    let collection_not_found = || {
+        ScaffoldError::CollectionNotFound(
+            snake_collection_name.clone(),
+            integrity_zome_file_tree.dna_file_tree.dna_manifest.name(),
+            integrity_zome_file_tree.zome_manifest.name.0.to_string(),
+        )
+    };
+
+    let Some((_, getter)) = find_extern_function_in_zomes(
+        &integrity_zome_file_tree.dna_file_tree,
+        &coordinator_zomes(
+            &integrity_zome_file_tree.dna_file_tree,
+            &integrity_zome_file_tree.zome_manifest,
+        ),
+        &format!("get_{snake_collection_name}"),
+    )?
+    else {
+        return Err(collection_not_found());
+    };
+    // The links of by-path collections are created under a path built from the key field of the entry
+    let key_field = find_by_path_key_field(
+        &integrity_zome_file_tree.dna_file_tree,
+        &coordinator_zomes(
+            &integrity_zome_file_tree.dna_file_tree,
+            &integrity_zome_file_tree.zome_manifest,
+        ),
+        &snake_collection_name,
+    )?;
+    // The links of time-indexed collections are created under a tree of paths with its own link type
+    let time_indexed = get_all_link_types(&integrity_zome_file_tree)?
+        .unwrap_or_default()
+        .contains(&time_index_link_type_name(&link_type_name));
+    let collection_type = match (key_field, getter.sig.inputs.first()) {
+        _ if gets_links_of_agent(&getter) => CollectionType::ByAuthor,
+        (Some(key_field), _) => CollectionType::ByPath { key_field },
+        _ if time_indexed => CollectionType::TimeIndexed,
+        (None, None) => CollectionType::Global,
+        // The getters of paginated global collections take a page input
+        (None, Some(_)) => CollectionType::Global,
+    };
+
+    // The getter of a collection of several entry types returns an enum with a variant for each of them,
+    // otherwise the link type of the collection is validated in the file of the collected entry type
+    let multiple_entry_types = find_collection_entry_types(
+        &integrity_zome_file_tree.dna_file_tree,
+        &coordinator_zomes(
+            &integrity_zome_file_tree.dna_file_tree,
+            &integrity_zome_file_tree.zome_manifest,
+        ),
+        &snake_collection_name,
+    )?;
+    let crate_src_path = integrity_zome_file_tree.zome_crate_path.join("src");
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    let validate_create_fn = format!("validate_create_link_{snake_collection_name}");
+    let validation_files = find_map_rust_files(
+        integrity_zome_file_tree
+            .dna_file_tree
+            .file_tree_ref()
+            .path(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        &|_file_path, file| {
+            file.items
+                .iter()
+                .any(|item| matches!(item, syn::Item::Fn(item_fn) if item_fn.sig.ident == validate_create_fn))
+                .then_some(())
+        },
+    );
+    let entry_type = validation_files
+        .keys()
+        .next()
+        .and_then(|path| path.file_stem())
+        .map(|stem| stem.to_string_lossy().to_case(Case::Pascal))
+        .and_then(|entry_type| {
+            get_all_entry_types(&integrity_zome_file_tree)
+                .ok()
+                .flatten()?
+                .into_iter()
+                .find(|e| e.entry_type == entry_type)
+        })
+        .ok_or_else(collection_not_found)?;
+    let entry_types = multiple_entry_types.unwrap_or_else(|| vec![entry_type.clone()]);
+
+    let filter = find_collection_filter(
+        &integrity_zome_file_tree.dna_file_tree,
+        &coordinator_zomes(
+            &integrity_zome_file_tree.dna_file_tree,
+            &integrity_zome_file_tree.zome_manifest,
+        ),
+        &snake_collection_name,
+        &link_type_name,
+        &collection_type,
+        &entry_type,
+    )?;
+
+    let integrity_zome_manifest = integrity_zome_file_tree.zome_manifest.clone();
+    let dna_file_tree = remove_collection_from_coordinators(
+        integrity_zome_file_tree,
+        &snake_collection_name,
+        &link_type_name,
+        &collection_type,
+        &entry_types,
+        filter.as_ref(),
+    )?;
+    let integrity_zome_file_tree =
+        ZomeFileTree::from_zome_manifest(dna_file_tree, integrity_zome_manifest.clone())?;
+
+    let mut link_types = vec![link_type_name.clone()];
+    match collection_type {
+        CollectionType::ByPath { .. } => link_types.push(keys_link_type_name(&link_type_name)),
+        CollectionType::TimeIndexed => link_types.push(time_index_link_type_name(&link_type_name)),
+        _ => {}
+    }
+
+    let integrity_zome_file_tree = remove_link_types(integrity_zome_file_tree, &link_types)?;
+    let mut dna_file_tree = integrity_zome_file_tree.dna_file_tree;
+
+    if let CollectionType::TimeIndexed = collection_type {
+        dna_file_tree = remove_unused_time_index_module(dna_file_tree, &integrity_zome_manifest)?;
+    }
+
+    check_not_referenced(&dna_file_tree, &integrity_zome_manifest, &link_types)?;
+
+    remove_generated_files(
+        dna_file_tree,
+        &integrity_zome_manifest,
+        &[snake_collection_name],
+        delete_ui_and_tests,
+        no_interactive,
+    )
+}
+
+/// Removes the given link types from the integrity zome and the files with their functions from its coordinator zomes
+fn remove_link_types(
+    integrity_zome_file_tree: ZomeFileTree,
+    link_types: &[String],
+) -> ScaffoldResult<ZomeFileTree> {
+    let (integrity_zome_file_tree, no_link_types_left) =
+        remove_link_types_from_integrity_zome(integrity_zome_file_tree, link_types)?;
+
+    let integrity_zome_manifest = integrity_zome_file_tree.zome_manifest.clone();
+    let crate_src_path = integrity_zome_file_tree.zome_crate_path.join("src");
+    let dna_manifest_path = integrity_zome_file_tree
+        .dna_file_tree
+        .dna_manifest_path
+        .clone();
+    let mut file_tree = integrity_zome_file_tree.dna_file_tree.file_tree();
+
+    // Files created by `link-type` only held the validation functions, delete them if nothing else is left
+    for link_type in link_types {
+        let module_name = link_type.to_case(Case::Snake);
+        let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+        let only_imports_left = find_map_rust_files(
+            file_tree
+                .path(&mut v.iter())
+                .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+            &|file_path, file| {
+                (file_path.file_stem() == Some(OsStr::new(&module_name))
+                    && file
+                        .items
+                        .iter()
+                        .all(|item| matches!(item, syn::Item::Use(_))))
+                .then_some(())
+            },
+        );
+        if !only_imports_left.is_empty() {
+            remove_module_from_crate(&mut file_tree, &crate_src_path, &module_name)?;
+        }
+    }
+
+    let mut dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+
+    for coordinator_zome in coordinator_zomes(&dna_file_tree, &integrity_zome_manifest) {
+        let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, coordinator_zome)?;
+        dna_file_tree = remove_link_type_functions_from_coordinator(
+            zome_file_tree,
+            link_types,
+            no_link_types_left,
+        )?
+        .dna_file_tree;
+    }
+
+    ZomeFileTree::from_zome_manifest(dna_file_tree, integrity_zome_manifest)
+}
+
+/// Removes the helpers of time-indexed collections from the coordinator zomes that have none of them left
+fn remove_unused_time_index_module(
+    mut dna_file_tree: DnaFileTree,
+    integrity_zome_manifest: &ZomeManifest,
+) -> ScaffoldResult<DnaFileTree> {
+    for coordinator_zome in coordinator_zomes(&dna_file_tree, integrity_zome_manifest) {
+        let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, coordinator_zome)?;
+        let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+        let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
+        let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+        let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+        let users = find_map_rust_files(
+            file_tree
+                .path(&mut v.iter())
+                .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+            &|file_path, file| {
+                (file_path != Path::new(&format!("{TIME_INDEX_MODULE}.rs"))
+                    && file.items.iter().any(|item| {
+                        !matches!(item, syn::Item::Mod(item_mod) if item_mod.ident == TIME_INDEX_MODULE)
+                            && contains_ident(item.to_token_stream(), TIME_INDEX_MODULE)
+                    }))
+                .then_some(())
+            },
+        );
+        if users.is_empty() {
+            remove_module_from_crate(&mut file_tree, &crate_src_path, TIME_INDEX_MODULE)?;
+        }
+
+        dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    }
+
+    Ok(dna_file_tree)
+}
+
+fn coordinator_zomes(
+    dna_file_tree: &DnaFileTree,
+    integrity_zome_manifest: &ZomeManifest,
+) -> Vec<ZomeManifest> {
+    get_coordinator_zomes_for_integrity(
+        &dna_file_tree.dna_manifest,
+        integrity_zome_manifest.name.0.as_ref(),
+    )
+}
+
+/// Fails if any of the given identifiers is still used in the integrity zome or in its coordinator zomes,
+/// since removing what was scaffolded for it would leave them uncompilable
+fn check_not_referenced(
+    dna_file_tree: &DnaFileTree,
+    integrity_zome_manifest: &ZomeManifest,
+    identifiers: &[String],
+) -> ScaffoldResult<()> {
+    let mut zomes = vec![integrity_zome_manifest.clone()];
+    zomes.extend(coordinator_zomes(dna_file_tree, integrity_zome_manifest));
+
+    for zome in zomes {
+        let zome_file_tree = ZomeFileTree::from_zome_manifest(
+            DnaFileTree::from_dna_manifest_path(
+                dna_file_tree.file_tree_ref().clone(),
+                &dna_file_tree.dna_manifest_path,
+            )?,
+            zome,
+        )?;
+        let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+        let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+        let references = find_map_rust_files(
+            zome_file_tree
+                .dna_file_tree
+                .file_tree_ref()
+                .path(&mut v.iter())
+                .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+            &|_file_path, file| {
+                identifiers
+                    .iter()
+                    .find(|identifier| contains_ident(file.to_token_stream(), identifier))
+                    .cloned()
+            },
+        );
+
+        if let Some((file_path, identifier)) = references.into_iter().next() {
+            return Err(ScaffoldError::StillReferenced(
+                identifier,
+                crate_src_path.join(file_path),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the given collection getter gets the links of an agent, like the ones of by-author collections,
+/// rather than the links of a path, from the base that it passes to `GetLinksInputBuilder::try_new`
+fn gets_links_of_agent(getter: &syn::ItemFn) -> bool {
+    #[derive(Default)]
+    struct LinksBaseFinder(Option<syn::Expr>);
+
+    impl<'ast> Visit<'ast> for LinksBaseFinder {
+        fn visit_expr_call(&mut self, i: &'ast syn::ExprCall) {
+            if let syn::Expr::Path(function) = i.func.as_ref() {
+                let segments: Vec<String> = function
+                    .path
+                    .segments
+                    .iter()
+                    .map(|segment| segment.ident.to_string())
+                    .collect();
+                if self.0.is_none() && segments == ["GetLinksInputBuilder", "try_new"] {
+                    self.0 = i.args.first().cloned();
+                }
+            }
+            visit::visit_expr_call(self, i);
+        }
+    }
+
+    let mut finder = LinksBaseFinder::default();
+    finder.visit_item_fn(getter);
+    let base = match finder.0 {
+        Some(syn::Expr::Try(expr_try)) => *expr_try.expr,
+        Some(base) => base,
+        None => return false,
+    };
+    !matches!(base, syn::Expr::MethodCall(method_call) if method_call.method == "path_entry_hash")
+}
+
+fn contains_ident(token_stream: TokenStream, ident: &str) -> bool {
+    token_stream.into_iter().any(|token_tree| match token_tree {
+        TokenTree::Ident(i) => i == ident,
+        TokenTree::Group(group) => contains_ident(group.stream(), ident),
+        _ => false,
+    })
+}
+
+fn generated_file_names_for_entry_type(entry_type: &str) -> Vec<String> {
+    let snake_entry_type = entry_type.to_case(Case::Snake);
+    vec![
+        format!("create_{snake_entry_type}"),
+        format!("edit_{snake_entry_type}"),
+        format!("{snake_entry_type}_detail"),
+        snake_entry_type,
+    ]
+}
+
+fn generated_file_names_for_link_type(link_type: &str) -> Vec<String> {
+    let snake_link_type = link_type.to_case(Case::Snake);
+    let mut file_names = vec![snake_link_type.clone()];
+
+    // Link types named "<from>_to_<to>" render "<to>_for_<from>" components, and "<from>_for_<to>" ones when bidirectional
+    if let Some((from, to)) = snake_link_type.split_once("_to_") {
+        file_names.push(format!("{to}_for_{from}"));
+        file_names.push(format!(
+            "{}_for_{}",
+            pluralizer::pluralize(from, 2, false),
+            pluralizer::pluralize(to, 1, false)
+        ));
+    }
+
+    file_names
+}
+
+/// Finds the UI and test files that were rendered for the given names in the folders of the coordinator
+/// zomes of the given integrity zome, and deletes them if the user agrees to it
+fn remove_generated_files(
+    dna_file_tree: DnaFileTree,
+    integrity_zome_manifest: &ZomeManifest,
+    file_names: &[String],
+    delete_ui_and_tests: Option<bool>,
+    no_interactive: bool,
+) -> ScaffoldResult<FileTree> {
+    let generated_files: Vec<PathBuf> =
+        find_ui_and_test_files(&dna_file_tree, integrity_zome_manifest)
+            .into_iter()
+            .filter(|file_path| {
+                file_path
+                    .file_name()
+                    .and_then(|file_name| {
+                        file_name
+                            .to_string_lossy()
+                            .split('.')
+                            .next()
+                            .map(|stem| stem.to_case(Case::Snake))
+                    })
+                    .is_some_and(|file_name| file_names.contains(&file_name))
+            })
+            .collect();
+
+    let mut file_tree = dna_file_tree.file_tree();
+
+    if generated_files.is_empty() {
+        return Ok(file_tree);
+    }
+
+    let delete = match delete_ui_and_tests {
+        Some(delete) => delete,
+        None => {
+            check_interactive("the `--delete-ui-and-tests` option", no_interactive)?;
+            println!("\nThese UI and test files were generated for it:");
+            for file in &generated_files {
+                println!("  {}", file.display());
+            }
+            Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Delete them too?")
+                .interact()?
+        }
+    };
+
+    if delete {
+        for file in generated_files {
+            remove_file(&mut file_tree, &file)?;
+        }
+    }
+
+    Ok(file_tree)
+}
+
+/// Returns the paths of the files in the UI and test folders of the coordinator zomes for the given integrity zome,
+/// which is where the templates render the files for its entry types, link types and collections
+pub fn find_ui_and_test_files(
+    dna_file_tree: &DnaFileTree,
+    integrity_zome_manifest: &ZomeManifest,
+) -> Vec<PathBuf> {
+    let dna_name = dna_file_tree.dna_manifest.name();
+    let zome_folders: Vec<PathBuf> = coordinator_zomes(dna_file_tree, integrity_zome_manifest)
+        .into_iter()
+        .map(|z| PathBuf::from(&dna_name).join(z.name.0.as_ref()))
+        .collect();
+
+    find_map_files(dna_file_tree.file_tree_ref(), &|file_path, _contents| {
+        file_path
+            .parent()
+            .is_some_and(|folder| zome_folders.iter().any(|z| folder.ends_with(z)))
+            .then_some(())
+    })
+    .into_keys()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        file_tree::file_content,
+        scaffold::{
+            happ_spec::{apply_happ_spec, HappSpec},
+            web_app::{
+                package_manager::PackageManager, scaffold_web_app, template_type::TemplateType,
+            },
+        },
+        templates::ScaffoldedTemplate,
+    };
+
+    #[test]
+    fn detects_the_collections_of_agents_from_the_links_base() {
+        let paginated_global: syn::ItemFn = syn::parse_quote! {
+            #[hdk_extern]
+            pub fn get_all_posts(input: PageInput) -> ExternResult<Page> {
+                let path = Path::from("all_posts");
+                let links = get_links(GetLinksInputBuilder::try_new(path.path_entry_hash()?, LinkTypes::AllPosts)?.build())?;
+                page(links, input.cursor, input.limit)
+            }
+        };
+        let paginated_by_author: syn::ItemFn = syn::parse_quote! {
+            #[hdk_extern]
+            pub fn get_posts_by_author(input: PageInput) -> ExternResult<Page> {
+                let links = get_links(GetLinksInputBuilder::try_new(input.author, LinkTypes::PostsByAuthor)?.build())?;
+                page(links, input.cursor, input.limit)
+            }
+        };
+        let by_author: syn::ItemFn = syn::parse_quote! {
+            #[hdk_extern]
+            pub fn get_posts_by_author(author: AgentPubKey) -> ExternResult<Vec<Link>> {
+                get_links(GetLinksInputBuilder::try_new(author, LinkTypes::PostsByAuthor)?.build())
+            }
+        };
+
+        assert!(!gets_links_of_agent(&paginated_global));
+        assert!(gets_links_of_agent(&paginated_by_author));
+        assert!(gets_links_of_agent(&by_author));
+    }
+
+    /// The forum app with its posts zomes scaffolded from the given spec, with its UI and tests
+    fn forum_integrity_zome(spec: &str) -> ZomeFileTree {
+        let template_file_tree = TemplateType::Svelte.file_tree().unwrap();
+        let ScaffoldedTemplate { file_tree, .. } = scaffold_web_app(
+            "forum",
+            None,
+            PackageManager::Npm,
+            true,
+            &template_file_tree,
+            false,
+        )
+        .unwrap();
+        let spec = HappSpec::from_file_content(Path::new("happ-spec.yml"), spec).unwrap();
+        let file_tree =
+            apply_happ_spec(file_tree, &template_file_tree, &spec, false, false, true).unwrap();
+
+        let dna_file_tree = DnaFileTree::get_or_choose(file_tree, Some("forum"), true).unwrap();
+        ZomeFileTree::get_or_choose_integrity(dna_file_tree, Some("posts_integrity"), true).unwrap()
+    }
+
+    fn integrity_lib_rs(file_tree: &FileTree) -> String {
+        file_content(
+            file_tree,
+            Path::new("dnas/forum/zomes/integrity/posts/src/lib.rs"),
+        )
+        .unwrap()
+    }
+
+    fn coordinator_file_exists(file_tree: &FileTree, file_name: &str) -> bool {
+        file_exists(
+            file_tree,
+            &Path::new("dnas/forum/zomes/coordinator/posts/src").join(file_name),
+        )
+    }
+
+    #[test]
+    fn test_remove_time_indexed_collection() {
+        let integrity_zome_file_tree = forum_integrity_zome(
+            r#"
+dnas:
+  - name: forum
+    zomes:
+      - name: posts
+        entry_types:
+          - name: post
+            fields: ["title:String:TextField"]
+        collections:
+          - name: recent_posts
+            type: time-indexed
+            entry_type: post
+          - name: posts_by_hour
+            type: time-indexed
+            entry_type: post
+"#,
+        );
+        assert!(coordinator_file_exists(
+            integrity_zome_file_tree.dna_file_tree.file_tree_ref(),
+            "time_index.rs"
+        ));
+
+        let file_tree =
+            remove_collection(integrity_zome_file_tree, "recent_posts", Some(true), true).unwrap();
+        assert!(!integrity_lib_rs(&file_tree).contains("RecentPostsTimeIndex"));
+        assert!(!coordinator_file_exists(&file_tree, "recent_posts.rs"));
+        // The other time-indexed collection still uses the shared helpers
+        assert!(coordinator_file_exists(&file_tree, "time_index.rs"));
+
+        let dna_file_tree = DnaFileTree::get_or_choose(file_tree, Some("forum"), true).unwrap();
+        let integrity_zome_file_tree =
+            ZomeFileTree::get_or_choose_integrity(dna_file_tree, Some("posts_integrity"), true)
+                .unwrap();
+        let file_tree =
+            remove_collection(integrity_zome_file_tree, "posts_by_hour", Some(true), true).unwrap();
+        assert!(!integrity_lib_rs(&file_tree).contains("PostsByHourTimeIndex"));
+        assert!(!coordinator_file_exists(&file_tree, "time_index.rs"));
+        assert!(!file_content(
+            &file_tree,
+            Path::new("dnas/forum/zomes/coordinator/posts/src/lib.rs")
+        )
+        .unwrap()
+        .contains("time_index"));
+    }
+
+    #[test]
+    fn test_remove_entry_type_with_links_and_collections() {
+        let integrity_zome_file_tree = forum_integrity_zome(
+            r#"
+dnas:
+  - name: forum
+    zomes:
+      - name: posts
+        entry_types:
+          - name: post
+            fields: ["title:String:TextField"]
+          - name: comment
+            fields: ["content:String:TextArea", "post_hash:ActionHash::Post"]
+        collections:
+          - name: all_comments
+            type: global
+            entry_type: comment
+"#,
+        );
+        let file_tree = integrity_zome_file_tree.dna_file_tree.file_tree();
+        let posts_integrity_zome = |file_tree: FileTree| {
+            let dna_file_tree = DnaFileTree::get_or_choose(file_tree, Some("forum"), true).unwrap();
+            ZomeFileTree::get_or_choose_integrity(dna_file_tree, Some("posts_integrity"), true)
+                .unwrap()
+        };
+
+        // The comments still reference the posts
+        assert!(remove_entry_type(
+            posts_integrity_zome(file_tree.clone()),
+            "post",
+            Some(true),
+            true
+        )
+        .is_err());
+
+        let file_tree =
+            remove_entry_type(posts_integrity_zome(file_tree), "comment", Some(true), true)
+                .unwrap();
+
+        let lib_rs = integrity_lib_rs(&file_tree);
+        assert!(lib_rs.contains("Post(Post)"));
+        assert!(lib_rs.contains("PostUpdates"));
+        for removed in ["Comment", "PostToComments", "AllComments"] {
+            assert!(!lib_rs.contains(removed), "{removed} was not removed");
+        }
+        assert!(!file_exists(
+            &file_tree,
+            Path::new("dnas/forum/zomes/integrity/posts/src/comment.rs")
+        ));
+        assert!(coordinator_file_exists(&file_tree, "post.rs"));
+        assert!(!coordinator_file_exists(&file_tree, "comment.rs"));
+        assert!(!coordinator_file_exists(&file_tree, "all_comments.rs"));
+
+        for kept in [
+            "ui/src/forum/posts/PostDetail.svelte",
+            "tests/src/forum/posts/post.test.ts",
+        ] {
+            assert!(
+                file_exists(&file_tree, Path::new(kept)),
+                "{kept} was removed"
+            );
+        }
+        for removed in [
+            "ui/src/forum/posts/AllComments.svelte",
+            "ui/src/forum/posts/CommentDetail.svelte",
+            "ui/src/forum/posts/CommentsForPost.svelte",
+            "ui/src/forum/posts/CreateComment.svelte",
+            "ui/src/forum/posts/EditComment.svelte",
+            "tests/src/forum/posts/all-comments.test.ts",
+            "tests/src/forum/posts/comment.test.ts",
+        ] {
+            assert!(
+                !file_exists(&file_tree, Path::new(removed)),
+                "{removed} was not removed"
+            );
+        }
+    }
+}
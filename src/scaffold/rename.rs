@@ -0,0 +1,622 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::{OsStr, OsString},
+    path::PathBuf,
+};
+
+use convert_case::{Case, Casing};
+use proc_macro2::{TokenStream, TokenTree};
+use regex::{Captures, Regex};
+use syn::visit::{self, Visit};
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::{file_content, find_map_files, insert_file, remove_file, FileTree},
+    reserved_words::check_for_reserved_keywords,
+};
+
+use super::{
+    dna::DnaFileTree,
+    entry_type::integrity::get_all_entry_types,
+    link_type::integrity::get_all_link_types,
+    remove::find_ui_and_test_files,
+    zome::{
+        coordinator::HOLOCHAIN_CALLBACKS, utils::get_coordinator_zomes_for_integrity, ZomeFileTree,
+    },
+};
+
+/// Renames the given entry type in the integrity zome, its coordinator zomes and their UI and test files
+///
+/// Only the identifiers the scaffolding tool derives from the name of an entry type are renamed (the struct, the
+/// `EntryTypes` variant, the validation and CRUD functions, the updates and collection link types, the UI
+/// components...): the ones that join the words of its singular or plural form with the words the tool uses to
+/// build names, or with the names of the other entry types and link types of the zome. In the Rust files, only the
+/// identifiers that the zome crates define are renamed, so the items of the HDK, the HDI and other crates keep their
+/// name. The files whose names are derived from the entry type are renamed as well
+pub fn rename_entry_type(
+    integrity_zome_file_tree: ZomeFileTree,
+    old_name: &str,
+    new_name: &str,
+) -> ScaffoldResult<FileTree> {
+    check_for_reserved_keywords(new_name)?;
+
+    let dna_name = integrity_zome_file_tree.dna_file_tree.dna_manifest.name();
+    let integrity_zome_name = integrity_zome_file_tree.zome_manifest.name.0.to_string();
+
+    let entry_types: Vec<String> = get_all_entry_types(&integrity_zome_file_tree)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|e| e.entry_type)
+        .collect();
+    if !entry_types.contains(&old_name.to_case(Case::Pascal)) {
+        return Err(ScaffoldError::EntryTypeNotFound(
+            old_name.to_string(),
+            dna_name,
+            integrity_zome_name,
+        ));
+    }
+    if entry_types.contains(&new_name.to_case(Case::Pascal)) {
+        return Err(ScaffoldError::EntryTypeAlreadyExists(
+            new_name.to_string(),
+            dna_name,
+            integrity_zome_name,
+        ));
+    }
+
+    let link_types = get_all_link_types(&integrity_zome_file_tree)?.unwrap_or_default();
+    let mut name_words: BTreeSet<String> = GENERATED_NAME_WORDS
+        .split_whitespace()
+        .map(|word| word.to_string())
+        .collect();
+    for name in entry_types.iter().chain(link_types.iter()) {
+        for form in [1, 2] {
+            name_words.extend(words(&pluralizer::pluralize(
+                &name.to_case(Case::Snake),
+                form,
+                false,
+            )));
+        }
+    }
+
+    let coordinator_zomes = get_coordinator_zomes_for_integrity(
+        &integrity_zome_file_tree.dna_file_tree.dna_manifest,
+        &integrity_zome_name,
+    );
+
+    // The names of the zomes and the DNA can contain the words of the entry type (eg. a "posts" zome for a "post"
+    // entry type) but are not derived from it, so they must stay as they are where they are referenced
+    let mut protected_names = vec![dna_name.clone(), integrity_zome_name.clone()];
+    protected_names.extend(coordinator_zomes.iter().map(|z| z.name.0.to_string()));
+
+    let renamer = EntryTypeRenamer::new(old_name, new_name, name_words, protected_names);
+
+    let mut crate_src_paths = vec![integrity_zome_file_tree.zome_crate_path.join("src")];
+    for coordinator_zome in &coordinator_zomes {
+        let dna_file_tree = DnaFileTree::from_dna_manifest_path(
+            integrity_zome_file_tree
+                .dna_file_tree
+                .file_tree_ref()
+                .clone(),
+            &integrity_zome_file_tree.dna_file_tree.dna_manifest_path,
+        )?;
+        let zome_file_tree =
+            ZomeFileTree::from_zome_manifest(dna_file_tree, coordinator_zome.clone())?;
+        crate_src_paths.push(zome_file_tree.zome_crate_path.join("src"));
+    }
+
+    let mut rust_files: BTreeMap<PathBuf, syn::File> = BTreeMap::new();
+    for crate_src_path in &crate_src_paths {
+        let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+        let files = find_map_files(
+            integrity_zome_file_tree
+                .dna_file_tree
+                .file_tree_ref()
+                .path(&mut v.iter())
+                .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+            &|file_path, contents| {
+                (file_path.extension() == Some(OsStr::new("rs"))).then(|| contents.clone())
+            },
+        );
+        for (file_path, contents) in files {
+            let file_path = crate_src_path.join(file_path);
+            let file: syn::File = syn::parse_str(&contents)
+                .map_err(|e| ScaffoldError::MalformedFile(file_path.clone(), e.to_string()))?;
+            rust_files.insert(file_path, file);
+        }
+    }
+
+    let mut defined_identifiers = DefinedIdentifiers::default();
+    for file in rust_files.values() {
+        defined_identifiers.visit_file(file);
+    }
+
+    let ui_and_test_files = find_ui_and_test_files(
+        &integrity_zome_file_tree.dna_file_tree,
+        &integrity_zome_file_tree.zome_manifest,
+    );
+    let mut file_tree = integrity_zome_file_tree.dna_file_tree.file_tree();
+    let files_to_rename = rust_files
+        .keys()
+        .cloned()
+        .chain(ui_and_test_files)
+        .collect::<Vec<PathBuf>>();
+
+    for file_path in files_to_rename {
+        let contents = file_content(&file_tree, &file_path)?;
+        let (new_contents, file_name_case) = match rust_files.get(&file_path) {
+            Some(file) => (
+                rename_rust_identifiers(&contents, file, |identifier| {
+                    if !defined_identifiers.0.contains(identifier)
+                        || HOLOCHAIN_CALLBACKS.contains(&identifier)
+                    {
+                        return None;
+                    }
+                    let renamed = renamer.rename_identifier(identifier, Case::Snake);
+                    (renamed != identifier).then_some(renamed)
+                }),
+                Case::Snake,
+            ),
+            // Single lowercase words are variables, in camel case in the UI and tests
+            None => (renamer.rename(&contents, Case::Camel), Case::Kebab),
+        };
+
+        let new_file_path = match file_path.file_name() {
+            Some(file_name) => {
+                let file_name = file_name.to_string_lossy();
+                let (stem, extension) = file_name.split_once('.').unwrap_or((&file_name, ""));
+                let new_stem = renamer.rename(stem, file_name_case);
+                match extension.is_empty() {
+                    true => file_path.with_file_name(new_stem),
+                    false => file_path.with_file_name(format!("{new_stem}.{extension}")),
+                }
+            }
+            None => file_path.clone(),
+        };
+
+        if new_file_path != file_path {
+            remove_file(&mut file_tree, &file_path)?;
+        }
+        if new_file_path != file_path || new_contents != contents {
+            insert_file(&mut file_tree, &new_file_path, &new_contents)?;
+        }
+    }
+
+    Ok(file_tree)
+}
+
+/// Words that the scaffolding tool joins with the name of an entry type to build the names derived from it,
+/// in the zomes (eg. `get_all_revisions_for_post`, `UpdatePostInput`) and in the UI (eg. `PostDetail`, `isPostValid`)
+const GENERATED_NAME_WORDS: &str = "add all base by count create created current delete deleted deletes detail \
+    edit entry error fetch for get has hash hashes in input into is keys latest link links oldest on original page \
+    partial previous props range record records remove render revisions sample set target to update updated updates valid";
+
+fn words(name: &str) -> Vec<String> {
+    name.to_case(Case::Snake)
+        .split('_')
+        .map(|w| w.to_string())
+        .collect()
+}
+
+/// The names of the items, enum variants, fields, parameters and variables defined in Rust files
+#[derive(Default)]
+struct DefinedIdentifiers(BTreeSet<String>);
+
+impl<'ast> Visit<'ast> for DefinedIdentifiers {
+    fn visit_signature(&mut self, i: &'ast syn::Signature) {
+        self.0.insert(i.ident.to_string());
+        visit::visit_signature(self, i);
+    }
+
+    fn visit_item_struct(&mut self, i: &'ast syn::ItemStruct) {
+        self.0.insert(i.ident.to_string());
+        visit::visit_item_struct(self, i);
+    }
+
+    fn visit_item_enum(&mut self, i: &'ast syn::ItemEnum) {
+        self.0.insert(i.ident.to_string());
+        visit::visit_item_enum(self, i);
+    }
+
+    fn visit_item_type(&mut self, i: &'ast syn::ItemType) {
+        self.0.insert(i.ident.to_string());
+        visit::visit_item_type(self, i);
+    }
+
+    fn visit_item_mod(&mut self, i: &'ast syn::ItemMod) {
+        self.0.insert(i.ident.to_string());
+        visit::visit_item_mod(self, i);
+    }
+
+    fn visit_item_const(&mut self, i: &'ast syn::ItemConst) {
+        self.0.insert(i.ident.to_string());
+        visit::visit_item_const(self, i);
+    }
+
+    fn visit_variant(&mut self, i: &'ast syn::Variant) {
+        self.0.insert(i.ident.to_string());
+        visit::visit_variant(self, i);
+    }
+
+    fn visit_field(&mut self, i: &'ast syn::Field) {
+        if let Some(ident) = &i.ident {
+            self.0.insert(ident.to_string());
+        }
+        visit::visit_field(self, i);
+    }
+
+    fn visit_pat_ident(&mut self, i: &'ast syn::PatIdent) {
+        self.0.insert(i.ident.to_string());
+        visit::visit_pat_ident(self, i);
+    }
+}
+
+/// All the identifiers of a Rust file, including the ones in the arguments of macro invocations
+#[derive(Default)]
+struct Identifiers(Vec<proc_macro2::Ident>);
+
+impl<'ast> Visit<'ast> for Identifiers {
+    fn visit_ident(&mut self, i: &'ast proc_macro2::Ident) {
+        self.0.push(i.clone());
+    }
+
+    fn visit_macro(&mut self, i: &'ast syn::Macro) {
+        visit::visit_macro(self, i);
+        self.visit_token_stream(i.tokens.clone());
+    }
+}
+
+impl Identifiers {
+    fn visit_token_stream(&mut self, tokens: TokenStream) {
+        for token in tokens {
+            match token {
+                TokenTree::Ident(ident) => self.0.push(ident),
+                TokenTree::Group(group) => self.visit_token_stream(group.stream()),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Replaces the identifiers of the parsed Rust file for which `rename` returns a new name, in place in its contents,
+/// so that the comments and the formatting of the file are kept
+fn rename_rust_identifiers<F: Fn(&str) -> Option<String>>(
+    contents: &str,
+    file: &syn::File,
+    rename: F,
+) -> String {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(contents.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+
+    let mut identifiers = Identifiers::default();
+    identifiers.visit_file(file);
+
+    let mut replacements: Vec<(usize, usize, String)> = identifiers
+        .0
+        .into_iter()
+        .filter_map(|ident| {
+            let identifier = ident.to_string();
+            let new_identifier = rename(&identifier)?;
+            let location = ident.span().start();
+            let line_start = *line_starts.get(location.line.checked_sub(1)?)?;
+            let (column, _) = contents[line_start..].char_indices().nth(location.column)?;
+            let start = line_start + column;
+            (contents.get(start..start + identifier.len()) == Some(identifier.as_str()))
+                .then_some((start, start + identifier.len(), new_identifier))
+        })
+        .collect();
+    replacements.sort_by_key(|(start, _, _)| *start);
+    replacements.dedup_by_key(|(start, _, _)| *start);
+
+    let mut new_contents = contents.to_string();
+    for (start, end, new_identifier) in replacements.into_iter().rev() {
+        new_contents.replace_range(start..end, &new_identifier);
+    }
+    new_contents
+}
+
+/// Renames the words of an entry type name, in its singular and plural forms,
+/// inside the identifiers derived from it while keeping their case
+struct EntryTypeRenamer {
+    /// Pairs of words to replace and their replacement, plural forms first
+    renames: Vec<(Vec<String>, Vec<String>)>,
+    /// The words that can be joined with the words of the entry type in the identifiers derived from it
+    name_words: BTreeSet<String>,
+    protected_names: Vec<String>,
+    identifier_regex: Regex,
+}
+
+impl EntryTypeRenamer {
+    fn new(
+        old_name: &str,
+        new_name: &str,
+        name_words: BTreeSet<String>,
+        protected_names: Vec<String>,
+    ) -> Self {
+        let plural = |name: &str| pluralizer::pluralize(&name.to_case(Case::Snake), 2, false);
+        let singular = |name: &str| pluralizer::pluralize(&name.to_case(Case::Snake), 1, false);
+
+        EntryTypeRenamer {
+            renames: vec![
+                (words(&plural(old_name)), words(&plural(new_name))),
+                (words(&singular(old_name)), words(&singular(new_name))),
+            ],
+            name_words,
+            protected_names,
+            identifier_regex: Regex::new(r"[A-Za-z_][A-Za-z0-9_]*(?:-[A-Za-z][A-Za-z0-9_]*)*")
+                .expect("Failed to create regex"),
+        }
+    }
+
+    fn rename(&self, contents: &str, lowercase_identifiers_case: Case) -> String {
+        // Multi-word names also appear as separate words in texts, eg. "Create Blog Post"
+        let mut contents = contents.to_string();
+        for (old_words, new_words) in &self.renames {
+            if old_words.len() > 1 {
+                for case in [Case::Title, Case::Lower] {
+                    contents = contents.replace(
+                        &old_words.join(" ").to_case(case),
+                        &new_words.join(" ").to_case(case),
+                    );
+                }
+            }
+        }
+
+        self.identifier_regex
+            .replace_all(&contents, |captures: &Captures| {
+                let identifier = captures.get(0).expect("Regex always has a full match");
+                if self.is_protected(&contents, identifier) {
+                    return identifier.as_str().to_string();
+                }
+                self.rename_identifier(identifier.as_str(), lowercase_identifiers_case)
+            })
+            .into_owned()
+    }
+
+    /// Holochain callbacks are always protected, and zome and DNA names are protected in quotes, paths,
+    /// crate names and the signal types of the UI
+    fn is_protected(&self, contents: &str, identifier: regex::Match) -> bool {
+        // Holochain calls its callbacks by their name, so they must keep it even if it contains the words of the entry type
+        if HOLOCHAIN_CALLBACKS.contains(&identifier.as_str()) {
+            return true;
+        }
+        if self
+            .protected_names
+            .iter()
+            .any(|name| identifier.as_str() == format!("{}Signal", name.to_case(Case::Pascal)))
+        {
+            return true;
+        }
+
+        let is_zome_or_dna_name = self.protected_names.iter().any(|name| {
+            identifier.as_str() == name || identifier.as_str() == name.to_case(Case::Kebab)
+        });
+        if !is_zome_or_dna_name {
+            return false;
+        }
+        let before = contents[..identifier.start()].chars().last();
+        let after = &contents[identifier.end()..];
+        matches!(before, Some('"' | '\'' | '`' | '/'))
+            || after.starts_with("::")
+            || after.starts_with('/')
+    }
+
+    fn rename_identifier(&self, identifier: &str, lowercase_identifiers_case: Case) -> String {
+        let separator = if identifier.contains('-') {
+            "-"
+        } else if identifier.contains('_') {
+            "_"
+        } else {
+            ""
+        };
+        let words: Vec<String> = match separator {
+            "" => identifier
+                .to_case(Case::Snake)
+                .split('_')
+                .map(|w| w.to_string())
+                .collect(),
+            _ => identifier.split(separator).map(|w| w.to_string()).collect(),
+        };
+
+        let mut renamed_words: Vec<String> = vec![];
+        let mut renamed = false;
+        let mut i = 0;
+        while i < words.len() {
+            match self.renames.iter().find(|(old_words, _)| {
+                words.len() >= i + old_words.len()
+                    && words[i..i + old_words.len()]
+                        .iter()
+                        .zip(old_words)
+                        .all(|(word, old_word)| word.to_lowercase() == *old_word)
+            }) {
+                Some((old_words, new_words)) => {
+                    renamed = true;
+                    renamed_words.extend(new_words.iter().cloned());
+                    i += old_words.len();
+                }
+                None => {
+                    // Identifiers with other words, like `publish_post_digest`, are not derived from the entry type
+                    let word = words[i].to_lowercase();
+                    if !word.is_empty() && !self.name_words.contains(&word) {
+                        return identifier.to_string();
+                    }
+                    renamed_words.push(words[i].clone());
+                    i += 1;
+                }
+            }
+        }
+
+        if !renamed {
+            return identifier.to_string();
+        }
+
+        let first_char_is_uppercase = identifier
+            .chars()
+            .find(|c| c.is_alphabetic())
+            .is_some_and(|c| c.is_uppercase());
+        let is_all_uppercase = !identifier.chars().any(|c| c.is_lowercase());
+
+        match separator {
+            "" if first_char_is_uppercase && !is_all_uppercase => renamed_words
+                .iter()
+                .map(|w| w.to_case(Case::Pascal))
+                .collect(),
+            "" if is_all_uppercase => renamed_words.join("").to_uppercase(),
+            // A single lowercase word could be in any case, only several words tell that it's camel case
+            "" if words.len() > 1 => renamed_words.join("_").to_case(Case::Camel),
+            "" => renamed_words.join("_").to_case(lowercase_identifiers_case),
+            _ if is_all_uppercase => renamed_words.join(separator).to_uppercase(),
+            _ => renamed_words
+                .iter()
+                .map(|w| match w.chars().next() {
+                    Some(c) if c.is_uppercase() => w.to_case(Case::Pascal),
+                    _ => w.to_lowercase(),
+                })
+                .collect::<Vec<String>>()
+                .join(separator),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn renamer() -> EntryTypeRenamer {
+        let mut name_words: BTreeSet<String> = GENERATED_NAME_WORDS
+            .split_whitespace()
+            .map(|word| word.to_string())
+            .collect();
+        name_words.extend(["comment".to_string(), "comments".to_string()]);
+        EntryTypeRenamer::new(
+            "post",
+            "blog_article",
+            name_words,
+            vec!["posts".to_string(), "posts_integrity".to_string()],
+        )
+    }
+
+    #[test]
+    fn renames_identifiers_derived_from_the_entry_type_name() {
+        let renamer = renamer();
+        let rename = |identifier: &str| renamer.rename_identifier(identifier, Case::Snake);
+
+        assert_eq!(rename("Post"), "BlogArticle");
+        assert_eq!(rename("post"), "blog_article");
+        assert_eq!(rename("get_latest_post"), "get_latest_blog_article");
+        assert_eq!(rename("AllPosts"), "AllBlogArticles");
+        assert_eq!(rename("PostToComments"), "BlogArticleToComments");
+        assert_eq!(rename("postHash"), "blogArticleHash");
+        assert_eq!(rename("create-post"), "create-blog-article");
+        assert_eq!(rename("_post"), "_blog_article");
+        assert_eq!(rename("postal_code"), "postal_code");
+        assert_eq!(rename("publish_post_digest"), "publish_post_digest");
+        assert_eq!(rename("PostFormatter"), "PostFormatter");
+        assert_eq!(
+            renamer.rename("fn post_commit()", Case::Snake),
+            "fn post_commit()"
+        );
+
+        assert_eq!(
+            renamer.rename(
+                r#"use posts_integrity::*; callZome({ zome_name: "posts", fn_name: "get_all_posts" }); let posts: PostsSignal[] = [];"#,
+                Case::Camel
+            ),
+            r#"use posts_integrity::*; callZome({ zome_name: "posts", fn_name: "get_all_blog_articles" }); let blogArticles: PostsSignal[] = [];"#
+        );
+    }
+
+    #[test]
+    fn renames_only_the_rust_identifiers_the_zomes_define() {
+        let renamer = renamer();
+        let integrity = r#"use hdi::prelude::*;
+
+/// A post of the blog
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Post {
+    pub postal_code: String,
+}
+"#;
+        let coordinator = r#"use hdk::prelude::*;
+use posts_integrity::*;
+use reposts::{PostHash, PostFormatter};
+
+/// Creates a post, and publishes its digest
+#[hdk_extern]
+pub fn create_post(post: Post) -> ExternResult<Record> {
+    // The hash of the created post
+    let post_hash = create_entry(&EntryTypes::Post(post.clone()))?;
+    publish_post_digest(PostFormatter::format(&post), PostHash::from(post_hash.clone()))?;
+    let record = get(post_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Could not find the newly created Post".to_string())
+    ))?;
+    Ok(record)
+}
+
+fn publish_post_digest(digest: String, post_hash: PostHash) -> ExternResult<()> {
+    emit_signal(digest)?;
+    Ok(())
+}
+
+#[hdk_extern(infallible)]
+pub fn post_commit(committed_actions: Vec<SignedActionHashed>) {}
+"#;
+
+        let integrity_file: syn::File = syn::parse_str(integrity).unwrap();
+        let coordinator_file: syn::File = syn::parse_str(coordinator).unwrap();
+        let mut defined_identifiers = DefinedIdentifiers::default();
+        defined_identifiers.visit_file(&integrity_file);
+        defined_identifiers.visit_file(&coordinator_file);
+
+        let rename = |identifier: &str| {
+            if !defined_identifiers.0.contains(identifier)
+                || HOLOCHAIN_CALLBACKS.contains(&identifier)
+            {
+                return None;
+            }
+            let renamed = renamer.rename_identifier(identifier, Case::Snake);
+            (renamed != identifier).then_some(renamed)
+        };
+
+        assert_eq!(
+            rename_rust_identifiers(integrity, &integrity_file, rename),
+            r#"use hdi::prelude::*;
+
+/// A post of the blog
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct BlogArticle {
+    pub postal_code: String,
+}
+"#
+        );
+        assert_eq!(
+            rename_rust_identifiers(coordinator, &coordinator_file, rename),
+            r#"use hdk::prelude::*;
+use posts_integrity::*;
+use reposts::{PostHash, PostFormatter};
+
+/// Creates a post, and publishes its digest
+#[hdk_extern]
+pub fn create_blog_article(blog_article: BlogArticle) -> ExternResult<Record> {
+    // The hash of the created post
+    let blog_article_hash = create_entry(&EntryTypes::BlogArticle(blog_article.clone()))?;
+    publish_post_digest(PostFormatter::format(&blog_article), PostHash::from(blog_article_hash.clone()))?;
+    let record = get(blog_article_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Could not find the newly created Post".to_string())
+    ))?;
+    Ok(record)
+}
+
+fn publish_post_digest(digest: String, blog_article_hash: PostHash) -> ExternResult<()> {
+    emit_signal(digest)?;
+    Ok(())
+}
+
+#[hdk_extern(infallible)]
+pub fn post_commit(committed_actions: Vec<SignedActionHashed>) {}
+"#
+        );
+    }
+}
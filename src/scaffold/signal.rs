@@ -0,0 +1,426 @@
+use std::{ffi::OsString, path::Path};
+
+use convert_case::{Case, Casing};
+use quote::{format_ident, quote};
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::{file_exists, insert_file, map_file, map_rust_files, FileTree},
+    reserved_words::check_for_reserved_keywords,
+    templates::{signal::scaffold_signal_templates, ScaffoldedTemplate},
+    utils::check_case,
+};
+
+use super::{
+    app::AppFileTree,
+    capability::add_unrestricted_grant_to_init,
+    entry_type::{
+        definitions::{FieldDefinition, FieldType},
+        integrity::find_ending_match_expr_in_block,
+    },
+    zome::{coordinator::find_extern_function_in_zome, ZomeFileTree},
+};
+
+/// Module of the coordinator zome with the functions that send its remote signals
+const REMOTE_SIGNALS_MODULE: &str = "remote_signals";
+
+/// Adds a variant to the `Signal` enum of the coordinator zome that other agents can send with
+/// `send_<signal_name>`, and that `recv_remote_signal` emits to the UI when it's received
+pub fn scaffold_signal(
+    zome_file_tree: ZomeFileTree,
+    template_file_tree: &FileTree,
+    signal_name: &str,
+    fields: &[FieldDefinition],
+    no_ui: bool,
+    no_interactive: bool,
+) -> ScaffoldResult<ScaffoldedTemplate> {
+    check_case(signal_name, "signal name", Case::Snake)?;
+    check_for_reserved_keywords(signal_name)?;
+
+    for field in fields {
+        if matches!(
+            field.field_type,
+            FieldType::Enum { .. } | FieldType::Struct { .. }
+        ) {
+            return Err(ScaffoldError::InvalidArguments(format!(
+                "The field {} of a signal can't be an enum or a struct",
+                field.field_name
+            )));
+        }
+        // The recipients are part of the input of the send function, and the serde tag of the enum is "type"
+        if field.field_name == "recipients" || field.field_name == "type" {
+            return Err(ScaffoldError::InvalidArguments(format!(
+                "A signal can't have a field named {}",
+                field.field_name
+            )));
+        }
+    }
+
+    let has_recv_remote_signal =
+        find_extern_function_in_zome(&zome_file_tree, "recv_remote_signal")?.is_some();
+
+    let dna_role_name = zome_file_tree.dna_file_tree.dna_manifest.name();
+    let zome_manifest = zome_file_tree.zome_manifest.clone();
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    let pascal_signal_name = signal_name.to_case(Case::Pascal);
+
+    add_signal_to_lib_rs(
+        &mut file_tree,
+        &crate_src_path,
+        &pascal_signal_name,
+        fields,
+        has_recv_remote_signal,
+    )?;
+    add_unrestricted_grant_to_init(
+        &mut file_tree,
+        &crate_src_path,
+        "recv_remote_signal",
+        &[String::from("recv_remote_signal")],
+        "Let other agents send remote signals to this agent",
+    )?;
+
+    let remote_signals_path = crate_src_path.join(format!("{REMOTE_SIGNALS_MODULE}.rs"));
+    if !file_exists(&file_tree, &remote_signals_path) {
+        insert_file(
+            &mut file_tree,
+            &remote_signals_path,
+            "use hdk::prelude::*;\n\nuse crate::Signal;\n",
+        )?;
+        map_file(&mut file_tree, &crate_src_path.join("lib.rs"), |contents| {
+            Ok(format!("pub mod {REMOTE_SIGNALS_MODULE};\n{contents}"))
+        })?;
+    }
+
+    let send_handler: syn::File = syn::parse2(send_signal_handler(signal_name, fields))?;
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        |file_path, mut file| {
+            if file_path == Path::new(&format!("{REMOTE_SIGNALS_MODULE}.rs")) {
+                file.items.extend(send_handler.items.clone());
+            }
+            Ok(file)
+        },
+    )?;
+
+    let ts_variant = signal_ts_variant(&pascal_signal_name, fields);
+
+    let app_file_tree = AppFileTree::get_or_choose(file_tree, None, no_interactive)?;
+    let app_name = app_file_tree.app_manifest.app_name().to_string();
+
+    scaffold_signal_templates(
+        app_file_tree.file_tree(),
+        template_file_tree,
+        &app_name,
+        &dna_role_name,
+        &zome_manifest,
+        signal_name,
+        fields,
+        &ts_variant,
+        no_ui,
+    )
+}
+
+/// Adds the variant to the `Signal` enum and the arm that emits it to `recv_remote_signal`
+fn add_signal_to_lib_rs(
+    file_tree: &mut FileTree,
+    crate_src_path: &Path,
+    pascal_signal_name: &str,
+    fields: &[FieldDefinition],
+    has_recv_remote_signal: bool,
+) -> ScaffoldResult<()> {
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+    let variant_ident = format_ident!("{pascal_signal_name}");
+    let field_names: Vec<syn::Ident> = fields
+        .iter()
+        .map(|f| format_ident!("{}", f.field_name))
+        .collect();
+    let field_types: Vec<_> = fields.iter().map(|f| f.rust_type()).collect();
+
+    let variant: syn::Variant = match fields.is_empty() {
+        true => syn::parse_quote! { #variant_ident },
+        false => syn::parse_quote! { #variant_ident { #(#field_names: #field_types),* } },
+    };
+    let recv_arm: syn::Arm =
+        syn::parse_quote! { Signal::#variant_ident { .. } => emit_signal(signal), };
+
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.to_path_buf()))?,
+        |file_path, mut file| {
+            if file_path != Path::new("lib.rs") {
+                return Ok(file);
+            }
+
+            let signal_enum = file.items.iter_mut().find_map(|item| match item {
+                syn::Item::Enum(item_enum) if item_enum.ident == "Signal" => Some(item_enum),
+                _ => None,
+            });
+            let Some(signal_enum) = signal_enum else {
+                return Err(ScaffoldError::MalformedFile(
+                    file_path,
+                    String::from("the coordinator zome has no Signal enum"),
+                ));
+            };
+            if signal_enum
+                .variants
+                .iter()
+                .any(|v| v.ident == pascal_signal_name)
+            {
+                return Err(ScaffoldError::InvalidArguments(format!(
+                    "The signal {pascal_signal_name} already exists"
+                )));
+            }
+            signal_enum.variants.push(variant.clone());
+
+            if !has_recv_remote_signal {
+                let recv_remote_signal: syn::ItemFn = syn::parse_quote! {
+                    /// Emits the signals that other agents send to this agent to the UI, only for the variants meant to be sent remotely
+                    #[hdk_extern]
+                    pub fn recv_remote_signal(signal: Signal) -> ExternResult<()> {
+                        match signal {
+                            _ => Ok(()),
+                        }
+                    }
+                };
+                file.items.push(syn::Item::Fn(recv_remote_signal));
+            }
+
+            for item in &mut file.items {
+                let syn::Item::Fn(item_fn) = item else {
+                    continue;
+                };
+
+                if item_fn.sig.ident == "recv_remote_signal" {
+                    if let Some(expr_match) = find_ending_match_expr_in_block(&mut item_fn.block) {
+                        expr_match
+                            .arms
+                            .insert(expr_match.arms.len().saturating_sub(1), recv_arm.clone());
+                    }
+                }
+            }
+
+            Ok(file)
+        },
+    )
+    .map_err(|e| match e {
+        ScaffoldError::MalformedFile(path, error) => {
+            ScaffoldError::MalformedFile(crate_src_path.join(path), error)
+        }
+        _ => e,
+    })
+}
+
+fn send_signal_handler(signal_name: &str, fields: &[FieldDefinition]) -> proc_macro2::TokenStream {
+    let pascal_signal_name = signal_name.to_case(Case::Pascal);
+    let variant_ident = format_ident!("{pascal_signal_name}");
+    let input_struct = format_ident!("Send{pascal_signal_name}Input");
+    let function_name = format_ident!("send_{signal_name}");
+    let field_names: Vec<syn::Ident> = fields
+        .iter()
+        .map(|f| format_ident!("{}", f.field_name))
+        .collect();
+    let field_types: Vec<_> = fields.iter().map(|f| f.rust_type()).collect();
+
+    let signal = match fields.is_empty() {
+        true => quote! { Signal::#variant_ident },
+        false => quote! { Signal::#variant_ident { #(#field_names: input.#field_names),* } },
+    };
+
+    quote! {
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct #input_struct {
+            pub recipients: Vec<AgentPubKey>,
+            #(pub #field_names: #field_types),*
+        }
+
+        #[hdk_extern]
+        pub fn #function_name(input: #input_struct) -> ExternResult<()> {
+            send_remote_signal(#signal, input.recipients)
+        }
+    }
+}
+
+/// The member of the typescript union of the signals of the zome for this signal
+fn signal_ts_variant(pascal_signal_name: &str, fields: &[FieldDefinition]) -> String {
+    let mut ts_variant = format!("{{\n  type: '{pascal_signal_name}';\n");
+    for field in fields {
+        ts_variant.push_str(&field.ts_field_codegen());
+        ts_variant.push('\n');
+    }
+    ts_variant.push('}');
+    ts_variant
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_tree::file_content;
+    use crate::scaffold::{
+        entry_type::definitions::Cardinality, web_app::template_type::TemplateType,
+    };
+    use build_fs_tree::{dir, file};
+    use holochain_types::prelude::ZomeManifest;
+    use mr_bundle::Location;
+    use quote::ToTokens;
+    use std::path::PathBuf;
+
+    fn message_field() -> FieldDefinition {
+        FieldDefinition {
+            field_name: "message".to_string(),
+            field_type: FieldType::String,
+            widget: None,
+            cardinality: Cardinality::Single,
+            linked_from: None,
+            constraints: vec![],
+        }
+    }
+
+    #[test]
+    fn test_send_signal_handler() {
+        let file: syn::File = syn::parse2(send_signal_handler("ping", &[message_field()])).unwrap();
+        let file = file.to_token_stream().to_string();
+
+        assert!(file.contains(
+            "pub struct SendPingInput { pub recipients : Vec < AgentPubKey > , pub message : String }"
+        ));
+        assert!(file.contains("pub fn send_ping (input : SendPingInput) -> ExternResult < () >"));
+        assert!(file.contains(
+            "send_remote_signal (Signal :: Ping { message : input . message } , input . recipients)"
+        ));
+
+        assert_eq!(
+            signal_ts_variant("Ping", &[message_field()]),
+            "{\n  type: 'Ping';\n  message: string;\n}"
+        );
+    }
+
+    #[test]
+    fn test_add_signal_to_lib_rs() {
+        let mut file_tree: FileTree = dir! {
+            "src" => dir! {
+                "lib.rs" => file!(quote! {
+                    #[derive(Serialize, Deserialize, Debug)]
+                    #[serde(tag = "type")]
+                    pub enum Signal {
+                        LinkCreated { action: SignedActionHashed }
+                    }
+                }
+                .to_string())
+            }
+        };
+
+        add_signal_to_lib_rs(
+            &mut file_tree,
+            Path::new("src"),
+            "Ping",
+            &[message_field()],
+            false,
+        )
+        .unwrap();
+
+        let lib_rs = file_content(&file_tree, Path::new("src/lib.rs")).unwrap();
+        let lib_rs: syn::File = syn::parse_str(&lib_rs).unwrap();
+        let lib_rs = lib_rs.to_token_stream().to_string();
+        assert!(lib_rs.contains(
+            "pub enum Signal { LinkCreated { action : SignedActionHashed } , Ping { message : String } , }"
+        ));
+        assert!(lib_rs.contains(
+            "match signal { Signal :: Ping { .. } => emit_signal (signal) , _ => Ok (()) , }"
+        ));
+
+        // The new arms go before the catch-all arm of the existing function
+        add_signal_to_lib_rs(&mut file_tree, Path::new("src"), "Pong", &[], true).unwrap();
+        let lib_rs = file_content(&file_tree, Path::new("src/lib.rs")).unwrap();
+        let lib_rs: syn::File = syn::parse_str(&lib_rs).unwrap();
+        assert!(lib_rs.to_token_stream().to_string().contains(
+            "Signal :: Ping { .. } => emit_signal (signal) , Signal :: Pong { .. } => emit_signal (signal) , _ => Ok (()) ,"
+        ));
+
+        assert!(matches!(
+            add_signal_to_lib_rs(&mut file_tree, Path::new("src"), "Ping", &[], true),
+            Err(ScaffoldError::InvalidArguments(_))
+        ));
+    }
+
+    #[test]
+    fn test_signal_ts_variant_is_added_to_the_zome_signal_type() {
+        let zome_manifest = ZomeManifest {
+            name: "posts".into(),
+            hash: None,
+            location: Location::Bundled(PathBuf::from("posts.wasm")),
+            dependencies: None,
+            dylib: None,
+        };
+        let types_path = PathBuf::from("ui/src/forum/posts/types.ts");
+        let app_file_tree = |types_ts: &str| -> FileTree {
+            dir! {
+                "ui" => dir! {
+                    "src" => dir! {
+                        "forum" => dir! {
+                            "posts" => dir! {
+                                "types.ts" => file!(types_ts)
+                            }
+                        }
+                    }
+                }
+            }
+        };
+        let scaffold = |template_type: TemplateType, types_ts: &str| {
+            scaffold_signal_templates(
+                app_file_tree(types_ts),
+                &template_type.file_tree().unwrap(),
+                "forum",
+                "forum",
+                &zome_manifest,
+                "ping",
+                &[message_field()],
+                &signal_ts_variant("Ping", &[message_field()]),
+                false,
+            )
+        };
+
+        let types_ts = r#"import { ActionHash } from "@holochain/client";
+
+export type PostsSignal = {
+  type: "LinkDeleted";
+  link_type: string;
+};
+"#;
+        let scaffolded = scaffold(TemplateType::Lit, types_ts).unwrap();
+        let types_ts = file_content(&scaffolded.file_tree, &types_path).unwrap();
+        assert!(types_ts.contains("type: \"Ping\";\n  message: string;\n}"));
+        assert!(types_ts.contains("type: \"LinkDeleted\""));
+        assert!(file_exists(
+            &scaffolded.file_tree,
+            Path::new("ui/src/forum/posts/signals.ts")
+        ));
+
+        // Also with the union formatted with a leading separator
+        let types_ts = "import { ActionHash } from \"@holochain/client\";\n\nexport type PostsSignal =\n  | {\n    type: \"LinkDeleted\";\n  };\n";
+        let scaffolded = scaffold(TemplateType::Lit, types_ts).unwrap();
+        let types_ts = file_content(&scaffolded.file_tree, &types_path).unwrap();
+        assert!(types_ts.contains("type: \"Ping\""));
+        assert!(types_ts.contains("type: \"LinkDeleted\""));
+
+        // The signal type of the zome can't be silently left out
+        assert!(scaffold(
+            TemplateType::Lit,
+            "import { ActionHash } from \"@holochain/client\";\n"
+        )
+        .is_err());
+
+        // Templates without the UI of the coordinator zomes have no types to add the signal to
+        let scaffolded = scaffold(TemplateType::Vanilla, "").unwrap();
+        assert!(!file_exists(
+            &scaffolded.file_tree,
+            Path::new("ui/src/forum/posts/signals.ts")
+        ));
+    }
+}
@@ -7,6 +7,7 @@ use serde::Serialize;
 use crate::{
     error::{ScaffoldError, ScaffoldResult},
     file_tree::FileTree,
+    utils::check_interactive,
 };
 
 /// Represents different package managers that can be used.
@@ -31,13 +32,14 @@ pub enum SubCommand {
 }
 
 impl PackageManager {
-    pub fn choose() -> ScaffoldResult<PackageManager> {
+    pub fn choose(no_interactive: bool) -> ScaffoldResult<PackageManager> {
         let managers = [
             PackageManager::Bun,
             PackageManager::Npm,
             PackageManager::Pnpm,
             PackageManager::Yarn,
         ];
+        check_interactive("the `--package-manager` option", no_interactive)?;
         let selection = Select::with_theme(&ColorfulTheme::default())
             .with_prompt("Choose a package manager: (Use arrow-keys. Return to submit)")
             .default(1)
@@ -130,10 +132,13 @@ impl std::str::FromStr for PackageManager {
     }
 }
 
-impl TryFrom<&FileTree> for PackageManager {
-    type Error = ScaffoldError;
-
-    fn try_from(app_file_tree: &FileTree) -> ScaffoldResult<PackageManager> {
+impl PackageManager {
+    /// Gets the package manager of the app from its lockfile, or prompts the user to choose one
+    /// if the app has none
+    pub fn get_or_choose(
+        app_file_tree: &FileTree,
+        no_interactive: bool,
+    ) -> ScaffoldResult<PackageManager> {
         if PackageManager::lockfile_exists(app_file_tree, Path::new("bun.lockb")) {
             Ok(PackageManager::Bun)
         } else if PackageManager::lockfile_exists(app_file_tree, Path::new("package-lock.json")) {
@@ -143,7 +148,7 @@ impl TryFrom<&FileTree> for PackageManager {
         } else if PackageManager::lockfile_exists(app_file_tree, Path::new("yarn.lock")) {
             Ok(PackageManager::Yarn)
         } else {
-            PackageManager::choose()
+            PackageManager::choose(no_interactive)
         }
     }
 }
@@ -180,37 +185,47 @@ mod tests {
     }
 
     #[test]
-    fn test_try_from_file_tree_bun() {
+    fn test_get_or_choose_bun() {
         let app_file_tree = setup_filetree("bun.lockb");
-        let package_manager = PackageManager::try_from(&app_file_tree).unwrap();
+        let package_manager = PackageManager::get_or_choose(&app_file_tree, true).unwrap();
         assert_eq!(package_manager, PackageManager::Bun);
     }
 
     #[test]
-    fn test_try_from_file_tree_npm() {
+    fn test_get_or_choose_npm() {
         let app_file_tree = setup_filetree("package-lock.json");
-        let package_manager = PackageManager::try_from(&app_file_tree).unwrap();
+        let package_manager = PackageManager::get_or_choose(&app_file_tree, true).unwrap();
         assert_eq!(package_manager, PackageManager::Npm);
     }
 
     #[test]
-    fn test_try_from_file_tree_yarn() {
+    fn test_get_or_choose_yarn() {
         let app_file_tree = setup_filetree("yarn.lock");
-        let package_manager = PackageManager::try_from(&app_file_tree).unwrap();
+        let package_manager = PackageManager::get_or_choose(&app_file_tree, true).unwrap();
         assert_eq!(package_manager, PackageManager::Yarn);
     }
 
     #[test]
-    fn test_try_from_file_tree_pnpm() {
+    fn test_get_or_choose_pnpm() {
         let app_file_tree = setup_filetree("pnpm-lock.yaml");
-        let package_manager = PackageManager::try_from(&app_file_tree).unwrap();
+        let package_manager = PackageManager::get_or_choose(&app_file_tree, true).unwrap();
         assert_eq!(package_manager, PackageManager::Pnpm);
     }
 
+    #[test]
+    fn test_get_or_choose_without_lockfile_non_interactively() {
+        let app_file_tree = setup_filetree("Cargo.lock");
+        assert!(matches!(
+            PackageManager::get_or_choose(&app_file_tree, true),
+            Err(ScaffoldError::MissingArgumentInNonInteractiveMode(argument))
+                if argument == "the `--package-manager` option"
+        ));
+    }
+
     #[test]
     fn test_run_with_npm() {
         let app_file_tree = setup_filetree("package-lock.json");
-        let package_manager = PackageManager::try_from(&app_file_tree).unwrap();
+        let package_manager = PackageManager::get_or_choose(&app_file_tree, true).unwrap();
         let expected_command = "npm install";
         let actual_command = package_manager.run_command_string(SubCommand::Install, None);
         assert_eq!(expected_command, actual_command);
@@ -224,7 +239,7 @@ mod tests {
     #[test]
     fn test_run_with_yarn() {
         let app_file_tree = setup_filetree("yarn.lock");
-        let package_manager = PackageManager::try_from(&app_file_tree).unwrap();
+        let package_manager = PackageManager::get_or_choose(&app_file_tree, true).unwrap();
         let expected_command = "yarn install";
         let actual_command = package_manager.run_command_string(SubCommand::Install, None);
         assert_eq!(expected_command, actual_command);
@@ -238,7 +253,7 @@ mod tests {
     #[test]
     fn test_run_with_pnpm() {
         let app_file_tree = setup_filetree("pnpm-lock.yaml");
-        let package_manager = PackageManager::try_from(&app_file_tree).unwrap();
+        let package_manager = PackageManager::get_or_choose(&app_file_tree, true).unwrap();
         let expected_command = "pnpm install";
         let actual_command = package_manager.run_command_string(SubCommand::Install, None);
         assert_eq!(expected_command, actual_command);
@@ -252,7 +267,7 @@ mod tests {
     #[test]
     fn test_run_with_bun() {
         let app_file_tree = setup_filetree("bun.lockb");
-        let package_manager = PackageManager::try_from(&app_file_tree).unwrap();
+        let package_manager = PackageManager::get_or_choose(&app_file_tree, true).unwrap();
         let expected_command = "bun install";
         let actual_command = package_manager.run_command_string(SubCommand::Install, None);
         assert_eq!(expected_command, actual_command);
@@ -10,6 +10,7 @@ use crate::{
         dir_exists, file_content, file_exists, load_directory_into_memory,
         template_dirs_to_file_tree, FileTree,
     },
+    utils::check_interactive,
 };
 
 static LIT_TEMPLATES: Dir<'static> =
@@ -76,7 +77,7 @@ impl TemplateType {
         template_dirs_to_file_tree(ui_framework_dir, &GENERIC_TEMPLATES)
     }
 
-    pub fn choose() -> ScaffoldResult<TemplateType> {
+    pub fn choose(no_interactive: bool) -> ScaffoldResult<TemplateType> {
         let frameworks = [
             TemplateType::Lit,
             TemplateType::Svelte,
@@ -85,6 +86,7 @@ impl TemplateType {
             TemplateType::Vanilla,
             TemplateType::Headless,
         ];
+        check_interactive("the `--template` option", no_interactive)?;
         let selection = Select::with_theme(&ColorfulTheme::default())
             .with_prompt("Choose UI framework: (Use arrow-keys. Return to submit)")
             .default(0)
@@ -93,13 +95,14 @@ impl TemplateType {
         Ok(frameworks[selection].clone())
     }
 
-    pub fn choose_non_vanilla() -> ScaffoldResult<TemplateType> {
+    pub fn choose_non_vanilla(no_interactive: bool) -> ScaffoldResult<TemplateType> {
         let frameworks = [
             TemplateType::Lit,
             TemplateType::Svelte,
             TemplateType::React,
             TemplateType::Vue,
         ];
+        check_interactive("the `--template` option", no_interactive)?;
         let selection = Select::with_theme(&ColorfulTheme::default())
             .with_prompt("Choose UI framework: (Use arrow-keys. Return to submit)")
             .default(0)
@@ -108,7 +111,7 @@ impl TemplateType {
         Ok(frameworks[selection].clone())
     }
 
-    pub fn choose_non_headless() -> ScaffoldResult<TemplateType> {
+    pub fn choose_non_headless(no_interactive: bool) -> ScaffoldResult<TemplateType> {
         let frameworks = [
             TemplateType::Lit,
             TemplateType::Svelte,
@@ -116,6 +119,7 @@ impl TemplateType {
             TemplateType::Vue,
             TemplateType::Vanilla,
         ];
+        check_interactive("the `--template` option", no_interactive)?;
         let selection = Select::with_theme(&ColorfulTheme::default())
             .with_prompt("Choose UI framework: (Use arrow-keys. Return to submit)")
             .default(0)
@@ -131,12 +135,10 @@ impl From<PathBuf> for TemplateType {
     }
 }
 
-impl TryFrom<&FileTree> for TemplateType {
-    type Error = ScaffoldError;
-
+impl TemplateType {
     /// Try to get ui framework from app file tree, if the ui framework cannot be inferred, then
     /// the user will be prompted to choose one via `TemplateType::choose`
-    fn try_from(app_file_tree: &FileTree) -> Result<Self, Self::Error> {
+    pub fn get_or_choose(app_file_tree: &FileTree, no_interactive: bool) -> ScaffoldResult<Self> {
         let ui_package_json_path = PathBuf::from("ui/package.json");
         if file_exists(app_file_tree, &ui_package_json_path) {
             let v: Vec<OsString> = ui_package_json_path
@@ -161,7 +163,7 @@ impl TryFrom<&FileTree> for TemplateType {
                 return Ok(TemplateType::Vanilla);
             }
         }
-        TemplateType::choose()
+        TemplateType::choose(no_interactive)
     }
 }
 
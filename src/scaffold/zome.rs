@@ -1,6 +1,7 @@
 use convert_case::Case;
 use dialoguer::{theme::ColorfulTheme, Confirm, Select};
 use mr_bundle::Location;
+use quote::format_ident;
 use regex::Regex;
 use std::{
     ffi::OsString,
@@ -8,13 +9,13 @@ use std::{
 };
 
 use crate::{
-    file_tree::{build_file_tree, file_exists, insert_file_tree_in_dir, FileTree},
+    file_tree::{file_exists, insert_file_tree_in_dir, FileTree},
     reserved_words::check_for_reserved_keywords,
     templates::{
         coordinator::scaffold_coordinator_zome_templates,
         integrity::scaffold_integrity_zome_templates, ScaffoldedTemplate,
     },
-    utils::{input_with_case_and_initial_text, unparse_pretty},
+    utils::{check_interactive, input_with_case_and_initial_text, unparse_pretty},
     versions,
 };
 use build_fs_tree::{dir, file};
@@ -51,6 +52,7 @@ impl ZomeFileTree {
     pub fn get_or_choose_integrity(
         dna_file_tree: DnaFileTree,
         integrity_zome_name: Option<&str>,
+        no_interactive: bool,
     ) -> ScaffoldResult<ZomeFileTree> {
         let integrity_zomes = match dna_file_tree.dna_manifest.clone() {
             DnaManifest::V1(v1) => v1.integrity.zomes.clone(),
@@ -68,9 +70,11 @@ impl ZomeFileTree {
                         dna_file_tree.dna_manifest.name(),
                     ))
             }
-            (_, None) => {
-                choose_integrity_zome(&dna_file_tree.dna_manifest.name(), &integrity_zomes)
-            }
+            (_, None) => choose_integrity_zome(
+                &dna_file_tree.dna_manifest.name(),
+                &integrity_zomes,
+                no_interactive,
+            ),
             (_, Some(name)) => integrity_zomes
                 .into_iter()
                 .find(|zome| zome.name.0.to_string().eq(name))
@@ -82,6 +86,39 @@ impl ZomeFileTree {
         ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)
     }
 
+    pub fn get_or_choose_coordinator(
+        dna_file_tree: DnaFileTree,
+        coordinator_zome_name: Option<&str>,
+        no_interactive: bool,
+    ) -> ScaffoldResult<ZomeFileTree> {
+        let coordinator_zomes = match dna_file_tree.dna_manifest.clone() {
+            DnaManifest::V1(v1) => v1.coordinator.zomes.clone(),
+        };
+
+        let zome_manifest =
+            match (coordinator_zomes.len(), coordinator_zome_name) {
+                (0, None) => Err(ScaffoldError::NoCoordinatorZomesFound(
+                    dna_file_tree.dna_manifest.name(),
+                )),
+                (1, None) => coordinator_zomes.into_iter().last().ok_or(
+                    ScaffoldError::NoCoordinatorZomesFound(dna_file_tree.dna_manifest.name()),
+                ),
+                (_, None) => choose_coordinator_zome(
+                    &dna_file_tree.dna_manifest.name(),
+                    &coordinator_zomes,
+                    no_interactive,
+                ),
+                (_, Some(name)) => coordinator_zomes
+                    .into_iter()
+                    .find(|zome| zome.name.0.to_string().eq(name))
+                    .ok_or(ScaffoldError::CoordinatorZomeNotFound(
+                        name.to_owned(),
+                        dna_file_tree.dna_manifest.name(),
+                    )),
+            }?;
+        ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)
+    }
+
     pub fn from_zome_manifest(
         dna_file_tree: DnaFileTree,
         zome_manifest: ZomeManifest,
@@ -140,6 +177,19 @@ pub fn integrity_zome_name(coordinator_zome_name: &str) -> String {
     format!("{}_integrity", coordinator_zome_name)
 }
 
+/// The path segments of the module of the given file of the src folder, eg. `posts` for "posts/mod.rs"
+pub fn module_path(file_path: &Path) -> Vec<syn::Ident> {
+    let mut segments: Vec<syn::Ident> = file_path
+        .with_extension("")
+        .iter()
+        .map(|s| format_ident!("{}", s.to_string_lossy().to_string()))
+        .collect();
+    if segments.last().is_some_and(|s| s == "lib" || s == "mod") {
+        segments.pop();
+    }
+    segments
+}
+
 pub fn iter_all_eq<T: PartialEq>(iter: impl IntoIterator<Item = T>) -> Option<T> {
     let mut iter = iter.into_iter();
     let first = iter.next()?;
@@ -149,12 +199,14 @@ pub fn iter_all_eq<T: PartialEq>(iter: impl IntoIterator<Item = T>) -> Option<T>
 fn choose_integrity_zome(
     dna_name: &str,
     integrity_zomes: &[ZomeManifest],
+    no_interactive: bool,
 ) -> ScaffoldResult<ZomeManifest> {
     let integrity_zome_names: Vec<String> = integrity_zomes
         .iter()
         .map(|z| z.name.0.to_string())
         .collect();
 
+    check_interactive("the `--zome` option", no_interactive)?;
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt(format!(
             "Multiple integrity zomes were found in DNA {}, choose one:",
@@ -167,6 +219,29 @@ fn choose_integrity_zome(
     Ok(integrity_zomes[selection].clone())
 }
 
+fn choose_coordinator_zome(
+    dna_name: &str,
+    coordinator_zomes: &[ZomeManifest],
+    no_interactive: bool,
+) -> ScaffoldResult<ZomeManifest> {
+    let coordinator_zome_names: Vec<String> = coordinator_zomes
+        .iter()
+        .map(|z| z.name.0.to_string())
+        .collect();
+
+    check_interactive("the `--zome` option", no_interactive)?;
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "Multiple coordinator zomes were found in DNA {}, choose one:",
+            dna_name
+        ))
+        .default(0)
+        .items(&coordinator_zome_names[..])
+        .interact()?;
+
+    Ok(coordinator_zomes[selection].clone())
+}
+
 /// Tries to guess the location of the integrity zomes
 ///
 /// Procedure:
@@ -401,28 +476,34 @@ pub fn scaffold_integrity_zome(
     template_file_tree: &FileTree,
     zome_name: &str,
     path: &Option<PathBuf>,
+    no_interactive: bool,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
     let path_to_scaffold_in = match path {
         Some(p) => p.clone(),
-        None => match try_to_guess_integrity_zomes_location(&dna_file_tree)? {
-            Some(p) => {
-                if Confirm::with_theme(&ColorfulTheme::default())
-                    .with_prompt(format!("Scaffold integrity zome in folder {:?}?", p))
-                    .interact()?
-                {
-                    p
-                } else {
-                    choose_directory_path(
-                        &String::from("Where should the integrity zome be scaffolded instead?"),
-                        dna_file_tree.file_tree_ref(),
-                    )?
+        None => {
+            check_interactive("the `--integrity <path>` option", no_interactive)?;
+            match try_to_guess_integrity_zomes_location(&dna_file_tree)? {
+                Some(p) => {
+                    if Confirm::with_theme(&ColorfulTheme::default())
+                        .with_prompt(format!("Scaffold integrity zome in folder {:?}?", p))
+                        .interact()?
+                    {
+                        p
+                    } else {
+                        choose_directory_path(
+                            &String::from("Where should the integrity zome be scaffolded instead?"),
+                            dna_file_tree.file_tree_ref(),
+                            no_interactive,
+                        )?
+                    }
                 }
+                None => choose_directory_path(
+                    &String::from("Where should the integrity zome be scaffolded?"),
+                    dna_file_tree.file_tree_ref(),
+                    no_interactive,
+                )?,
             }
-            None => choose_directory_path(
-                &String::from("Where should the integrity zome be scaffolded?"),
-                dna_file_tree.file_tree_ref(),
-            )?,
-        },
+        }
     };
 
     scaffold_integrity_zome_with_path(
@@ -481,24 +562,25 @@ pub fn scaffold_coordinator_zome(
     zome_name: &str,
     dependencies: Option<&Vec<String>>,
     path: &Option<PathBuf>,
+    no_interactive: bool,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
     let prompt = String::from("Where should the coordinator zome be scaffolded?");
 
     let path_to_scaffold_in = match path {
         Some(p) => p.clone(),
-        None => match try_to_guess_coordinator_zomes_location(&dna_file_tree)? {
-            Some(p) => {
-                if Confirm::with_theme(&ColorfulTheme::default())
-                    .with_prompt(format!("Scaffold coordinator zome in {:?}?", p))
-                    .interact()?
+        None => {
+            check_interactive("the `--coordinator <path>` option", no_interactive)?;
+            match try_to_guess_coordinator_zomes_location(&dna_file_tree)? {
+                Some(p)
+                    if Confirm::with_theme(&ColorfulTheme::default())
+                        .with_prompt(format!("Scaffold coordinator zome in {:?}?", p))
+                        .interact()? =>
                 {
                     p
-                } else {
-                    choose_directory_path(&prompt, dna_file_tree.file_tree_ref())?
                 }
+                _ => choose_directory_path(&prompt, dna_file_tree.file_tree_ref(), no_interactive)?,
             }
-            None => choose_directory_path(&prompt, dna_file_tree.file_tree_ref())?,
-        },
+        }
     };
 
     scaffold_coordinator_zome_in_path(
@@ -514,14 +596,16 @@ pub fn scaffold_zome_pair(
     app_file_tree: FileTree,
     template_file_tree: FileTree,
     dna_name: &str,
-) -> Result<(), ScaffoldError> {
-    let mut dna_file_tree = DnaFileTree::get_or_choose(app_file_tree, Some(dna_name))?;
+    no_interactive: bool,
+) -> Result<FileTree, ScaffoldError> {
+    let mut dna_file_tree =
+        DnaFileTree::get_or_choose(app_file_tree, Some(dna_name), no_interactive)?;
     let dna_manifest_path = dna_file_tree.dna_manifest_path.clone();
 
     let zome_name = input_with_case_and_initial_text(
             "Enter coordinator zome name (snake_case):\n(The integrity zome will automatically be named '{name of coordinator zome}_integrity')\n",
             Case::Snake,
-            dna_name
+            dna_name, no_interactive
         )?;
 
     let integrity_zome_name = integrity_zome_name(&zome_name);
@@ -530,6 +614,7 @@ pub fn scaffold_zome_pair(
         &template_file_tree,
         &integrity_zome_name,
         &None,
+        no_interactive,
     )?;
     dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
 
@@ -539,8 +624,8 @@ pub fn scaffold_zome_pair(
         &zome_name,
         Some(&vec![integrity_zome_name]),
         &None,
+        no_interactive,
     )?;
 
-    build_file_tree(file_tree, ".")?;
-    Ok(())
+    Ok(file_tree)
 }
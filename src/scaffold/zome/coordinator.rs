@@ -1,15 +1,16 @@
-use std::{collections::BTreeMap, ffi::OsString};
+use std::{collections::BTreeMap, ffi::OsString, path::Path};
 
 use dialoguer::{theme::ColorfulTheme, Select};
 use holochain_types::prelude::ZomeManifest;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
-use syn::ItemFn;
+use syn::{visit::Visit, ItemFn};
 
 use crate::{
     error::{ScaffoldError, ScaffoldResult},
-    file_tree::find_map_rust_files,
-    scaffold::dna::DnaFileTree,
+    file_tree::{find_map_rust_files, map_rust_files, FileTree},
+    scaffold::{dna::DnaFileTree, entry_type::integrity::find_ending_match_expr_in_block},
+    utils::check_interactive,
 };
 
 use super::ZomeFileTree;
@@ -89,9 +90,20 @@ pub fn initial_lib_rs(dependencies: Option<&Vec<String>>) -> TokenStream {
     }
 }
 
+/// Functions that Holochain calls by their name
+pub const HOLOCHAIN_CALLBACKS: [&str; 6] = [
+    "init",
+    "validate",
+    "genesis_self_check",
+    "post_commit",
+    "recv_remote_signal",
+    "migrate_agent",
+];
+
 fn choose_extern_function(
     functions_by_zome: &BTreeMap<String, Vec<ItemFn>>,
     prompt: &str,
+    no_interactive: bool,
 ) -> ScaffoldResult<(String, ItemFn)> {
     let all_functions: Vec<(String, ItemFn)> = functions_by_zome
         .iter()
@@ -107,6 +119,7 @@ fn choose_extern_function(
         .map(|(z, f)| format!(r#""{}", in zome "{}""#, f.sig.ident, z))
         .collect();
 
+    check_interactive(&format!("answer to \"{prompt}\""), no_interactive)?;
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .default(0)
@@ -121,6 +134,7 @@ pub fn find_extern_function_or_choose(
     coordinator_zomes: &Vec<ZomeManifest>,
     fn_name_to_find: &str,
     prompt: &str,
+    no_interactive: bool,
 ) -> ScaffoldResult<(ZomeManifest, ItemFn)> {
     let mut functions_by_zome: BTreeMap<String, Vec<ItemFn>> = BTreeMap::new();
 
@@ -143,7 +157,7 @@ pub fn find_extern_function_or_choose(
         functions_by_zome.insert(coordinator_zome.name.to_string(), all_extern_functions);
     }
 
-    let (zome_name, fn_name) = choose_extern_function(&functions_by_zome, prompt)?;
+    let (zome_name, fn_name) = choose_extern_function(&functions_by_zome, prompt, no_interactive)?;
 
     let chosen_zome = coordinator_zomes
         .iter()
@@ -229,3 +243,178 @@ pub fn find_all_extern_functions(zome_file_tree: &ZomeFileTree) -> ScaffoldResul
 
     Ok(hdk_extern_instances.values().flatten().cloned().collect())
 }
+
+/// Whether the statement calls the function with the given name anywhere, eg. `update_entry`
+pub fn calls_function(stmt: &syn::Stmt, fn_name: &str) -> bool {
+    struct FunctionCallFinder<'a> {
+        fn_name: &'a str,
+        found: bool,
+    }
+
+    impl<'ast> Visit<'ast> for FunctionCallFinder<'_> {
+        fn visit_expr_call(&mut self, expr_call: &'ast syn::ExprCall) {
+            if let syn::Expr::Path(expr_path) = expr_call.func.as_ref() {
+                if expr_path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == self.fn_name)
+                {
+                    self.found = true;
+                }
+            }
+            syn::visit::visit_expr_call(self, expr_call);
+        }
+    }
+
+    let mut finder = FunctionCallFinder {
+        fn_name,
+        found: false,
+    };
+    finder.visit_stmt(stmt);
+    finder.found
+}
+
+/// The `T` of a function returning `ExternResult<T>`
+pub fn extern_output_type(item_fn: &ItemFn) -> ScaffoldResult<syn::Type> {
+    if let syn::ReturnType::Type(_, ty) = &item_fn.sig.output {
+        if let syn::Type::Path(type_path) = ty.as_ref() {
+            if let Some(segment) = type_path.path.segments.last() {
+                if segment.ident == "ExternResult" {
+                    if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                        if let Some(syn::GenericArgument::Type(output)) = args.args.first() {
+                            return Ok(output.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Err(ScaffoldError::InvalidArguments(format!(
+        "The function {} doesn't return an ExternResult, so it can't be called from other zomes or agents",
+        item_fn.sig.ident
+    )))
+}
+
+/// The first segment of the relative paths of the types of the input and the output of the extern function,
+/// including the ones in their generic arguments, eg. `Vec`, `Post` and `Record` for `(Vec<Post>) -> Option<Record>`
+pub fn signature_type_idents(item_fn: &ItemFn) -> ScaffoldResult<Vec<syn::Ident>> {
+    #[derive(Default)]
+    struct TypeIdentsCollector {
+        idents: Vec<syn::Ident>,
+    }
+
+    impl<'ast> Visit<'ast> for TypeIdentsCollector {
+        fn visit_type_path(&mut self, type_path: &'ast syn::TypePath) {
+            if type_path.qself.is_none() && type_path.path.leading_colon.is_none() {
+                if let Some(first) = type_path.path.segments.first() {
+                    if !self.idents.contains(&first.ident) {
+                        self.idents.push(first.ident.clone());
+                    }
+                }
+            }
+            syn::visit::visit_type_path(self, type_path);
+        }
+    }
+
+    let mut collector = TypeIdentsCollector::default();
+    for input in &item_fn.sig.inputs {
+        if let syn::FnArg::Typed(pat_type) = input {
+            collector.visit_type(&pat_type.ty);
+        }
+    }
+    collector.visit_type(&extern_output_type(item_fn)?);
+
+    Ok(collector.idents)
+}
+
+/// The path and the last element of each name, rename or glob imported by the use tree
+pub fn use_tree_leaves(
+    tree: &syn::UseTree,
+    prefix: Vec<syn::Ident>,
+) -> Vec<(Vec<syn::Ident>, syn::UseTree)> {
+    match tree {
+        syn::UseTree::Path(use_path) => {
+            let mut prefix = prefix;
+            prefix.push(use_path.ident.clone());
+            use_tree_leaves(&use_path.tree, prefix)
+        }
+        syn::UseTree::Group(use_group) => use_group
+            .items
+            .iter()
+            .flat_map(|tree| use_tree_leaves(tree, prefix.clone()))
+            .collect(),
+        leaf => vec![(prefix, leaf.clone())],
+    }
+}
+
+/// Removes the given variants from the `Signal` enum of the coordinator zome, together with the arms
+/// of `signal_action` that match the given actions and the given helper functions
+pub fn remove_signals_from_coordinator(
+    file_tree: &mut FileTree,
+    crate_src_path: &Path,
+    variants: &[&str],
+    actions: &[&str],
+    helper_functions: &[&str],
+) -> ScaffoldResult<()> {
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.to_path_buf()))?,
+        |file_path, mut file| {
+            if file_path != Path::new("lib.rs") {
+                return Ok(file);
+            }
+
+            file.items.retain(|item| match item {
+                syn::Item::Fn(item_fn) => !helper_functions
+                    .iter()
+                    .any(|helper| item_fn.sig.ident == helper),
+                _ => true,
+            });
+
+            for item in &mut file.items {
+                if let syn::Item::Enum(item_enum) = item {
+                    if item_enum.ident == "Signal" {
+                        item_enum.variants = item_enum
+                            .variants
+                            .clone()
+                            .into_iter()
+                            .filter(|v| !variants.iter().any(|variant| v.ident == variant))
+                            .collect();
+                    }
+                }
+
+                if let syn::Item::Fn(item_fn) = item {
+                    if item_fn.sig.ident == "signal_action" {
+                        if let Some(expr_match) =
+                            find_ending_match_expr_in_block(&mut item_fn.block)
+                        {
+                            expr_match.arms.retain(|arm| match &arm.pat {
+                                syn::Pat::TupleStruct(tuple_struct_pat) => {
+                                    !tuple_struct_pat.path.segments.last().is_some_and(|s| {
+                                        actions.iter().any(|action| s.ident == action)
+                                    })
+                                }
+                                _ => true,
+                            });
+
+                            // Only the wildcard arm is left, so go back to the initial body of the function
+                            if expr_match
+                                .arms
+                                .iter()
+                                .all(|arm| matches!(arm.pat, syn::Pat::Wild(_)))
+                            {
+                                *item_fn.block = syn::parse_quote! {{ Ok(()) }};
+                            }
+                        }
+                    }
+                }
+            }
+
+            Ok(file)
+        },
+    )
+}
@@ -1,7 +1,11 @@
+use std::{ffi::OsString, path::Path};
+
 use dialoguer::{theme::ColorfulTheme, MultiSelect, Select};
 use holochain_types::prelude::{DnaManifest, ZomeManifest};
 
-use crate::error::ScaffoldResult;
+use crate::error::{ScaffoldError, ScaffoldResult};
+use crate::file_tree::{file_exists, map_rust_files, remove_file, FileTree};
+use crate::utils::check_interactive;
 
 /// Prompts a MultiSelect dialog to select one or multiple integrity zomes
 ///
@@ -9,6 +13,7 @@ use crate::error::ScaffoldResult;
 pub fn select_integrity_zomes(
     dna_manifest: &DnaManifest,
     prompt: Option<&str>,
+    no_interactive: bool,
 ) -> ScaffoldResult<Vec<String>> {
     let integrity_zomes: Vec<String> = match dna_manifest {
         DnaManifest::V1(v1) => v1
@@ -26,6 +31,10 @@ pub fn select_integrity_zomes(
 
     let prompt = prompt.unwrap_or("Select integrity zome (SPACE to select/unselect):");
 
+    check_interactive(
+        "the integrity zome dependencies of the coordinator zome",
+        no_interactive,
+    )?;
     let selected_options = MultiSelect::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .items(&integrity_zomes)
@@ -59,13 +68,51 @@ pub fn get_coordinator_zomes_for_integrity(
     }
 }
 
+/// Deletes the file of the given module from the crate, removing its `pub mod` and `pub use` items from the lib.rs file
+pub fn remove_module_from_crate(
+    file_tree: &mut FileTree,
+    crate_src_path: &Path,
+    module_name: &str,
+) -> ScaffoldResult<()> {
+    let module_path = crate_src_path.join(format!("{module_name}.rs"));
+    if file_exists(file_tree, &module_path) {
+        remove_file(file_tree, &module_path)?;
+    }
+
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.to_path_buf()))?,
+        |file_path, mut file| {
+            if file_path == Path::new("lib.rs") {
+                file.items.retain(|item| match item {
+                    syn::Item::Mod(item_mod) => item_mod.ident != module_name,
+                    syn::Item::Use(item_use) => !matches!(
+                        &item_use.tree,
+                        syn::UseTree::Path(use_path)
+                            if use_path.ident == module_name
+                                && matches!(*use_path.tree, syn::UseTree::Glob(_))
+                    ),
+                    _ => true,
+                });
+            }
+            Ok(file)
+        },
+    )
+}
+
 /// Select whether to scaffold zome pair or integrity / coordintor zomes
 ///
 /// # Example
 /// ```rs,no_run
 /// let (scaffold_integrity, scaffold_coordintor) = select_scaffold_zome_options().unwrap();
 /// ```
-pub fn select_scaffold_zome_options() -> ScaffoldResult<(bool, bool)> {
+pub fn select_scaffold_zome_options(no_interactive: bool) -> ScaffoldResult<(bool, bool)> {
+    check_interactive(
+        "the `--integrity` or `--coordinator` option",
+        no_interactive,
+    )?;
     let option = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("What do you want to scaffold?")
         .default(0)
@@ -22,6 +22,7 @@ pub mod entry_type;
 pub mod example;
 pub mod integrity;
 pub mod link_type;
+pub mod signal;
 pub mod web_app;
 
 static EACH_TEMPLATE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
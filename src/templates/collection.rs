@@ -1,5 +1,6 @@
 use std::{ffi::OsString, path::PathBuf};
 
+use convert_case::{Case, Casing};
 use holochain_types::prelude::ZomeManifest;
 use serde::Serialize;
 
@@ -7,7 +8,7 @@ use crate::{
     error::ScaffoldResult,
     file_tree::{file_content, FileTree},
     scaffold::{
-        collection::CollectionType,
+        collection::{CollectionFilter, CollectionType},
         entry_type::definitions::{EntryTypeReference, Referenceable},
     },
 };
@@ -24,7 +25,13 @@ pub struct ScaffoldCollectionData {
     pub collection_type: CollectionType,
     pub collection_name: String,
     pub referenceable: Referenceable,
+    /// All the collected entry types, of which `referenceable` is the first
+    pub referenceables: Vec<Referenceable>,
+    /// Name of the type returned by the getter for each link, if the collection has more than one entry type
+    pub item_type: Option<String>,
     pub deletable: bool,
+    pub paginated: bool,
+    pub filter: Option<CollectionFilter>,
 }
 
 // TODO: group some params into a new-type or prefer builder pattern
@@ -37,8 +44,10 @@ pub fn scaffold_collection_templates(
     coordinator_zome_manifest: &ZomeManifest,
     collection_type: &CollectionType,
     collection_name: &str,
-    entry_type_reference: &EntryTypeReference,
+    entry_type_references: &[EntryTypeReference],
     deletable: bool,
+    paginated: bool,
+    filter: Option<CollectionFilter>,
     no_ui: bool,
     no_spec: bool,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
@@ -47,9 +56,18 @@ pub fn scaffold_collection_templates(
         dna_role_name: dna_role_name.to_owned(),
         coordinator_zome_manifest: coordinator_zome_manifest.clone(),
         collection_name: collection_name.to_owned(),
-        collection_type: *collection_type,
-        referenceable: Referenceable::EntryType(entry_type_reference.clone()),
+        collection_type: collection_type.clone(),
+        referenceable: Referenceable::EntryType(entry_type_references[0].clone()),
+        referenceables: entry_type_references
+            .iter()
+            .cloned()
+            .map(Referenceable::EntryType)
+            .collect(),
+        item_type: (entry_type_references.len() > 1)
+            .then(|| format!("{}Item", collection_name.to_case(Case::Pascal))),
         deletable,
+        paginated,
+        filter,
     };
 
     let h = build_handlebars(template_file_tree)?;
@@ -1,6 +1,7 @@
 use std::{ffi::OsString, path::PathBuf};
 
 use holochain_types::prelude::ZomeManifest;
+use itertools::Itertools;
 use serde::Serialize;
 
 use crate::{
@@ -88,3 +89,98 @@ pub fn scaffold_entry_type_templates(
         next_instructions,
     })
 }
+
+#[derive(Serialize, Debug)]
+pub struct ScaffoldEntryTypeFieldData<'a> {
+    pub app_name: String,
+    pub dna_role_name: String,
+    pub coordinator_zome_manifest: ZomeManifest,
+    /// The entry type being extended, containing only the fields being added to it
+    pub entry_type: EntryDefinition,
+    /// Typescript definitions for the types of the new fields, eg. the union type of an enum
+    pub new_fields_ts_type_definitions: Vec<String>,
+    /// Declarations of the new fields inside the typescript interface of the entry type
+    pub new_fields_ts_fields: &'a str,
+    pub crud: Crud,
+    pub link_from_original_to_each_update: bool,
+}
+
+// TODO: group some params into a new-type or prefer builder pattern
+#[allow(unknown_lints, clippy::too_many_arguments, clippy::manual_inspect)]
+pub fn scaffold_entry_type_field_templates(
+    mut app_file_tree: FileTree,
+    template_file_tree: &FileTree,
+    app_name: &str,
+    dna_role_name: &str,
+    coordinator_zome: &ZomeManifest,
+    entry_type: &EntryDefinition,
+    crud: &Crud,
+    link_from_original_to_each_update: bool,
+    no_ui: bool,
+    no_spec: bool,
+) -> ScaffoldResult<ScaffoldedTemplate> {
+    let new_fields_ts_type_definitions = entry_type
+        .fields
+        .iter()
+        .filter_map(|f| f.field_type.ts_type_definition())
+        .unique()
+        .collect();
+    let new_fields_ts_fields = entry_type
+        .fields
+        .iter()
+        .map(|f| f.ts_field_codegen())
+        .join("\n");
+
+    let data = ScaffoldEntryTypeFieldData {
+        app_name: app_name.to_owned(),
+        dna_role_name: dna_role_name.to_owned(),
+        coordinator_zome_manifest: coordinator_zome.clone(),
+        entry_type: entry_type.clone(),
+        new_fields_ts_type_definitions,
+        new_fields_ts_fields: &new_fields_ts_fields,
+        crud: *crud,
+        link_from_original_to_each_update,
+    };
+    let h = build_handlebars(template_file_tree)?;
+
+    let entry_type_field_path = PathBuf::from("entry-type-field");
+    let v: Vec<OsString> = entry_type_field_path
+        .iter()
+        .map(|s| s.to_os_string())
+        .collect();
+
+    if let Some(entry_type_field_template) = template_file_tree.path(&mut v.iter()) {
+        let mut entry_type_field_template = entry_type_field_template.clone();
+        if no_ui {
+            entry_type_field_template.dir_content_mut().map(|v| {
+                v.retain(|k, _| k != "ui");
+                v
+            });
+        }
+        if no_spec {
+            entry_type_field_template.dir_content_mut().map(|v| {
+                v.retain(|k, _| k != "tests");
+                v
+            });
+        }
+        app_file_tree = render_template_file_tree_and_merge_with_existing(
+            app_file_tree,
+            &h,
+            &entry_type_field_template,
+            &data,
+        )?;
+    }
+
+    let next_instructions = match file_content(
+        template_file_tree,
+        &PathBuf::from("entry-type-field.instructions.hbs"),
+    ) {
+        Ok(content) => Some(h.render_template(content.as_str(), &data)?),
+        Err(_) => None,
+    };
+
+    Ok(ScaffoldedTemplate {
+        file_tree: app_file_tree,
+        next_instructions,
+    })
+}
@@ -2,6 +2,8 @@ use handlebars::{
     Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderError,
     Renderable, StringOutput,
 };
+use itertools::Itertools;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Number, Value};
 
@@ -49,6 +51,45 @@ pub fn get_scope_open_and_close_char_indexes(
     Ok((scope_opener_index, index))
 }
 
+/// Returns the index of the first character of the lines containing the given text, and the index where those lines end
+///
+/// Any whitespace in the given text matches any whitespace in the lines, so that it doesn't depend on their indentation
+pub fn get_lines_start_and_end_indexes(
+    text: &str,
+    line_content: &str,
+) -> Result<(usize, usize), RenderError> {
+    let line_content_regex = Regex::new(
+        &line_content
+            .split_whitespace()
+            .map(regex::escape)
+            .join(r"\s+"),
+    )
+    .map_err(|e| RenderError::new(e.to_string()))?;
+    let line_content_match = line_content_regex
+        .find(text)
+        .filter(|m| !m.is_empty())
+        .ok_or(RenderError::new(
+            "Given line content not found in the given parameter",
+        ))?;
+    let index = line_content_match.start();
+
+    let lines_start = text[..index].rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+    if lines_start == 0 {
+        return Err(RenderError::new(
+            "match_line can't match the first line of the given parameter",
+        ));
+    }
+
+    let content_end = line_content_match.end();
+    let lines_end = text[content_end..]
+        .find('\n')
+        .map(|i| i + content_end)
+        .unwrap_or(text.len());
+
+    Ok((lines_start, lines_end))
+}
+
 #[derive(Clone, Copy)]
 pub struct Merge;
 
@@ -101,6 +142,7 @@ impl HelperDef for Merge {
                 matched_scopes.sort_by(|a, b| b.__starting_index.cmp(&a.__starting_index));
 
                 let mut full_merge_content = String::from("");
+                let mut first_match_is_line = false;
                 for matched_scope in matched_scopes {
                     let mut full_scope_content = String::from("");
                     let start_index = matched_scope.__starting_index;
@@ -109,9 +151,15 @@ impl HelperDef for Merge {
                         &s[(start_index + matched_scope.__old_scope_length)..previous_index],
                     );
                     previous_index = start_index + 1;
+                    first_match_is_line = matched_scope.__matched_line;
                     full_merge_content.insert_str(0, full_scope_content.as_str());
                 }
-                full_merge_content.insert_str(0, &s[0..=previous_index]);
+                // Matched lines start right after their starting index
+                if first_match_is_line {
+                    full_merge_content.insert_str(0, &s[0..previous_index]);
+                } else {
+                    full_merge_content.insert_str(0, &s[0..=previous_index]);
+                }
                 out.write(&full_merge_content)?;
 
                 data.remove(MATCHED_SCOPES);
@@ -130,6 +178,8 @@ struct MatchedScopedData {
     __starting_index: usize,
     __new_scope_content: String,
     __old_scope_length: usize,
+    #[serde(default)]
+    __matched_line: bool,
 }
 
 const MATCHED_SCOPES: &str = "__matched_scopes";
@@ -137,6 +187,7 @@ const SCOPE_CONTENT: &str = "__scope_content";
 const STARTING_INDEX: &str = "__starting_index";
 const NEW_SCOPE_CONTENT: &str = "__new_scope_content";
 const OLD_SCOPE_LENGTH: &str = "__old_scope_length";
+const MATCHED_LINE: &str = "__matched_line";
 
 #[derive(Clone, Copy)]
 pub struct MatchScope;
@@ -225,9 +276,100 @@ impl HelperDef for MatchScope {
     }
 }
 
+#[derive(Clone, Copy)]
+pub struct MatchLine;
+
+impl HelperDef for MatchLine {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        _out: &mut dyn Output,
+    ) -> HelperResult {
+        let t = h.template().ok_or(RenderError::new(
+            "match_line helper cannot have empty content",
+        ))?;
+
+        let mut data = rc
+            .context()
+            .unwrap()
+            .data()
+            .as_object()
+            .ok_or(RenderError::new("Context must be an object"))?
+            .clone();
+
+        let Some(Value::String(scope_content)) = data.get(SCOPE_CONTENT) else {
+            return Err(RenderError::new(
+                "match_line needs to be placed inside a merge helper",
+            ));
+        };
+
+        let line_content = h
+            .param(0)
+            .ok_or(RenderError::new("match_line helper needs 1 parameter"))?
+            .value()
+            .as_str()
+            .ok_or(RenderError::new(
+                "match_line's first parameter must be a string",
+            ))?
+            .to_string();
+
+        let (lines_start, lines_end) =
+            get_lines_start_and_end_indexes(scope_content, &line_content)?;
+
+        let previous_line_content = scope_content[lines_start..lines_end].to_string();
+
+        data.insert(
+            String::from("previous_line_content"),
+            Value::String(previous_line_content),
+        );
+
+        let mut matched_scopes = match data.get(MATCHED_SCOPES) {
+            Some(Value::Array(array)) => array.clone(),
+            _ => vec![],
+        };
+
+        rc.set_context(Context::wraps(data.clone())?);
+
+        let mut inner_output = StringOutput::new();
+        t.render(r, ctx, rc, &mut inner_output)?;
+
+        let out_string = inner_output.into_string().unwrap();
+
+        // The matched lines are replaced from the line break that precedes them
+        let starting_index = lines_start - 1;
+
+        let mut map = Map::new();
+        map.insert(
+            String::from(STARTING_INDEX),
+            Value::Number(Number::from(starting_index)),
+        );
+        map.insert(
+            String::from(NEW_SCOPE_CONTENT),
+            Value::String(out_string.trim_start_matches('\n').trim_end().to_string()),
+        );
+        map.insert(
+            String::from(OLD_SCOPE_LENGTH),
+            Value::Number(Number::from(lines_end - starting_index)),
+        );
+        map.insert(String::from(MATCHED_LINE), Value::Bool(true));
+
+        matched_scopes.push(Value::Object(map));
+
+        data.insert(MATCHED_SCOPES.to_string(), Value::Array(matched_scopes));
+
+        rc.set_context(Context::wraps(data)?);
+
+        Ok(())
+    }
+}
+
 pub fn register_merge(mut h: Handlebars) -> Handlebars {
     h.register_helper("merge", Box::new(Merge));
     h.register_helper("match_scope", Box::new(MatchScope));
+    h.register_helper("match_line", Box::new(MatchLine));
 
     h
 }
@@ -378,4 +520,89 @@ export class B {
 "#,
         );
     }
+
+    #[test]
+    fn test_merge_match_line() {
+        let mut h = Handlebars::new();
+        h.register_escape_fn(handlebars::no_escape);
+
+        let h = register_merge(h);
+
+        let code = r#"class A {
+    existingFn() {}
+}
+<div>
+  <button>Save</button>
+</div>
+"#;
+        let value = json!({"previous_file_content": code});
+        let context = Context::from(value);
+        let template = r#"{{#merge previous_file_content}}
+    {{#match_scope "class A {"}}
+    {{previous_scope_content}}
+    newFn() {}
+    {{/match_scope}}
+    {{#match_line "<button>Save"}}
+  <input />
+{{previous_line_content}}
+    {{/match_line}}
+{{/merge}}"#;
+
+        assert_eq!(
+            h.render_template_with_context(template, &context).unwrap(),
+            r#"class A {
+    existingFn() {}
+    newFn() {}
+}
+<div>
+  <input />
+  <button>Save</button>
+</div>
+"#,
+        );
+
+        let template = r#"{{#merge previous_file_content}}
+    {{#match_line "<div>"}}
+{{previous_line_content}}
+  <span />
+    {{/match_line}}
+{{/merge}}"#;
+
+        assert_eq!(
+            h.render_template_with_context(template, &context).unwrap(),
+            r#"class A {
+    existingFn() {}
+}
+<div>
+  <span />
+  <button>Save</button>
+</div>
+"#,
+        );
+    }
+
+    #[test]
+    fn test_match_line_ignores_indentation() {
+        let mut h = Handlebars::new();
+        h.register_escape_fn(handlebars::no_escape);
+
+        let h = register_merge(h);
+
+        let template = r#"{{#merge previous_file_content}}
+    {{#match_line "<div>\n<button"}}
+  <span />
+{{previous_line_content}}
+    {{/match_line}}
+{{/merge}}"#;
+
+        for indentation in ["", "  ", "\t\t"] {
+            let code = format!("<section>\n{indentation}<div>\n{indentation}  <button>Save</button>\n{indentation}</div>\n</section>\n");
+            let context = Context::from(json!({ "previous_file_content": code }));
+
+            assert_eq!(
+                h.render_template_with_context(template, &context).unwrap(),
+                format!("<section>\n  <span />\n{indentation}<div>\n{indentation}  <button>Save</button>\n{indentation}</div>\n</section>\n"),
+            );
+        }
+    }
 }
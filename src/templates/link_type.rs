@@ -5,7 +5,7 @@ use serde::Serialize;
 
 use crate::{
     error::ScaffoldResult,
-    file_tree::{file_content, FileTree},
+    file_tree::{dir_exists, file_content, FileTree},
     scaffold::entry_type::definitions::{FieldType, Referenceable},
 };
 
@@ -23,6 +23,10 @@ pub struct ScaffoldLinkTypeData<'a> {
     pub to_referenceable: Option<Referenceable>,
     pub delete: bool,
     pub bidirectional: Option<&'a str>,
+    /// Struct serialized into the tag of the links
+    pub tag_type: Option<&'a FieldType>,
+    /// Typescript interface of the tag type
+    pub tag_type_ts_definition: Option<String>,
 }
 
 // TODO: group some params into a new-type or prefer builder pattern
@@ -38,6 +42,7 @@ pub fn scaffold_link_type_templates(
     to_referenceable: &Option<Referenceable>,
     delete: bool,
     bidirectional: Option<&str>,
+    tag_type: Option<&FieldType>,
     no_ui: bool,
     no_spec: bool,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
@@ -50,6 +55,8 @@ pub fn scaffold_link_type_templates(
         to_referenceable: to_referenceable.clone(),
         delete,
         bidirectional,
+        tag_type,
+        tag_type_ts_definition: tag_type.and_then(|t| t.ts_type_definition()),
     };
 
     // This is a measure to prevent UI from getting scaffolded for link-types where the base
@@ -59,7 +66,9 @@ pub fn scaffold_link_type_templates(
         || to_referenceable
             .as_ref()
             .map(|r| r.field_type() == FieldType::ExternalHash)
-            .unwrap_or_default();
+            .unwrap_or_default()
+        // The tag type is added to the types of the UI of the coordinator zome template
+        || !dir_exists(template_file_tree, &PathBuf::from("coordinator-zome/ui"));
 
     let h = build_handlebars(template_file_tree)?;
 
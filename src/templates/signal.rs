@@ -0,0 +1,83 @@
+use std::{ffi::OsString, path::PathBuf};
+
+use holochain_types::prelude::ZomeManifest;
+use serde::Serialize;
+
+use crate::{
+    error::ScaffoldResult,
+    file_tree::{dir_exists, file_content, FileTree},
+    scaffold::entry_type::definitions::FieldDefinition,
+};
+
+use super::{
+    build_handlebars, render_template_file_tree_and_merge_with_existing, ScaffoldedTemplate,
+};
+
+#[derive(Serialize)]
+pub struct ScaffoldSignalData<'a> {
+    pub app_name: &'a str,
+    pub dna_role_name: &'a str,
+    pub coordinator_zome_manifest: ZomeManifest,
+    pub signal_name: &'a str,
+    pub fields: &'a [FieldDefinition],
+    /// Member of the typescript union of the signals of the zome, without the separating " | "
+    pub signal_ts_variant: &'a str,
+}
+
+#[allow(unknown_lints, clippy::too_many_arguments, clippy::manual_inspect)]
+pub fn scaffold_signal_templates(
+    mut app_file_tree: FileTree,
+    template_file_tree: &FileTree,
+    app_name: &str,
+    dna_role_name: &str,
+    coordinator_zome_manifest: &ZomeManifest,
+    signal_name: &str,
+    fields: &[FieldDefinition],
+    signal_ts_variant: &str,
+    no_ui: bool,
+) -> ScaffoldResult<ScaffoldedTemplate> {
+    let data = ScaffoldSignalData {
+        app_name,
+        dna_role_name,
+        coordinator_zome_manifest: coordinator_zome_manifest.clone(),
+        signal_name,
+        fields,
+        signal_ts_variant,
+    };
+
+    let h = build_handlebars(template_file_tree)?;
+
+    let signal_path = PathBuf::from("signal");
+    let v: Vec<OsString> = signal_path.iter().map(|s| s.to_os_string()).collect();
+
+    if let Some(signal_template) = template_file_tree.path(&mut v.iter()) {
+        let mut signal_template = signal_template.clone();
+        // The signals of the zome are typed in the UI of its coordinator zome template
+        let has_zome_ui = dir_exists(template_file_tree, &PathBuf::from("coordinator-zome/ui"));
+        if no_ui || !has_zome_ui {
+            signal_template.dir_content_mut().map(|v| {
+                v.retain(|k, _| k != "ui");
+                v
+            });
+        }
+        app_file_tree = render_template_file_tree_and_merge_with_existing(
+            app_file_tree,
+            &h,
+            &signal_template,
+            &data,
+        )?;
+    }
+
+    let next_instructions = match file_content(
+        template_file_tree,
+        &PathBuf::from("signal.instructions.hbs"),
+    ) {
+        Ok(content) => Some(h.render_template(content.as_str(), &data)?),
+        Err(_) => None,
+    };
+
+    Ok(ScaffoldedTemplate {
+        file_tree: app_file_tree,
+        next_instructions,
+    })
+}
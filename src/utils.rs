@@ -13,7 +13,24 @@ use dprint_plugin_typescript::configuration::ConfigurationBuilder;
 use crate::error::{ScaffoldError, ScaffoldResult};
 use crate::file_tree::{dir_content, FileTree};
 
-pub fn choose_directory_path(prompt: &str, app_file_tree: &FileTree) -> ScaffoldResult<PathBuf> {
+/// Raises an error naming the argument that would otherwise be prompted for, if prompts are disabled
+/// with the `--no-interactive` flag or by running in CI
+pub fn check_interactive(missing_argument: &str, no_interactive: bool) -> ScaffoldResult<()> {
+    if no_interactive {
+        Err(ScaffoldError::MissingArgumentInNonInteractiveMode(
+            missing_argument.to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn choose_directory_path(
+    prompt: &str,
+    app_file_tree: &FileTree,
+    no_interactive: bool,
+) -> ScaffoldResult<PathBuf> {
+    check_interactive(&format!("answer to \"{prompt}\""), no_interactive)?;
     let mut chosen_directory: Option<PathBuf> = None;
     let mut current_path = PathBuf::new();
 
@@ -75,7 +92,12 @@ fn get_folder_names(folder: &BTreeMap<OsString, FileTree>) -> Vec<String> {
 
 #[inline]
 /// "yes" or "no" input dialog, with the option to specify a recommended answer (yes = true, no = false)
-pub fn input_yes_or_no(prompt: &str, recommended: Option<bool>) -> ScaffoldResult<bool> {
+pub fn input_yes_or_no(
+    prompt: &str,
+    recommended: Option<bool>,
+    no_interactive: bool,
+) -> ScaffoldResult<bool> {
+    check_interactive(&format!("answer to \"{prompt}\""), no_interactive)?;
     let yes_recommended = if recommended == Some(true) {
         " (recommended)"
     } else {
@@ -102,10 +124,15 @@ pub fn input_yes_or_no(prompt: &str, recommended: Option<bool>) -> ScaffoldResul
 }
 
 #[inline]
-pub fn input_with_custom_validation<V>(prompt: &str, validator: V) -> ScaffoldResult<String>
+pub fn input_with_custom_validation<V>(
+    prompt: &str,
+    no_interactive: bool,
+    validator: V,
+) -> ScaffoldResult<String>
 where
     V: Fn(String) -> Result<(), String>,
 {
+    check_interactive(&format!("answer to \"{prompt}\""), no_interactive)?;
     let mut input: String = Input::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .interact_text()?;
@@ -121,7 +148,8 @@ where
 }
 
 #[inline]
-pub fn input_with_case(prompt: &str, case: Case) -> ScaffoldResult<String> {
+pub fn input_with_case(prompt: &str, case: Case, no_interactive: bool) -> ScaffoldResult<String> {
+    check_interactive(&format!("answer to \"{prompt}\""), no_interactive)?;
     let input: String = Input::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .validate_with(|input: &String| -> Result<(), String> {
@@ -137,7 +165,9 @@ pub fn input_with_case_and_initial_text(
     prompt: &str,
     case: Case,
     initial_text: &str,
+    no_interactive: bool,
 ) -> ScaffoldResult<String> {
+    check_interactive(&format!("answer to \"{prompt}\""), no_interactive)?;
     let input: String = Input::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .with_initial_text(initial_text)
@@ -150,7 +180,8 @@ pub fn input_with_case_and_initial_text(
 }
 
 #[inline]
-pub fn input_no_whitespace(prompt: &str) -> ScaffoldResult<String> {
+pub fn input_no_whitespace(prompt: &str, no_interactive: bool) -> ScaffoldResult<String> {
+    check_interactive(&format!("answer to \"{prompt}\""), no_interactive)?;
     let input = Input::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .validate_with(|input: &String| -> Result<(), String> {
@@ -381,8 +412,12 @@ fn format_nested<'a>(
 }
 
 /// Runs `cargo fmt` if it's available in the current Rust toolchain otherwise will exit
-/// gracefully
-pub fn run_cargo_fmt_if_available() -> ScaffoldResult<()> {
+/// gracefully. Does nothing on dry runs, which don't write the files it would format
+pub fn run_cargo_fmt_if_available(dry_run: bool) -> ScaffoldResult<()> {
+    if dry_run {
+        return Ok(());
+    }
+
     let cargo_fmt_available = Command::new("cargo").arg("fmt").arg("--version").output();
 
     match cargo_fmt_available {